@@ -4,6 +4,10 @@
 // Two textures are needed for the game of life as each pixel of step N depends on the state of its
 // neighbors at step N-1.
 
+// The share of cells that start alive is a pipeline-overridable constant, set from the `constants`
+// of the `ComputePipelineDescriptor`.
+override initial_alive_probability: f32 = 0.1;
+
 @group(0) @binding(0) var input: texture_storage_2d<r32float, read>;
 
 @group(0) @binding(1) var output: texture_storage_2d<r32float, write>;
@@ -28,7 +32,7 @@ fn init(@builtin(global_invocation_id) invocation_id: vec3<u32>, @builtin(num_wo
     let location = vec2<i32>(i32(invocation_id.x), i32(invocation_id.y));
 
     let randomNumber = randomFloat(invocation_id.y << 16u | invocation_id.x);
-    let alive = randomNumber > 0.9;
+    let alive = randomNumber < initial_alive_probability;
     let color = vec4<f32>(f32(alive));
 
     textureStore(output, location, color);
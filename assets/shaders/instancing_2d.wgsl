@@ -0,0 +1,27 @@
+#import bevy_sprite::mesh2d_view_bindings::view
+
+struct Vertex {
+    @location(0) corner: vec2<f32>,
+
+    @location(1) i_position_size: vec4<f32>,
+    @location(2) i_color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let position = vertex.corner * vertex.i_position_size.zw + vertex.i_position_size.xy;
+    var out: VertexOutput;
+    out.clip_position = view.clip_from_world * vec4<f32>(position, 0.0, 1.0);
+    out.color = vertex.i_color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -98,6 +98,9 @@ impl SpecializedRenderPipeline for BlitPipeline {
                 ..Default::default()
             },
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
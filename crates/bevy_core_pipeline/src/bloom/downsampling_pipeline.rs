@@ -126,6 +126,9 @@ impl SpecializedRenderPipeline for BloomDownsamplingPipeline {
             depth_stencil: None,
             multisample: MultisampleState::default(),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -124,6 +124,9 @@ impl SpecializedRenderPipeline for BloomUpsamplingPipeline {
             depth_stencil: None,
             multisample: MultisampleState::default(),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
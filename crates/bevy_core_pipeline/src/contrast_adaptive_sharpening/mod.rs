@@ -230,6 +230,9 @@ impl SpecializedRenderPipeline for CASPipeline {
             depth_stencil: None,
             multisample: MultisampleState::default(),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
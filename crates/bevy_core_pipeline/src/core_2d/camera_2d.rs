@@ -18,6 +18,37 @@ use bevy_transform::prelude::{GlobalTransform, Transform};
 #[reflect(Component)]
 pub struct Camera2d;
 
+/// Marker component that makes a [`Camera2d`] render its main passes into an HDR
+/// intermediate texture ([`ViewTarget::TEXTURE_FORMAT_HDR`]) before tonemapping.
+///
+/// This is what allows bloom and [`Tonemapping`] to work correctly on 2D cameras.
+/// While this component is present, [`Camera::hdr`] is kept enabled by [`sync_camera_2d_hdr`].
+/// Removing it switches the camera back to an LDR target.
+///
+/// [`ViewTarget::TEXTURE_FORMAT_HDR`]: bevy_render::view::ViewTarget::TEXTURE_FORMAT_HDR
+#[derive(Component, Default, Reflect, Clone, Copy)]
+#[reflect(Component, Default)]
+pub struct Hdr;
+
+/// Enables [`Camera::hdr`] on every [`Camera2d`] with an [`Hdr`] component,
+/// and disables it again when the component is removed.
+pub fn sync_camera_2d_hdr(
+    mut hdr_cameras: Query<&mut Camera, (With<Camera2d>, With<Hdr>)>,
+    mut removed_hdr: RemovedComponents<Hdr>,
+    mut cameras: Query<&mut Camera, (With<Camera2d>, Without<Hdr>)>,
+) {
+    for mut camera in &mut hdr_cameras {
+        if !camera.hdr {
+            camera.hdr = true;
+        }
+    }
+    for entity in removed_hdr.read() {
+        if let Ok(mut camera) = cameras.get_mut(entity) {
+            camera.hdr = false;
+        }
+    }
+}
+
 #[derive(Bundle, Clone)]
 pub struct Camera2dBundle {
     pub camera: Camera,
@@ -93,3 +124,46 @@ impl Camera2dBundle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_render::{render_resource::TextureFormat, view::ViewTarget};
+
+    fn main_texture_format(world: &World, camera: Entity) -> TextureFormat {
+        ViewTarget::main_texture_format_for(world.get::<Camera>(camera).unwrap().hdr)
+    }
+
+    #[test]
+    fn hdr_camera_2d_uses_float_view_target() {
+        let mut world = World::new();
+        let hdr_camera = world.spawn((Camera2dBundle::default(), Hdr)).id();
+        let ldr_camera = world.spawn(Camera2dBundle::default()).id();
+
+        world.run_system_once(sync_camera_2d_hdr);
+        assert_eq!(
+            main_texture_format(&world, hdr_camera),
+            ViewTarget::TEXTURE_FORMAT_HDR
+        );
+        assert_ne!(
+            main_texture_format(&world, ldr_camera),
+            ViewTarget::TEXTURE_FORMAT_HDR
+        );
+
+        // HDR stays enabled while the marker is present.
+        world.get_mut::<Camera>(hdr_camera).unwrap().hdr = false;
+        world.run_system_once(sync_camera_2d_hdr);
+        assert_eq!(
+            main_texture_format(&world, hdr_camera),
+            ViewTarget::TEXTURE_FORMAT_HDR
+        );
+
+        world.entity_mut(hdr_camera).remove::<Hdr>();
+        world.run_system_once(sync_camera_2d_hdr);
+        assert_ne!(
+            main_texture_format(&world, hdr_camera),
+            ViewTarget::TEXTURE_FORMAT_HDR
+        );
+    }
+}
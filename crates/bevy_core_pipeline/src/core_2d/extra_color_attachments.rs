@@ -0,0 +1,299 @@
+use bevy_asset::Handle;
+use bevy_color::LinearRgba;
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+use bevy_render::{
+    camera::Camera,
+    extract_component::ExtractComponent,
+    render_asset::RenderAssets,
+    render_resource::{
+        ColorTargetState, ColorWrites, LoadOp, Operations, RenderPassColorAttachment, StoreOp,
+        TextureFormat,
+    },
+    texture::{GpuImage, Image},
+};
+use bevy_utils::warn_once;
+
+/// An additional render target written by the 2D main passes, next to the view's main texture.
+#[derive(Clone, Debug, Default, Reflect)]
+pub struct ExtraColorAttachment {
+    /// The image to render into. It must have [`TextureUsages::RENDER_ATTACHMENT`] and the same
+    /// size and sample count as the camera's main texture. With [`Msaa`] enabled, that means the
+    /// image has to be multisampled itself, as extra attachments aren't resolved.
+    ///
+    /// [`Msaa`]: bevy_render::view::Msaa
+    ///
+    /// [`TextureUsages::RENDER_ATTACHMENT`]: bevy_render::render_resource::TextureUsages::RENDER_ATTACHMENT
+    pub image: Handle<Image>,
+    /// The color the attachment is cleared to at the start of the main pass.
+    /// If `None`, the previous contents are loaded instead.
+    pub clear_color: Option<LinearRgba>,
+}
+
+/// Additional color attachments bound by the 2D main passes of a [`Camera2d`](super::Camera2d),
+/// after the view's main color attachment at index `0`.
+///
+/// Each entry `i` is bound at `@location(i + 1)` of the fragment shader output, so every pipeline
+/// drawn by this camera has to declare matching targets, which [`Self::color_target_states`]
+/// builds. The built-in sprite and mesh pipelines only write a single target, which means this is
+/// meant to be used with cameras whose [`Transparent2d`](super::Transparent2d) items all come from
+/// custom pipelines.
+///
+/// The extra attachments are only bound once all of their images have been prepared on the GPU
+/// and their sample count matches the main texture, see [`Self::formats`].
+#[derive(Component, Clone, Debug, Default, Reflect, ExtractComponent)]
+#[extract_component_filter(With<Camera>)]
+#[reflect(Component, Default)]
+pub struct ExtraColorAttachments(pub Vec<ExtraColorAttachment>);
+
+impl ExtraColorAttachments {
+    /// Returns the texture formats of the extra attachments, in binding order.
+    ///
+    /// `sample_count` is the sample count of the view's main texture, i.e. the
+    /// [`Msaa`](bevy_render::view::Msaa) sample count. Returns `None` if any of the images isn't
+    /// available in `images` yet or has a different sample count, in which case the main passes
+    /// only bind the main color attachment. Pipelines should use this to decide whether to
+    /// specialize for the extra targets so they stay in sync with the pass.
+    pub fn formats(
+        &self,
+        images: &RenderAssets<GpuImage>,
+        sample_count: u32,
+    ) -> Option<Vec<TextureFormat>> {
+        Some(
+            self.gpu_images(images, sample_count)?
+                .into_iter()
+                .map(|gpu_image| gpu_image.texture_format)
+                .collect(),
+        )
+    }
+
+    /// Builds the color attachments to append after the main color attachment.
+    ///
+    /// Returns `None` under the same conditions as [`Self::formats`].
+    pub fn color_attachments<'a>(
+        &self,
+        images: &'a RenderAssets<GpuImage>,
+        sample_count: u32,
+    ) -> Option<Vec<Option<RenderPassColorAttachment<'a>>>> {
+        let gpu_images = self.gpu_images(images, sample_count)?;
+        Some(
+            self.0
+                .iter()
+                .zip(gpu_images)
+                .map(|(attachment, gpu_image)| {
+                    Some(RenderPassColorAttachment {
+                        view: &gpu_image.texture_view,
+                        resolve_target: None,
+                        ops: Operations {
+                            load: match attachment.clear_color {
+                                Some(color) => LoadOp::Clear(color.into()),
+                                None => LoadOp::Load,
+                            },
+                            store: StoreOp::Store,
+                        },
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn gpu_images<'a>(
+        &self,
+        images: &'a RenderAssets<GpuImage>,
+        sample_count: u32,
+    ) -> Option<Vec<&'a GpuImage>> {
+        let gpu_images = self
+            .0
+            .iter()
+            .map(|attachment| images.get(&attachment.image))
+            .collect::<Option<Vec<_>>>()?;
+        gpu_images
+            .iter()
+            .all(|gpu_image| sample_count_matches(gpu_image.texture.sample_count(), sample_count))
+            .then_some(gpu_images)
+    }
+
+    /// Builds the color targets a pipeline needs after its main target to be drawn into a pass
+    /// with these extra attachments. The extra targets are written without blending.
+    pub fn color_target_states(formats: &[TextureFormat]) -> Vec<Option<ColorTargetState>> {
+        formats
+            .iter()
+            .map(|&format| {
+                Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Builds all color attachments of the 2D main passes: the main color attachment, followed by the
+/// extra attachments if they can be bound with a main texture of `sample_count` samples.
+pub(crate) fn main_pass_2d_color_attachments<'a>(
+    main_color_attachment: RenderPassColorAttachment<'a>,
+    extra: Option<&ExtraColorAttachments>,
+    images: Option<&'a RenderAssets<GpuImage>>,
+    sample_count: u32,
+) -> Vec<Option<RenderPassColorAttachment<'a>>> {
+    let mut color_attachments = vec![Some(main_color_attachment)];
+    if let (Some(extra), Some(images)) = (extra, images) {
+        color_attachments.extend(
+            extra
+                .color_attachments(images, sample_count)
+                .unwrap_or_default(),
+        );
+    }
+    color_attachments
+}
+
+fn sample_count_matches(image_sample_count: u32, sample_count: u32) -> bool {
+    if image_sample_count == sample_count {
+        return true;
+    }
+    warn_once!(
+        "Ignoring ExtraColorAttachments: an image has {image_sample_count} samples but the main \
+        texture has {sample_count}. Attachments of a render pass must all have the same sample count."
+    );
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::UVec2;
+    use bevy_render::{
+        render_resource::{
+            BlendState, Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{initialize_renderer, RenderDevice},
+        settings::WgpuSettings,
+    };
+
+    #[test]
+    fn formats_are_unavailable_until_images_are_prepared() {
+        let extra = ExtraColorAttachments(vec![ExtraColorAttachment {
+            image: Handle::default(),
+            clear_color: Some(LinearRgba::NONE),
+        }]);
+        let images = RenderAssets::<GpuImage>::default();
+
+        assert_eq!(extra.formats(&images, 1), None);
+        assert!(extra.color_attachments(&images, 1).is_none());
+        assert_eq!(
+            ExtraColorAttachments::default().formats(&images, 4),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn sample_counts_must_match_the_main_texture() {
+        assert!(sample_count_matches(1, 1));
+        assert!(sample_count_matches(4, 4));
+        // A single-sampled image next to the main texture of the default `Msaa::Sample4`.
+        assert!(!sample_count_matches(1, 4));
+        assert!(!sample_count_matches(4, 1));
+    }
+
+    #[test]
+    fn two_attachment_targets() {
+        let mut targets = vec![Some(ColorTargetState {
+            format: TextureFormat::Rgba8UnormSrgb,
+            blend: Some(BlendState::ALPHA_BLENDING),
+            write_mask: ColorWrites::ALL,
+        })];
+        targets.extend(ExtraColorAttachments::color_target_states(&[
+            TextureFormat::R32Uint,
+        ]));
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(
+            targets[0].as_ref().unwrap().format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        let id_target = targets[1].as_ref().unwrap();
+        assert_eq!(id_target.format, TextureFormat::R32Uint);
+        assert_eq!(id_target.blend, None);
+    }
+
+    fn gpu_image(render_device: &RenderDevice, format: TextureFormat) -> GpuImage {
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        GpuImage {
+            texture_view: texture.create_view(&Default::default()),
+            texture,
+            texture_format: format,
+            sampler: render_device.create_sampler(&Default::default()),
+            size: UVec2::splat(4),
+            mip_level_count: 1,
+            mip_level_views: Vec::new(),
+            format_views: Vec::new(),
+            format_mip_level_views: Vec::new(),
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn main_pass_binds_the_targets_pipelines_specialize_for() {
+        let (render_device, ..) = bevy_tasks::block_on(initialize_renderer(
+            &wgpu::Instance::default(),
+            &WgpuSettings::default(),
+            &wgpu::RequestAdapterOptions::default(),
+        ))
+        .expect("GPU tests require an adapter");
+
+        let handle = Handle::<Image>::weak_from_u128(0xe47a);
+        let mut images = RenderAssets::<GpuImage>::default();
+        images.insert(&handle, gpu_image(&render_device, TextureFormat::R32Uint));
+        let main_texture = gpu_image(&render_device, TextureFormat::Rgba8UnormSrgb);
+        let main_color_attachment = || RenderPassColorAttachment {
+            view: &main_texture.texture_view,
+            resolve_target: None,
+            ops: Operations::default(),
+        };
+        let extra = ExtraColorAttachments(vec![ExtraColorAttachment {
+            image: handle,
+            clear_color: Some(LinearRgba::BLACK),
+        }]);
+
+        let attachments =
+            main_pass_2d_color_attachments(main_color_attachment(), Some(&extra), Some(&images), 1);
+        assert_eq!(attachments.len(), 2);
+        let id_attachment = attachments[1].as_ref().unwrap();
+        assert!(std::ptr::eq(
+            id_attachment.view,
+            &*images.get(&extra.0[0].image).unwrap().texture_view
+        ));
+        assert!(matches!(id_attachment.ops.load, LoadOp::Clear(_)));
+
+        // What a pipeline drawn into this pass specializes its fragment targets with.
+        let formats = extra.formats(&images, 1).unwrap();
+        let mut targets = vec![Some(ColorTargetState {
+            format: main_texture.texture_format,
+            blend: Some(BlendState::ALPHA_BLENDING),
+            write_mask: ColorWrites::ALL,
+        })];
+        targets.extend(ExtraColorAttachments::color_target_states(&formats));
+        assert_eq!(targets.len(), attachments.len());
+        assert_eq!(targets[1].as_ref().unwrap().format, TextureFormat::R32Uint);
+
+        // With a multisampled main texture, neither the pass nor the pipelines use the image.
+        let attachments =
+            main_pass_2d_color_attachments(main_color_attachment(), Some(&extra), Some(&images), 4);
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(extra.formats(&images, 4), None);
+    }
+}
@@ -1,12 +1,17 @@
-use crate::core_2d::Transparent2d;
+use crate::core_2d::{
+    main_pass_2d_color_attachment, main_pass_2d_color_attachments, ExtraColorAttachments,
+    MsaaResolveTarget, Transparent2d,
+};
 use bevy_ecs::prelude::*;
 use bevy_render::{
     camera::ExtractedCamera,
     diagnostic::RecordDiagnostics,
+    render_asset::RenderAssets,
     render_graph::{NodeRunError, RenderGraphContext, ViewNode},
     render_phase::ViewSortedRenderPhases,
     render_resource::RenderPassDescriptor,
-    renderer::RenderContext,
+    renderer::{RenderBackend, RenderContext},
+    texture::GpuImage,
     view::ViewTarget,
 };
 #[cfg(feature = "trace")]
@@ -16,13 +21,21 @@ use bevy_utils::tracing::info_span;
 pub struct MainTransparentPass2dNode {}
 
 impl ViewNode for MainTransparentPass2dNode {
-    type ViewQuery = (&'static ExtractedCamera, &'static ViewTarget);
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static ViewTarget,
+        Option<&'static ExtraColorAttachments>,
+        Option<&'static MsaaResolveTarget>,
+    );
 
     fn run<'w>(
         &self,
         graph: &mut RenderGraphContext,
         render_context: &mut RenderContext<'w>,
-        (camera, target): bevy_ecs::query::QueryItem<'w, Self::ViewQuery>,
+        (camera, target, extra_color_attachments, resolve_target): bevy_ecs::query::QueryItem<
+            'w,
+            Self::ViewQuery,
+        >,
         world: &'w World,
     ) -> Result<(), NodeRunError> {
         let Some(transparent_phases) =
@@ -43,9 +56,18 @@ impl ViewNode for MainTransparentPass2dNode {
 
             let diagnostics = render_context.diagnostic_recorder();
 
+            let color_attachments = main_pass_2d_color_attachments(
+                main_pass_2d_color_attachment(target, resolve_target),
+                extra_color_attachments,
+                world.get_resource::<RenderAssets<GpuImage>>(),
+                target
+                    .sampled_main_texture()
+                    .map_or(1, |texture| texture.sample_count()),
+            );
+
             let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
                 label: Some("main_transparent_pass_2d"),
-                color_attachments: &[Some(target.get_color_attachment())],
+                color_attachments: &color_attachments,
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
@@ -64,15 +86,36 @@ impl ViewNode for MainTransparentPass2dNode {
             pass_span.end(&mut render_pass);
         }
 
+        // The main pass resolved into the `MsaaResolveTarget`, so resolve the samples into the
+        // main texture too, for the passes reading it afterwards.
+        if resolve_target.is_some_and(|resolve_target| resolve_target.is_used_by(target)) {
+            #[cfg(feature = "trace")]
+            let _resolve_main_texture_2d = info_span!("resolve_main_texture_2d").entered();
+            let pass_descriptor = RenderPassDescriptor {
+                label: Some("resolve_main_texture_2d"),
+                color_attachments: &[Some(target.get_color_attachment())],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            };
+
+            render_context
+                .command_encoder()
+                .begin_render_pass(&pass_descriptor);
+        }
+
         // WebGL2 quirk: if ending with a render pass with a custom viewport, the viewport isn't
         // reset for the next render pass so add an empty render pass without a custom viewport
-        #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
-        if camera.viewport.is_some() {
+        if camera.viewport.is_some()
+            && world
+                .get_resource::<RenderBackend>()
+                .is_some_and(RenderBackend::is_webgl2)
+        {
             #[cfg(feature = "trace")]
             let _reset_viewport_pass_2d = info_span!("reset_viewport_pass_2d").entered();
             let pass_descriptor = RenderPassDescriptor {
                 label: Some("reset_viewport_pass_2d"),
-                color_attachments: &[Some(target.get_color_attachment())],
+                color_attachments: &[Some(main_pass_2d_color_attachment(target, resolve_target))],
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
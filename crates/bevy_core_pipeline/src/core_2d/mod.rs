@@ -1,5 +1,8 @@
 mod camera_2d;
+mod extra_color_attachments;
 mod main_transparent_pass_2d_node;
+mod msaa_resolve_target;
+mod screenshot_target_2d;
 
 pub mod graph {
     use bevy_render::render_graph::{RenderLabel, RenderSubGraph};
@@ -30,9 +33,12 @@ pub mod graph {
 use std::ops::Range;
 
 pub use camera_2d::*;
+pub use extra_color_attachments::*;
 pub use main_transparent_pass_2d_node::*;
+pub use msaa_resolve_target::*;
+pub use screenshot_target_2d::*;
 
-use bevy_app::{App, Plugin};
+use bevy_app::{App, Plugin, PostUpdate};
 use bevy_ecs::{entity::EntityHashSet, prelude::*};
 use bevy_math::FloatOrd;
 use bevy_render::{
@@ -40,8 +46,9 @@ use bevy_render::{
     extract_component::ExtractComponentPlugin,
     render_graph::{EmptyNode, RenderGraphApp, ViewNodeRunner},
     render_phase::{
-        sort_phase_system, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions, PhaseItem,
-        PhaseItemExtraIndex, SortedPhaseItem, ViewSortedRenderPhases,
+        index_sorted_phase_items, sort_phase_system, CachedRenderPipelinePhaseItem, DrawFunctionId,
+        DrawFunctions, PhaseItem, PhaseItemExtraIndex, PhaseItemIndex, SortedPhaseItem,
+        ViewSortedRenderPhases,
     },
     render_resource::CachedRenderPipelineId,
     Extract, ExtractSchedule, Render, RenderApp, RenderSet,
@@ -56,7 +63,13 @@ pub struct Core2dPlugin;
 impl Plugin for Core2dPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Camera2d>()
-            .add_plugins(ExtractComponentPlugin::<Camera2d>::default());
+            .register_type::<Hdr>()
+            .register_type::<ExtraColorAttachments>()
+            .add_plugins((
+                ExtractComponentPlugin::<Camera2d>::default(),
+                ExtractComponentPlugin::<ExtraColorAttachments>::default(),
+            ))
+            .add_systems(PostUpdate, sync_camera_2d_hdr);
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
@@ -67,7 +80,13 @@ impl Plugin for Core2dPlugin {
             .add_systems(ExtractSchedule, extract_core_2d_camera_phases)
             .add_systems(
                 Render,
-                sort_phase_system::<Transparent2d>.in_set(RenderSet::PhaseSort),
+                (
+                    sort_phase_system::<Transparent2d>,
+                    index_sorted_phase_items::<Transparent2d>
+                        .run_if(resource_exists::<PhaseItemIndex>),
+                )
+                    .chain()
+                    .in_set(RenderSet::PhaseSort),
             );
 
         render_app
@@ -97,6 +116,10 @@ impl Plugin for Core2dPlugin {
 
 pub struct Transparent2d {
     pub sort_key: FloatOrd,
+    /// Breaks ties between items with equal [`Self::sort_key`]s, e.g. coplanar sprites, which are
+    /// drawn in ascending order of this index. Queue systems set it to the index of the main world
+    /// entity the item was extracted from, so the order doesn't depend on the order they run in.
+    pub extracted_index: u32,
     pub entity: Entity,
     pub pipeline: CachedRenderPipelineId,
     pub draw_function: DrawFunctionId,
@@ -144,9 +167,14 @@ impl SortedPhaseItem for Transparent2d {
         self.sort_key
     }
 
+    /// Sorts by `(sort_key, extracted_index)`, so items with equal [`Self::sort_key`]s are drawn in
+    /// a reproducible order regardless of the order they were queued in. Render world entities
+    /// aren't used as a tiebreaker, as some of them are respawned with different ids every frame.
     #[inline]
     fn sort(items: &mut [Self]) {
         // radsort is a stable radix sort that performed better than `slice::sort_by_key` or `slice::sort_unstable_by_key`.
+        // Sorting by the tiebreaker first keeps its order between items with equal sort keys.
+        radsort::sort_by_key(items, |item| item.extracted_index);
         radsort::sort_by_key(items, |item| item.sort_key().0);
     }
 }
@@ -180,3 +208,103 @@ pub fn extract_core_2d_camera_phases(
     // Clear out all dead views.
     transparent_2d_phases.retain(|camera_entity, _| live_entities.contains(camera_entity));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_render::render_phase::{
+        clear_phase_item_index, Draw, IndexedPhaseItem, TrackedRenderPass,
+    };
+
+    struct DrawNothing;
+
+    impl Draw<Transparent2d> for DrawNothing {
+        fn draw<'w>(
+            &mut self,
+            _world: &'w World,
+            _pass: &mut TrackedRenderPass<'w>,
+            _view: Entity,
+            _item: &Transparent2d,
+        ) {
+        }
+    }
+
+    #[test]
+    fn phase_item_index_maps_entities_to_queued_items() {
+        let mut world = World::new();
+        let draw_functions = DrawFunctions::<Transparent2d>::default();
+        let draw_function = draw_functions.write().add(DrawNothing);
+
+        let view = world.spawn_empty().id();
+        let sprite = world.spawn_empty().id();
+        let mesh = world.spawn_empty().id();
+        let hidden = world.spawn_empty().id();
+
+        let mut phases = ViewSortedRenderPhases::<Transparent2d>::default();
+        phases.insert_or_clear(view);
+        let phase = phases.get_mut(&view).unwrap();
+        for entity in [sprite, mesh, sprite] {
+            phase.add(Transparent2d {
+                sort_key: FloatOrd(0.0),
+                extracted_index: entity.index(),
+                entity,
+                pipeline: CachedRenderPipelineId::INVALID,
+                draw_function,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::NONE,
+            });
+        }
+        world.insert_resource(phases);
+        world.init_resource::<PhaseItemIndex>();
+
+        world.run_system_once(index_sorted_phase_items::<Transparent2d>);
+
+        let index = world.resource::<PhaseItemIndex>();
+        let expected = IndexedPhaseItem {
+            view,
+            phase: std::any::type_name::<Transparent2d>(),
+            pipeline: CachedRenderPipelineId::INVALID,
+            draw_function,
+        };
+        assert_eq!(index.get(sprite), &[expected, expected]);
+        assert_eq!(index.get(mesh), &[expected]);
+        assert!(index.get(hidden).is_empty());
+
+        world.run_system_once(clear_phase_item_index);
+        assert!(world.resource::<PhaseItemIndex>().get(sprite).is_empty());
+    }
+
+    #[test]
+    fn coplanar_items_are_sorted_by_extracted_index() {
+        let draw_function = DrawFunctions::<Transparent2d>::default()
+            .write()
+            .add(DrawNothing);
+        let item = |index: u32| Transparent2d {
+            sort_key: FloatOrd(if index % 7 == 0 { 1.0 } else { 0.0 }),
+            extracted_index: index,
+            // Render world entities don't follow the extraction order.
+            entity: Entity::from_raw(999 - index),
+            pipeline: CachedRenderPipelineId::INVALID,
+            draw_function,
+            batch_range: 0..1,
+            extra_index: PhaseItemExtraIndex::NONE,
+        };
+        let expected: Vec<_> = (0..1000)
+            .filter(|index| index % 7 != 0)
+            .chain((0..1000).filter(|index| index % 7 == 0))
+            .map(|index| (item(index).sort_key, index))
+            .collect();
+
+        // Stepping by numbers coprime with 1000 visits every index in different scrambled orders.
+        for step in [1, 337, 999] {
+            let mut items: Vec<_> = (0..1000).map(|i| item((i * step) % 1000)).collect();
+            Transparent2d::sort(&mut items);
+            let sorted: Vec<_> = items
+                .iter()
+                .map(|item| (item.sort_key, item.extracted_index))
+                .collect();
+            assert_eq!(sorted, expected, "queued with step {step}");
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use bevy_ecs::prelude::*;
+use bevy_math::UVec2;
+use bevy_render::{
+    render_resource::{RenderPassColorAttachment, Texture, TextureFormat, TextureView},
+    view::ViewTarget,
+};
+use bevy_utils::warn_once;
+use thiserror::Error;
+
+/// Resolves the multisampled main texture of a 2D camera into [`Self::view`] in addition to the
+/// view's own main texture, e.g. to composite the anti-aliased result into an intermediate HDR
+/// texture.
+///
+/// This is a render world component: insert it on the camera's render world entity during the
+/// [`ExtractSchedule`](bevy_render::ExtractSchedule), as render world entities are recreated
+/// every frame. It's only used while [`Msaa`](bevy_render::view::Msaa) is enabled.
+///
+/// The main pass resolves into [`Self::view`], and an extra empty pass then resolves the same
+/// samples into the view's main texture, so the post-processing, tonemapping and upscaling
+/// passes of the camera still see the output of the main pass.
+#[derive(Component, Clone)]
+pub struct MsaaResolveTarget {
+    /// The texture to resolve into. It must be single-sampled and have the format and size of the
+    /// camera's main texture, see [`Self::validate`].
+    pub texture: Texture,
+    /// The view of [`Self::texture`] to resolve into, covering its first mip level and layer.
+    pub view: TextureView,
+}
+
+/// The reason an [`MsaaResolveTarget`] can't be used for a view.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MsaaResolveTargetError {
+    /// MSAA resolves can't convert between formats.
+    #[error("resolve target format {resolve_format:?} doesn't match the main texture format {main_texture_format:?}")]
+    FormatMismatch {
+        resolve_format: TextureFormat,
+        main_texture_format: TextureFormat,
+    },
+    /// MSAA resolves can't scale.
+    #[error("resolve target size {resolve_size} doesn't match the main texture size {main_texture_size}")]
+    SizeMismatch {
+        resolve_size: UVec2,
+        main_texture_size: UVec2,
+    },
+    /// Only single-sampled textures can be resolved into.
+    #[error("resolve target has {sample_count} samples, it must have 1")]
+    Multisampled { sample_count: u32 },
+}
+
+impl MsaaResolveTarget {
+    /// Checks that this can be the resolve target of the main texture of `target`.
+    pub fn validate(&self, target: &ViewTarget) -> Result<(), MsaaResolveTargetError> {
+        let main_texture = target.main_texture();
+        validate_resolve_target(
+            ResolveTextureInfo::of(&self.texture),
+            ResolveTextureInfo::of(main_texture),
+        )
+    }
+
+    /// Returns `true` if the main passes of `target` resolve into this target, i.e. if MSAA is
+    /// enabled and the target is valid. Logs a warning the first time the target is invalid.
+    pub fn is_used_by(&self, target: &ViewTarget) -> bool {
+        if target.sampled_main_texture().is_none() {
+            // MSAA is disabled, so there's nothing to resolve.
+            return false;
+        }
+        match self.validate(target) {
+            Ok(()) => true,
+            Err(error) => {
+                warn_once!("Ignoring MsaaResolveTarget: {error}");
+                false
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ResolveTextureInfo {
+    format: TextureFormat,
+    size: UVec2,
+    sample_count: u32,
+}
+
+impl ResolveTextureInfo {
+    fn of(texture: &Texture) -> Self {
+        Self {
+            format: texture.format(),
+            size: UVec2::new(texture.width(), texture.height()),
+            sample_count: texture.sample_count(),
+        }
+    }
+}
+
+fn validate_resolve_target(
+    resolve: ResolveTextureInfo,
+    main_texture: ResolveTextureInfo,
+) -> Result<(), MsaaResolveTargetError> {
+    if resolve.sample_count != 1 {
+        return Err(MsaaResolveTargetError::Multisampled {
+            sample_count: resolve.sample_count,
+        });
+    }
+    if resolve.format != main_texture.format {
+        return Err(MsaaResolveTargetError::FormatMismatch {
+            resolve_format: resolve.format,
+            main_texture_format: main_texture.format,
+        });
+    }
+    if resolve.size != main_texture.size {
+        return Err(MsaaResolveTargetError::SizeMismatch {
+            resolve_size: resolve.size,
+            main_texture_size: main_texture.size,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the main color attachment of the 2D passes of `target`, resolving into
+/// `resolve_target` if MSAA is enabled and it is valid, see [`MsaaResolveTarget::is_used_by`].
+///
+/// The first pass to use the attachment clears it with the camera's clear color, as resolved from
+/// [`ClearColorConfig`](bevy_render::camera::ClearColorConfig) in `prepare_view_targets`, so each
+/// camera can clear with its own color. When MSAA writeback runs for a camera that isn't the first
+/// on its target, the writeback counts as the first pass and this attachment loads the written back
+/// contents instead of clearing them.
+pub fn main_pass_2d_color_attachment<'a>(
+    target: &'a ViewTarget,
+    resolve_target: Option<&'a MsaaResolveTarget>,
+) -> RenderPassColorAttachment<'a> {
+    let mut attachment = target.get_color_attachment();
+    if let Some(resolve_target) = resolve_target.filter(|resolve| resolve.is_used_by(target)) {
+        attachment.resolve_target = Some(&resolve_target.view);
+    }
+    attachment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(format: TextureFormat, size: [u32; 2], sample_count: u32) -> ResolveTextureInfo {
+        ResolveTextureInfo {
+            format,
+            size: size.into(),
+            sample_count,
+        }
+    }
+
+    #[test]
+    fn resolve_target_must_match_the_main_texture() {
+        let hdr = ViewTarget::TEXTURE_FORMAT_HDR;
+        let main_texture = info(hdr, [1280, 720], 1);
+
+        assert_eq!(
+            validate_resolve_target(info(hdr, [1280, 720], 1), main_texture),
+            Ok(())
+        );
+        assert_eq!(
+            validate_resolve_target(
+                info(TextureFormat::Rgba8Unorm, [1280, 720], 1),
+                info(TextureFormat::Rgba8UnormSrgb, [1280, 720], 1)
+            ),
+            Err(MsaaResolveTargetError::FormatMismatch {
+                resolve_format: TextureFormat::Rgba8Unorm,
+                main_texture_format: TextureFormat::Rgba8UnormSrgb,
+            })
+        );
+        assert_eq!(
+            validate_resolve_target(info(hdr, [640, 360], 1), main_texture),
+            Err(MsaaResolveTargetError::SizeMismatch {
+                resolve_size: UVec2::new(640, 360),
+                main_texture_size: UVec2::new(1280, 720),
+            })
+        );
+        assert_eq!(
+            validate_resolve_target(info(hdr, [1280, 720], 4), main_texture),
+            Err(MsaaResolveTargetError::Multisampled { sample_count: 4 })
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use crate::core_2d::Camera2dBundle;
+use bevy_asset::{Assets, Handle};
+use bevy_render::{
+    camera::{Camera, RenderTarget},
+    render_resource::TextureFormat,
+    texture::Image,
+    view::screenshot::{
+        screenshot_target_image, ScreenshotAlreadyRequestedError, ScreenshotManager,
+    },
+};
+use bevy_utils::default;
+
+/// An offscreen, single-sampled [`Image`] a 2D camera renders into, whose color can be read back
+/// independently of any window, e.g. for deterministic screenshot tests.
+///
+/// Spawn the camera returned by [`Self::camera_bundle`], then request the pixels with
+/// [`Self::read_back`]. The image itself is single-sampled, but the main passes of the camera are
+/// multisampled according to the global [`Msaa`](bevy_render::view::Msaa) resource, which applies to
+/// every camera of the app. They're resolved before the output is written into the image, so the
+/// readback always sees the final, resolved color. For output that doesn't depend on the
+/// multisampling implementation of the GPU, the app has to use
+/// [`Msaa::Off`](bevy_render::view::Msaa::Off), which turns multisampling off for all of its
+/// cameras.
+#[derive(Clone, Debug)]
+pub struct ScreenshotTarget2d {
+    /// The image the camera renders into.
+    pub image: Handle<Image>,
+}
+
+impl ScreenshotTarget2d {
+    /// The format of the image, whose pixels are read back as 4 bytes each.
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+    /// Creates a `width` by `height` target image.
+    pub fn new(images: &mut Assets<Image>, width: u32, height: u32) -> Self {
+        Self {
+            image: images.add(screenshot_target_image(width, height, Self::FORMAT)),
+        }
+    }
+
+    /// Returns a [`Camera2dBundle`] rendering the 2D graph into the target image.
+    pub fn camera_bundle(&self) -> Camera2dBundle {
+        Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(self.image.clone()),
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// Reads back the pixels of the target image at the end of this frame.
+    ///
+    /// `callback` eventually receives the tightly packed rows of the image, with the row padding
+    /// required by the copy already removed, on one of the `AsyncComputeTaskPool`s threads.
+    pub fn read_back(
+        &self,
+        screenshot_manager: &mut ScreenshotManager,
+        callback: impl FnOnce(Vec<u8>) + Send + Sync + 'static,
+    ) -> Result<(), ScreenshotAlreadyRequestedError> {
+        screenshot_manager.take_image_screenshot(&self.image, move |image| callback(image.data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::UVec2;
+    use bevy_render::render_resource::TextureUsages;
+
+    #[test]
+    fn target_image_is_a_readable_render_target() {
+        let mut images = Assets::<Image>::default();
+        let target = ScreenshotTarget2d::new(&mut images, 3, 2);
+
+        let image = images.get(&target.image).unwrap();
+        let descriptor = &image.texture_descriptor;
+        assert_eq!(descriptor.format, ScreenshotTarget2d::FORMAT);
+        assert_eq!(image.size(), UVec2::new(3, 2));
+        assert_eq!(descriptor.sample_count, 1);
+        assert!(descriptor
+            .usage
+            .contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC));
+        assert_eq!(image.data.len(), 3 * 2 * 4);
+
+        let camera = target.camera_bundle().camera;
+        assert!(matches!(camera.target, RenderTarget::Image(handle) if handle == target.image));
+    }
+}
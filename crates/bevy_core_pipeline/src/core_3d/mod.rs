@@ -89,7 +89,7 @@ use bevy_render::{
     },
     renderer::RenderDevice,
     texture::{BevyDefault, ColorAttachment, Image, TextureCache},
-    view::{ExtractedView, ViewDepthTexture, ViewTarget},
+    view::{DepthTextures, ExtractedView, ViewDepthTexture, ViewTarget},
     Extract, ExtractSchedule, Render, RenderApp, RenderSet,
 };
 use bevy_utils::{tracing::warn, HashMap};
@@ -615,8 +615,11 @@ pub fn prepare_core_3d_depth_textures(
     alpha_mask_3d_phases: Res<ViewBinnedRenderPhases<AlphaMask3d>>,
     transmissive_3d_phases: Res<ViewSortedRenderPhases<Transmissive3d>>,
     transparent_3d_phases: Res<ViewSortedRenderPhases<Transparent3d>>,
+    mut depth_textures: ResMut<DepthTextures>,
     views_3d: Query<(Entity, &ExtractedCamera, Option<&DepthPrepass>, &Camera3d)>,
 ) {
+    depth_textures.clear();
+
     let mut render_target_usage = HashMap::default();
     for (view, camera, depth_prepass, camera_3d) in &views_3d {
         if !opaque_3d_phases.contains_key(&view)
@@ -674,6 +677,7 @@ pub fn prepare_core_3d_depth_textures(
             })
             .clone();
 
+        depth_textures.insert(entity, cached_texture.clone());
         commands.entity(entity).insert(ViewDepthTexture::new(
             cached_texture,
             match camera_3d.depth_load_op {
@@ -160,6 +160,9 @@ impl FromWorld for CopyDeferredLightingIdPipeline {
                     }),
                     multisample: MultisampleState::default(),
                     push_constant_ranges: vec![],
+                    constants: Vec::new(),
+                    vertex_compilation_options: None,
+                    fragment_compilation_options: None,
                 });
 
         Self {
@@ -786,6 +786,9 @@ impl SpecializedRenderPipeline for DepthOfFieldPipeline {
                 },
                 targets,
             }),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
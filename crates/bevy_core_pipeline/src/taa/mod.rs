@@ -14,7 +14,6 @@ use bevy_ecs::{
     system::{Commands, Query, Res, ResMut, Resource},
     world::{FromWorld, World},
 };
-use bevy_math::vec2;
 use bevy_reflect::Reflect;
 use bevy_render::{
     camera::{ExtractedCamera, MipBias, TemporalJitter},
@@ -333,11 +332,15 @@ impl SpecializedRenderPipeline for TaaPipeline {
             depth_stencil: None,
             multisample: MultisampleState::default(),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
 
 fn extract_taa_settings(mut commands: Commands, mut main_world: ResMut<MainWorld>) {
+    let main_world = main_world.world_mut();
     let mut cameras_3d = main_world
         .query_filtered::<(Entity, &Camera, &Projection, &mut TemporalAntiAliasSettings), (
             With<Camera3d>,
@@ -346,9 +349,7 @@ fn extract_taa_settings(mut commands: Commands, mut main_world: ResMut<MainWorld
             With<MotionVectorPrepass>,
         )>();
 
-    for (entity, camera, camera_projection, mut taa_settings) in
-        cameras_3d.iter_mut(&mut main_world)
-    {
+    for (entity, camera, camera_projection, mut taa_settings) in cameras_3d.iter_mut(main_world) {
         let has_perspective_projection = matches!(camera_projection, Projection::Perspective(_));
         if camera.is_active && has_perspective_projection {
             commands.get_or_spawn(entity).insert(taa_settings.clone());
@@ -365,19 +366,7 @@ fn prepare_taa_jitter_and_mip_bias(
     >,
     mut commands: Commands,
 ) {
-    // Halton sequence (2, 3) - 0.5, skipping i = 0
-    let halton_sequence = [
-        vec2(0.0, -0.16666666),
-        vec2(-0.25, 0.16666669),
-        vec2(0.25, -0.3888889),
-        vec2(-0.375, -0.055555552),
-        vec2(0.125, 0.2777778),
-        vec2(-0.125, -0.2777778),
-        vec2(0.375, 0.055555582),
-        vec2(-0.4375, 0.3888889),
-    ];
-
-    let offset = halton_sequence[frame_count.0 as usize % halton_sequence.len()];
+    let offset = TemporalJitter::halton(frame_count.0).offset;
 
     for (entity, mut jitter, mip_bias) in &mut query {
         jitter.offset = offset;
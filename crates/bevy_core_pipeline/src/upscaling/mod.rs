@@ -1,7 +1,10 @@
 use crate::blit::{BlitPipeline, BlitPipelineKey};
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
+use bevy_math::{URect, UVec2};
+use bevy_reflect::prelude::*;
 use bevy_render::camera::{CameraOutputMode, ExtractedCamera};
+use bevy_render::extract_component::{ExtractComponent, ExtractComponentPlugin};
 use bevy_render::view::ViewTarget;
 use bevy_render::{render_resource::*, Render, RenderApp, RenderSet};
 use bevy_utils::HashSet;
@@ -14,6 +17,9 @@ pub struct UpscalingPlugin;
 
 impl Plugin for UpscalingPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<FinalOutputViewport>()
+            .add_plugins(ExtractComponentPlugin::<FinalOutputViewport>::default());
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app.add_systems(
                 Render,
@@ -23,6 +29,47 @@ impl Plugin for UpscalingPlugin {
     }
 }
 
+/// Restricts the final blit of a camera's output to a sub-rectangle of its render target,
+/// in physical pixels.
+///
+/// The upscaling node uses this as the destination viewport and scissor rect when writing
+/// to the output texture, which allows several cameras to share one surface,
+/// e.g. for a minimap or picture-in-picture. Cameras without this component fill the whole target.
+///
+/// It only applies while the camera uses [`CameraOutputMode::Write`], and is overridden by its
+/// `dst_rect`, which documents how this differs from
+/// [`Camera::viewport`](bevy_render::camera::Camera::viewport).
+#[derive(Component, ExtractComponent, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct FinalOutputViewport(pub URect);
+
+impl FinalOutputViewport {
+    /// Returns the rect the camera output is blitted to, clamped to a target of size `target_size`.
+    pub fn blit_rect(&self, target_size: UVec2) -> URect {
+        URect::from_corners(self.0.min.min(target_size), self.0.max.min(target_size))
+    }
+}
+
+/// Returns the rect of a target of size `target_size` a camera's output is blitted to, or `None` if
+/// the output covers the whole target.
+///
+/// The `dst_rect` of [`CameraOutputMode::Write`] takes precedence over the camera's
+/// [`FinalOutputViewport`].
+fn output_rect(
+    output_mode: Option<&CameraOutputMode>,
+    output_viewport: Option<&FinalOutputViewport>,
+    target_size: UVec2,
+) -> Option<URect> {
+    let output_viewport = match output_mode {
+        Some(CameraOutputMode::Write {
+            dst_rect: Some(dst_rect),
+            ..
+        }) => FinalOutputViewport(*dst_rect),
+        _ => *output_viewport?,
+    };
+    Some(output_viewport.blit_rect(target_size))
+}
+
 #[derive(Component)]
 pub struct ViewUpscalingPipeline(CachedRenderPipelineId);
 
@@ -36,27 +83,10 @@ fn prepare_view_upscaling_pipelines(
     let mut output_textures = HashSet::new();
     for (entity, view_target, camera) in view_targets.iter() {
         let out_texture_id = view_target.out_texture().id();
-        let blend_state = if let Some(ExtractedCamera {
-            output_mode: CameraOutputMode::Write { blend_state, .. },
-            ..
-        }) = camera
-        {
-            match *blend_state {
-                None => {
-                    // If we've already seen this output for a camera and it doesn't have a output blend
-                    // mode configured, default to alpha blend so that we don't accidentally overwrite
-                    // the output texture
-                    if output_textures.contains(&out_texture_id) {
-                        Some(BlendState::ALPHA_BLENDING)
-                    } else {
-                        None
-                    }
-                }
-                _ => *blend_state,
-            }
-        } else {
-            None
-        };
+        let blend_state = output_blend_state(
+            camera.map(|camera| &camera.output_mode),
+            output_textures.contains(&out_texture_id),
+        );
         output_textures.insert(out_texture_id);
 
         let key = BlitPipelineKey {
@@ -74,3 +104,110 @@ fn prepare_view_upscaling_pipelines(
             .insert(ViewUpscalingPipeline(pipeline));
     }
 }
+
+/// Returns the blend state of the pipeline writing a camera's output to its target, given the
+/// camera's output mode and whether a previous camera already wrote to the same target.
+fn output_blend_state(
+    output_mode: Option<&CameraOutputMode>,
+    target_written: bool,
+) -> Option<BlendState> {
+    match output_mode {
+        Some(CameraOutputMode::Write {
+            blend_state: Some(blend_state),
+            ..
+        }) => Some(*blend_state),
+        // If we've already seen this output for a camera and it doesn't have a output blend
+        // mode configured, default to alpha blend so that we don't accidentally overwrite
+        // the output texture
+        Some(CameraOutputMode::Write {
+            blend_state: None, ..
+        }) if target_written => Some(BlendState::ALPHA_BLENDING),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_render::camera::ClearColorConfig;
+
+    #[test]
+    fn final_output_viewports_blit_rects() {
+        let target_size = UVec2::new(1280, 720);
+        let main = FinalOutputViewport(URect::new(0, 0, 1280, 720));
+        let minimap = FinalOutputViewport(URect::new(1024, 512, 1536, 1024));
+
+        assert_eq!(main.blit_rect(target_size), URect::new(0, 0, 1280, 720));
+        assert_eq!(
+            minimap.blit_rect(target_size),
+            URect::new(1024, 512, 1280, 720)
+        );
+    }
+
+    #[test]
+    fn picture_in_picture_is_blended_over_the_main_camera() {
+        let target_size = UVec2::new(1280, 720);
+        let output_mode = CameraOutputMode::default();
+
+        // The main camera has no `FinalOutputViewport`, so it fills the target. It's the first to
+        // write to the target, so it overwrites it.
+        assert_eq!(output_blend_state(Some(&output_mode), false), None);
+
+        // The inset camera writes afterwards, only to its corner, and is blended over the output
+        // of the main camera.
+        let inset = FinalOutputViewport(URect::new(960, 20, 1260, 200));
+        assert_eq!(inset.blit_rect(target_size), inset.0);
+        assert_eq!(
+            output_blend_state(Some(&output_mode), true),
+            Some(BlendState::ALPHA_BLENDING)
+        );
+
+        // An explicit blend state is kept.
+        let replace = CameraOutputMode::Write {
+            blend_state: Some(BlendState::REPLACE),
+            clear_color: Default::default(),
+            dst_rect: None,
+        };
+        assert_eq!(
+            output_blend_state(Some(&replace), true),
+            Some(BlendState::REPLACE)
+        );
+    }
+
+    #[test]
+    fn video_wall_cameras_write_to_their_dst_rects() {
+        let target_size = UVec2::new(3840, 1080);
+        let screen = |x: u32| CameraOutputMode::Write {
+            blend_state: None,
+            clear_color: ClearColorConfig::None,
+            dst_rect: Some(URect::new(x, 0, x + 1920, 1080)),
+        };
+
+        assert_eq!(
+            output_rect(Some(&screen(0)), None, target_size),
+            Some(URect::new(0, 0, 1920, 1080))
+        );
+        assert_eq!(
+            output_rect(Some(&screen(1920)), None, target_size),
+            Some(URect::new(1920, 0, 3840, 1080))
+        );
+        // Rects are clamped to the target.
+        assert_eq!(
+            output_rect(Some(&screen(2880)), None, target_size),
+            Some(URect::new(2880, 0, 3840, 1080))
+        );
+
+        // `dst_rect` takes precedence over `FinalOutputViewport`, which is used otherwise.
+        let output_viewport = FinalOutputViewport(URect::new(0, 0, 100, 100));
+        assert_eq!(
+            output_rect(Some(&screen(0)), Some(&output_viewport), target_size),
+            Some(URect::new(0, 0, 1920, 1080))
+        );
+        let output_mode = CameraOutputMode::default();
+        assert_eq!(
+            output_rect(Some(&output_mode), Some(&output_viewport), target_size),
+            Some(output_viewport.0)
+        );
+        assert_eq!(output_rect(Some(&output_mode), None, target_size), None);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{blit::BlitPipeline, upscaling::ViewUpscalingPipeline};
+use crate::{
+    blit::BlitPipeline,
+    upscaling::{output_rect, FinalOutputViewport, ViewUpscalingPipeline},
+};
 use bevy_ecs::{prelude::*, query::QueryItem};
 use bevy_render::camera::{ClearColor, ClearColorConfig};
 use bevy_render::{
@@ -22,13 +25,14 @@ impl ViewNode for UpscalingNode {
         &'static ViewTarget,
         &'static ViewUpscalingPipeline,
         Option<&'static ExtractedCamera>,
+        Option<&'static FinalOutputViewport>,
     );
 
     fn run(
         &self,
         _graph: &mut RenderGraphContext,
         render_context: &mut RenderContext,
-        (target, upscaling_target, camera): QueryItem<Self::ViewQuery>,
+        (target, upscaling_target, camera, output_viewport): QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), NodeRunError> {
         let pipeline_cache = world.get_resource::<PipelineCache>().unwrap();
@@ -84,6 +88,28 @@ impl ViewNode for UpscalingNode {
             .command_encoder()
             .begin_render_pass(&pass_descriptor);
 
+        if let Some(rect) = camera.and_then(|camera| {
+            output_rect(
+                Some(&camera.output_mode),
+                output_viewport,
+                camera.physical_target_size?,
+            )
+        }) {
+            let size = rect.size();
+            if size.x == 0 || size.y == 0 {
+                return Ok(());
+            }
+            render_pass.set_viewport(
+                rect.min.x as f32,
+                rect.min.y as f32,
+                size.x as f32,
+                size.y as f32,
+                0.0,
+                1.0,
+            );
+            render_pass.set_scissor_rect(rect.min.x, rect.min.y, size.x, size.y);
+        }
+
         render_pass.set_pipeline(pipeline);
         render_pass.set_bind_group(0, bind_group, &[]);
         render_pass.draw(0..3, 0..1);
@@ -147,6 +147,9 @@ impl SpecializedRenderPipeline for LineGizmoPipeline {
             },
             label: Some("LineGizmo Pipeline 2D".into()),
             push_constant_ranges: vec![],
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -232,6 +235,9 @@ impl SpecializedRenderPipeline for LineJointGizmoPipeline {
             },
             label: Some("LineJointGizmo Pipeline 2D".into()),
             push_constant_ranges: vec![],
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -296,6 +302,7 @@ fn queue_line_gizmos_2d(
                 draw_function,
                 pipeline,
                 sort_key: FloatOrd(f32::INFINITY),
+                extracted_index: entity.index(),
                 batch_range: 0..1,
                 extra_index: PhaseItemExtraIndex::NONE,
             });
@@ -355,6 +362,7 @@ fn queue_line_joint_gizmos_2d(
                 draw_function,
                 pipeline,
                 sort_key: FloatOrd(f32::INFINITY),
+                extracted_index: entity.index(),
                 batch_range: 0..1,
                 extra_index: PhaseItemExtraIndex::NONE,
             });
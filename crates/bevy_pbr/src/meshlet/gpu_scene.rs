@@ -64,12 +64,12 @@ pub fn extract_meshlet_meshes(
     >,
 ) {
     if system_state.is_none() {
-        *system_state = Some(SystemState::new(&mut main_world));
+        *system_state = Some(SystemState::new(main_world.world_mut()));
     }
     let system_state = system_state.as_mut().unwrap();
 
     let (instances_query, asset_server, mut assets, mut asset_events) =
-        system_state.get_mut(&mut main_world);
+        system_state.get_mut(main_world.world_mut());
 
     // Reset all temporary data for MeshletGpuScene
     gpu_scene.reset();
@@ -196,6 +196,9 @@ pub fn prepare_material_meshlet_meshes_main_opaque_pass<M: Material>(
                     entry_point: material_fragment.entry_point,
                     targets: material_fragment.targets,
                 }),
+                constants: Vec::new(),
+                vertex_compilation_options: None,
+                fragment_compilation_options: None,
             };
 
             let material_id = gpu_scene.get_material_id(material_id.untyped());
@@ -348,6 +351,9 @@ pub fn prepare_material_meshlet_meshes_prepass<M: Material>(
                     entry_point,
                     targets: material_fragment.targets,
                 }),
+                constants: Vec::new(),
+                vertex_compilation_options: None,
+                fragment_compilation_options: None,
             };
 
             let material_id = gpu_scene.get_material_id(material_id.untyped());
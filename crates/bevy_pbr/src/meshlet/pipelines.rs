@@ -55,6 +55,7 @@ impl FromWorld for MeshletPipelines {
                     shader: MESHLET_FILL_CLUSTER_BUFFERS_SHADER_HANDLE,
                     shader_defs: vec!["MESHLET_FILL_CLUSTER_BUFFERS_PASS".into()],
                     entry_point: "fill_cluster_buffers".into(),
+                    constants: Vec::new(),
                 },
             ),
 
@@ -68,6 +69,7 @@ impl FromWorld for MeshletPipelines {
                     "MESHLET_FIRST_CULLING_PASS".into(),
                 ],
                 entry_point: "cull_clusters".into(),
+                constants: Vec::new(),
             }),
 
             cull_second: pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
@@ -80,6 +82,7 @@ impl FromWorld for MeshletPipelines {
                     "MESHLET_SECOND_CULLING_PASS".into(),
                 ],
                 entry_point: "cull_clusters".into(),
+                constants: Vec::new(),
             }),
 
             downsample_depth_first: pipeline_cache.queue_compute_pipeline(
@@ -93,6 +96,7 @@ impl FromWorld for MeshletPipelines {
                     shader: MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE,
                     shader_defs: vec![],
                     entry_point: "downsample_depth_first".into(),
+                    constants: Vec::new(),
                 },
             ),
 
@@ -107,6 +111,7 @@ impl FromWorld for MeshletPipelines {
                     shader: MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE,
                     shader_defs: vec![],
                     entry_point: "downsample_depth_second".into(),
+                    constants: Vec::new(),
                 },
             ),
 
@@ -161,6 +166,9 @@ impl FromWorld for MeshletPipelines {
                             }),
                         ],
                     }),
+                    constants: Vec::new(),
+                    vertex_compilation_options: None,
+                    fragment_compilation_options: None,
                 },
             ),
 
@@ -193,6 +201,9 @@ impl FromWorld for MeshletPipelines {
                     }),
                     multisample: MultisampleState::default(),
                     fragment: None,
+                    constants: Vec::new(),
+                    vertex_compilation_options: None,
+                    fragment_compilation_options: None,
                 },
             ),
 
@@ -238,6 +249,9 @@ impl FromWorld for MeshletPipelines {
                         entry_point: "fragment".into(),
                         targets: vec![],
                     }),
+                    constants: Vec::new(),
+                    vertex_compilation_options: None,
+                    fragment_compilation_options: None,
                 },
             ),
 
@@ -261,6 +275,9 @@ impl FromWorld for MeshletPipelines {
                     entry_point: "copy_material_depth".into(),
                     targets: vec![],
                 }),
+                constants: Vec::new(),
+                vertex_compilation_options: None,
+                fragment_compilation_options: None,
             }),
         }
     }
@@ -1169,6 +1169,9 @@ impl FromWorld for MeshPipeline {
                 sampler,
                 size: image.size(),
                 mip_level_count: image.texture_descriptor.mip_level_count,
+                mip_level_views: Vec::new(),
+                format_views: Vec::new(),
+                format_mip_level_views: Vec::new(),
             }
         };
 
@@ -1917,6 +1920,9 @@ impl SpecializedMeshPipeline for MeshPipeline {
                 alpha_to_coverage_enabled,
             },
             label: Some(label),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         })
     }
 }
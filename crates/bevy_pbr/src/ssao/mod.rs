@@ -412,6 +412,7 @@ impl FromWorld for SsaoPipelines {
                 shader: PREPROCESS_DEPTH_SHADER_HANDLE,
                 shader_defs: Vec::new(),
                 entry_point: "preprocess_depth".into(),
+                constants: Vec::new(),
             });
 
         let spatial_denoise_pipeline =
@@ -425,6 +426,7 @@ impl FromWorld for SsaoPipelines {
                 shader: SPATIAL_DENOISE_SHADER_HANDLE,
                 shader_defs: Vec::new(),
                 entry_point: "spatial_denoise".into(),
+                constants: Vec::new(),
             });
 
         Self {
@@ -476,6 +478,7 @@ impl SpecializedComputePipeline for SsaoPipelines {
             shader: GTAO_SHADER_HANDLE,
             shader_defs,
             entry_point: "gtao".into(),
+            constants: Vec::new(),
         }
     }
 }
@@ -23,7 +23,7 @@ use crate::{
         ViewBinnedRenderPhases, ViewSortedRenderPhases,
     },
     render_resource::{BufferVec, GpuArrayBufferable, RawBufferVec, UninitBufferVec},
-    renderer::{RenderAdapter, RenderDevice, RenderQueue},
+    renderer::{HardwareWorkarounds, RenderAdapter, RenderDevice, RenderQueue},
     view::{GpuCulling, ViewTarget},
     Render, RenderApp, RenderSet,
 };
@@ -224,10 +224,13 @@ impl FromWorld for GpuPreprocessingSupport {
     fn from_world(world: &mut World) -> Self {
         let adapter = world.resource::<RenderAdapter>();
         let device = world.resource::<RenderDevice>();
+        let workarounds = world
+            .get_resource::<HardwareWorkarounds>()
+            .copied()
+            .unwrap_or_default();
 
-        if device.limits().max_compute_workgroup_size_x == 0 ||
-            // filter lower end / older devices on Android as they crash when using GPU preprocessing
-            (cfg!(target_os = "android") && adapter.get_info().name.starts_with("Adreno (TM) 6"))
+        if device.limits().max_compute_workgroup_size_x == 0
+            || workarounds.disable_gpu_preprocessing
         {
             GpuPreprocessingSupport::None
         } else if !device
@@ -22,11 +22,15 @@ use bevy_ecs::{
     prelude::With,
     query::Has,
     reflect::ReflectComponent,
+    schedule::SystemSet,
     system::{Commands, Query, Res, ResMut, Resource},
 };
-use bevy_math::{vec2, Dir3, Mat4, Ray3d, Rect, URect, UVec2, UVec4, Vec2, Vec3};
+use bevy_math::{
+    cubic_splines::CubicCurve, vec2, Dir3, Mat4, Ray3d, Rect, URect, UVec2, UVec4, Vec2, Vec3,
+};
 use bevy_reflect::prelude::*;
 use bevy_render_macros::ExtractComponent;
+use bevy_time::Time;
 use bevy_transform::components::GlobalTransform;
 use bevy_utils::{tracing::warn, warn_once};
 use bevy_utils::{HashMap, HashSet};
@@ -35,6 +39,7 @@ use bevy_window::{
     WindowScaleFactorChanged,
 };
 use std::ops::Range;
+use thiserror::Error;
 use wgpu::{BlendState, TextureFormat, TextureUsages};
 
 use super::{ClearColorConfig, Projection};
@@ -67,6 +72,30 @@ impl Default for Viewport {
     }
 }
 
+impl Viewport {
+    /// Shrinks each axis of [`physical_size`](Self::physical_size) independently so that it
+    /// does not exceed `target`.
+    pub fn clamp_to_size(&mut self, target: UVec2) {
+        self.physical_size = self.physical_size.min(target);
+    }
+
+    /// Shrinks [`physical_size`](Self::physical_size) so that it does not exceed `target`,
+    /// scaling both axes by the same factor to preserve the aspect ratio.
+    ///
+    /// If the viewport had to be shrunk, it is centered within `target`. Viewports that
+    /// already fit are left untouched.
+    pub fn clamp_to_size_keep_aspect(&mut self, target: UVec2) {
+        let size = self.physical_size;
+        if size.cmple(target).all() {
+            return;
+        }
+
+        let scale = (target.as_vec2() / size.as_vec2()).min_element();
+        self.physical_size = (size.as_vec2() * scale).as_uvec2().min(target);
+        self.physical_position = (target - self.physical_size) / 2;
+    }
+}
+
 /// Information about the current [`RenderTarget`].
 #[derive(Default, Debug, Clone)]
 pub struct RenderTargetInfo {
@@ -146,6 +175,48 @@ impl Default for Exposure {
     }
 }
 
+/// Animates the exposure of a camera over time, e.g. for cinematic sequences.
+///
+/// While present, the exposure handed to the renderer is sampled from [`Self::ev100`] during
+/// extraction, using the main world's [`Time::elapsed_seconds`] relative to
+/// [`Self::start_seconds`], instead of being read from the camera's [`Exposure`]. The curve is held
+/// at its end points outside of its domain, so a finished animation keeps its last value.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct ExposureCurve {
+    /// EV100 values over time. The curve parameter is in seconds since [`Self::start_seconds`].
+    pub ev100: CubicCurve<f32>,
+    /// The [`Time::elapsed_seconds`] at which the animation starts.
+    pub start_seconds: f32,
+}
+
+impl ExposureCurve {
+    /// Creates an animation of the EV100 values of `ev100`, starting at the
+    /// [`Time::elapsed_seconds`] given by `start_seconds`.
+    pub fn new(ev100: CubicCurve<f32>, start_seconds: f32) -> Self {
+        Self {
+            ev100,
+            start_seconds,
+        }
+    }
+
+    /// Samples the curve at `elapsed_seconds` and converts the result with [`Exposure::exposure`].
+    ///
+    /// Returns `None` if the curve has no segments.
+    pub fn exposure_at(&self, elapsed_seconds: f32) -> Option<f32> {
+        if self.ev100.segments.is_empty() {
+            return None;
+        }
+        let t = (elapsed_seconds - self.start_seconds).clamp(0.0, self.ev100.segments.len() as f32);
+        Some(
+            Exposure {
+                ev100: self.ev100.position(t),
+            }
+            .exposure(),
+        )
+    }
+}
+
 /// Parameters based on physical camera characteristics for calculating EV100
 /// values for use with [`Exposure`]. This is also used for depth of field.
 #[derive(Clone, Copy)]
@@ -227,6 +298,10 @@ pub struct Camera {
     pub msaa_writeback: bool,
     /// The clear color operation to perform on the render target.
     pub clear_color: ClearColorConfig,
+    /// If this is set to `true`, a [`Viewport`] that no longer fits in the [`RenderTarget`] (for
+    /// example after a fullscreen transition shrinks the window) is shrunk uniformly and centered
+    /// with [`Viewport::clamp_to_size_keep_aspect`] instead of being clamped per axis.
+    pub viewport_keep_aspect: bool,
 }
 
 impl Default for Camera {
@@ -241,6 +316,7 @@ impl Default for Camera {
             hdr: false,
             msaa_writeback: true,
             clear_color: Default::default(),
+            viewport_keep_aspect: false,
         }
     }
 }
@@ -383,10 +459,14 @@ impl Camera {
     /// To get the world space coordinates with Normalized Device Coordinates, you should use
     /// [`ndc_to_world`](Self::ndc_to_world).
     ///
+    /// Both perspective and orthographic projections are supported, as well as the reverse-z depth
+    /// used by Bevy's projections: the near plane is at NDC depth `1.0`.
+    ///
     /// Returns `None` if any of these conditions occur:
     /// - The logical viewport size cannot be computed. See [`logical_viewport_size`](Camera::logical_viewport_size)
     /// - The near or far plane cannot be computed. This can happen if the `camera_transform`, the `world_position`, or the projection matrix defined by [`CameraProjection`] contain `NAN`.
     ///     Panics if the projection matrix is null and `glam_assert` is enabled.
+    #[doc(alias = "screen_ray", alias = "screen_to_world")]
     pub fn viewport_to_world(
         &self,
         camera_transform: &GlobalTransform,
@@ -490,6 +570,16 @@ pub enum CameraOutputMode {
         blend_state: Option<BlendState>,
         /// The clear color operation to perform on the final render target texture.
         clear_color: ClearColorConfig,
+        /// The sub-rectangle of the render target the output is written to, in physical pixels,
+        /// clamped to the target. If `None`, the output covers the whole target, unless the camera
+        /// has a `FinalOutputViewport` from `bevy_core_pipeline`.
+        ///
+        /// This allows several cameras to be composited into one large target, e.g. a video wall.
+        /// Unlike [`Camera::viewport`], which changes what the camera renders by projecting the
+        /// scene onto the viewport's size, this leaves rendering untouched and only moves the
+        /// final result, which is stretched from the full intermediate textures to the rect. Use a
+        /// rect with the aspect ratio of the target, or a matching viewport, to avoid distortion.
+        dst_rect: Option<URect>,
     },
     /// Skips writing the camera output to the configured render target. The output will remain in the
     /// Render Target's "intermediate" textures, which a camera with a higher order should write to the render target
@@ -505,6 +595,7 @@ impl Default for CameraOutputMode {
         CameraOutputMode::Write {
             blend_state: None,
             clear_color: ClearColorConfig::Default,
+            dst_rect: None,
         }
     }
 }
@@ -536,6 +627,13 @@ pub enum RenderTarget {
     Window(WindowRef),
     /// Image to which the camera's view is rendered.
     Image(Handle<Image>),
+    /// A single mip level of an image to which the camera's view is rendered.
+    ///
+    /// The viewport is sized to the dimensions of the mip level, which makes it possible to
+    /// generate a mip chain through successive passes. The image must have
+    /// [`TextureUsages::RENDER_ATTACHMENT`](wgpu::TextureUsages::RENDER_ATTACHMENT) and more
+    /// than `mip_level` mip levels, otherwise the camera won't render.
+    ImageMipLevel(Handle<Image>, u32),
     /// Texture View to which the camera's view is rendered.
     /// Useful when the texture view needs to be created outside of Bevy, for example OpenXR.
     TextureView(ManualTextureViewHandle),
@@ -556,6 +654,8 @@ pub enum NormalizedRenderTarget {
     Window(NormalizedWindowRef),
     /// Image to which the camera's view is rendered.
     Image(Handle<Image>),
+    /// A single mip level of an image to which the camera's view is rendered.
+    ImageMipLevel(Handle<Image>, u32),
     /// Texture View to which the camera's view is rendered.
     /// Useful when the texture view needs to be created outside of Bevy, for example OpenXR.
     TextureView(ManualTextureViewHandle),
@@ -575,6 +675,9 @@ impl RenderTarget {
                 .normalize(primary_window)
                 .map(NormalizedRenderTarget::Window),
             RenderTarget::Image(handle) => Some(NormalizedRenderTarget::Image(handle.clone())),
+            RenderTarget::ImageMipLevel(handle, mip_level) => Some(
+                NormalizedRenderTarget::ImageMipLevel(handle.clone(), *mip_level),
+            ),
             RenderTarget::TextureView(id) => Some(NormalizedRenderTarget::TextureView(*id)),
         }
     }
@@ -582,10 +685,9 @@ impl RenderTarget {
     /// Get a handle to the render target's image,
     /// or `None` if the render target is another variant.
     pub fn as_image(&self) -> Option<&Handle<Image>> {
-        if let Self::Image(handle) = self {
-            Some(handle)
-        } else {
-            None
+        match self {
+            Self::Image(handle) | Self::ImageMipLevel(handle, _) => Some(handle),
+            _ => None,
         }
     }
 }
@@ -604,6 +706,9 @@ impl NormalizedRenderTarget {
             NormalizedRenderTarget::Image(image_handle) => {
                 images.get(image_handle).map(|image| &image.texture_view)
             }
+            NormalizedRenderTarget::ImageMipLevel(image_handle, mip_level) => images
+                .get(image_handle)
+                .and_then(|image| image.mip_level_view(*mip_level)),
             NormalizedRenderTarget::TextureView(id) => {
                 manual_texture_views.get(id).map(|tex| &tex.texture_view)
             }
@@ -621,7 +726,8 @@ impl NormalizedRenderTarget {
             NormalizedRenderTarget::Window(window_ref) => windows
                 .get(&window_ref.entity())
                 .and_then(|window| window.swap_chain_texture_format),
-            NormalizedRenderTarget::Image(image_handle) => {
+            NormalizedRenderTarget::Image(image_handle)
+            | NormalizedRenderTarget::ImageMipLevel(image_handle, _) => {
                 images.get(image_handle).map(|image| image.texture_format)
             }
             NormalizedRenderTarget::TextureView(id) => {
@@ -630,6 +736,28 @@ impl NormalizedRenderTarget {
         }
     }
 
+    /// Retrieves the format a camera with the given [`RenderTargetViewFormats`] will view this
+    /// render target as.
+    ///
+    /// Only [`NormalizedRenderTarget::Image`] and [`NormalizedRenderTarget::ImageMipLevel`]
+    /// targets honor extra view formats; returns `None` for other targets or if none of the
+    /// formats is supported by the image.
+    pub fn get_texture_view_format(
+        &self,
+        images: &RenderAssets<GpuImage>,
+        view_formats: &RenderTargetViewFormats,
+    ) -> Option<TextureFormat> {
+        let (NormalizedRenderTarget::Image(image_handle)
+        | NormalizedRenderTarget::ImageMipLevel(image_handle, _)) = self
+        else {
+            return None;
+        };
+        let image = images.get(image_handle)?;
+        view_formats.first_supported(image.texture_format, |format| {
+            image.format_view(format).is_some()
+        })
+    }
+
     pub fn get_render_target_info<'a>(
         &self,
         resolutions: impl IntoIterator<Item = (Entity, &'a Window)>,
@@ -651,6 +779,20 @@ impl NormalizedRenderTarget {
                     scale_factor: 1.0,
                 })
             }
+            NormalizedRenderTarget::ImageMipLevel(image_handle, mip_level) => {
+                let image = images.get(image_handle)?;
+                if *mip_level >= image.texture_descriptor.mip_level_count {
+                    warn!(
+                        "Camera render target mip level {} is out of range for an image with {} mip levels",
+                        mip_level, image.texture_descriptor.mip_level_count
+                    );
+                    return None;
+                }
+                Some(RenderTargetInfo {
+                    physical_size: mip_level_size(image.size(), *mip_level),
+                    scale_factor: 1.0,
+                })
+            }
             NormalizedRenderTarget::TextureView(id) => {
                 manual_texture_views.get(id).map(|tex| RenderTargetInfo {
                     physical_size: tex.size,
@@ -660,6 +802,22 @@ impl NormalizedRenderTarget {
         }
     }
 
+    /// Returns the aspect ratio (`width / height`) of this render target, using the same
+    /// lookup as [`NormalizedRenderTarget::get_render_target_info`].
+    ///
+    /// Returns `None` if the target can't be resolved or has a height of zero.
+    pub fn aspect_ratio<'a>(
+        &self,
+        resolutions: impl IntoIterator<Item = (Entity, &'a Window)>,
+        images: &Assets<Image>,
+        manual_texture_views: &ManualTextureViews,
+    ) -> Option<f32> {
+        let physical_size = self
+            .get_render_target_info(resolutions, images, manual_texture_views)?
+            .physical_size;
+        (physical_size.y != 0).then(|| physical_size.x as f32 / physical_size.y as f32)
+    }
+
     // Check if this render target is contained in the given changed windows or images.
     fn is_changed(
         &self,
@@ -670,7 +828,8 @@ impl NormalizedRenderTarget {
             NormalizedRenderTarget::Window(window_ref) => {
                 changed_window_ids.contains(&window_ref.entity())
             }
-            NormalizedRenderTarget::Image(image_handle) => {
+            NormalizedRenderTarget::Image(image_handle)
+            | NormalizedRenderTarget::ImageMipLevel(image_handle, _) => {
                 changed_image_handles.contains(&image_handle.id())
             }
             NormalizedRenderTarget::TextureView(_) => true,
@@ -678,6 +837,15 @@ impl NormalizedRenderTarget {
     }
 }
 
+/// Returns the size of the given mip level of a texture whose base level is `size`.
+fn mip_level_size(size: UVec2, mip_level: u32) -> UVec2 {
+    UVec2::new(
+        size.x.checked_shr(mip_level).unwrap_or(0),
+        size.y.checked_shr(mip_level).unwrap_or(0),
+    )
+    .max(UVec2::ONE)
+}
+
 /// System in charge of updating a [`Camera`] when its window or projection changes.
 ///
 /// The system detects window creation, resize, and scale factor change events to update the camera
@@ -771,15 +939,14 @@ pub fn camera_system<T: CameraProjection + Component>(
                 // This check is needed because when changing WindowMode to SizedFullscreen, the viewport may have invalid
                 // arguments due to a sudden change on the window size to a lower value.
                 // If the size of the window is lower, the viewport will match that lower value.
-                if let Some(viewport) = &mut camera.viewport {
-                    let target_info = &new_computed_target_info;
-                    if let Some(target) = target_info {
-                        if viewport.physical_size.x > target.physical_size.x {
-                            viewport.physical_size.x = target.physical_size.x;
-                        }
-                        if viewport.physical_size.y > target.physical_size.y {
-                            viewport.physical_size.y = target.physical_size.y;
-                        }
+                let keep_aspect = camera.viewport_keep_aspect;
+                if let (Some(viewport), Some(target)) =
+                    (&mut camera.viewport, &new_computed_target_info)
+                {
+                    if keep_aspect {
+                        viewport.clamp_to_size_keep_aspect(target.physical_size);
+                    } else {
+                        viewport.clamp_to_size(target.physical_size);
                     }
                 }
                 camera.computed.target_info = new_computed_target_info;
@@ -810,6 +977,89 @@ impl Default for CameraMainTextureUsages {
     }
 }
 
+/// Additional texture formats a camera may use when viewing its [`RenderTarget::Image`].
+///
+/// This lets a camera render to an image through a different, view-compatible format than the one
+/// the image was created with, e.g. rendering in sRGB to a linear `Rgba8Unorm` image that is later
+/// sampled linearly. Each format must also be listed in the image's
+/// [`TextureDescriptor::view_formats`](wgpu::TextureDescriptor::view_formats).
+///
+/// The first format supported by the image is used. If none are supported, the camera falls back to
+/// the image's own format.
+#[derive(Component, ExtractComponent, Clone, Default, Debug, PartialEq, Reflect)]
+#[reflect_value(Component, Default)]
+pub struct RenderTargetViewFormats(pub Vec<TextureFormat>);
+
+/// An error returned by [`RenderTargetViewFormats::validate`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderTargetViewFormatError {
+    #[error(
+        "view format {view_format:?} is not compatible with texture format {texture_format:?}"
+    )]
+    Incompatible {
+        texture_format: TextureFormat,
+        view_format: TextureFormat,
+    },
+    #[error("view format {0:?} is not listed in the image's `view_formats`")]
+    Missing(TextureFormat),
+}
+
+impl RenderTargetViewFormats {
+    /// Returns `true` if a texture with `texture_format` can be viewed as `view_format`.
+    ///
+    /// Formats are view-compatible if they only differ in their sRGB-ness.
+    pub fn is_view_compatible(texture_format: TextureFormat, view_format: TextureFormat) -> bool {
+        texture_format.remove_srgb_suffix() == view_format.remove_srgb_suffix()
+    }
+
+    /// Checks that every format is view-compatible with the `image` and listed in its
+    /// [`TextureDescriptor::view_formats`](wgpu::TextureDescriptor::view_formats).
+    pub fn validate(&self, image: &Image) -> Result<(), RenderTargetViewFormatError> {
+        self.validate_formats(image.texture_descriptor.format, |format| {
+            image.texture_descriptor.view_formats.contains(&format)
+        })
+    }
+
+    /// Like [`Self::validate`], for an image that has been prepared for rendering.
+    pub fn validate_gpu_image(&self, image: &GpuImage) -> Result<(), RenderTargetViewFormatError> {
+        self.validate_formats(image.texture_format, |format| {
+            image.format_view(format).is_some()
+        })
+    }
+
+    fn validate_formats(
+        &self,
+        texture_format: TextureFormat,
+        is_listed: impl Fn(TextureFormat) -> bool,
+    ) -> Result<(), RenderTargetViewFormatError> {
+        for &view_format in &self.0 {
+            if !Self::is_view_compatible(texture_format, view_format) {
+                return Err(RenderTargetViewFormatError::Incompatible {
+                    texture_format,
+                    view_format,
+                });
+            }
+            if view_format != texture_format && !is_listed(view_format) {
+                return Err(RenderTargetViewFormatError::Missing(view_format));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the first format that is view-compatible with `texture_format` and for which
+    /// `has_view` returns `true`.
+    pub fn first_supported(
+        &self,
+        texture_format: TextureFormat,
+        has_view: impl Fn(TextureFormat) -> bool,
+    ) -> Option<TextureFormat> {
+        self.0.iter().copied().find(|&view_format| {
+            Self::is_view_compatible(texture_format, view_format)
+                && (view_format == texture_format || has_view(view_format))
+        })
+    }
+}
+
 #[derive(Component, Debug)]
 pub struct ExtractedCamera {
     pub target: Option<NormalizedRenderTarget>,
@@ -826,6 +1076,15 @@ pub struct ExtractedCamera {
     pub hdr: bool,
 }
 
+/// System set containing [`extract_cameras`], which runs in the [`ExtractSchedule`].
+///
+/// Use this to order extraction systems that depend on [`ExtractedCamera`] or [`ExtractedView`]
+/// being present on camera entities in the render world.
+///
+/// [`ExtractSchedule`]: crate::ExtractSchedule
+#[derive(SystemSet, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CameraExtractSystems;
+
 pub fn extract_cameras(
     mut commands: Commands,
     query: Extract<
@@ -838,7 +1097,9 @@ pub fn extract_cameras(
             &Frustum,
             Option<&ColorGrading>,
             Option<&Exposure>,
+            Option<&ExposureCurve>,
             Option<&TemporalJitter>,
+            Option<&MipBias>,
             Option<&RenderLayers>,
             Option<&Projection>,
             Has<GpuCulling>,
@@ -846,8 +1107,10 @@ pub fn extract_cameras(
     >,
     primary_window: Extract<Query<Entity, With<PrimaryWindow>>>,
     gpu_preprocessing_support: Res<GpuPreprocessingSupport>,
+    time: Extract<Option<Res<Time>>>,
 ) {
     let primary_window = primary_window.iter().next();
+    let elapsed_seconds = time.as_ref().map(|time| time.elapsed_seconds());
     for (
         entity,
         camera,
@@ -857,7 +1120,9 @@ pub fn extract_cameras(
         frustum,
         color_grading,
         exposure,
+        exposure_curve,
         temporal_jitter,
+        mip_bias,
         render_layers,
         projection,
         gpu_culling,
@@ -900,8 +1165,10 @@ pub fn extract_cameras(
                     clear_color: camera.clear_color,
                     // this will be set in sort_cameras
                     sorted_camera_index_for_target: 0,
-                    exposure: exposure
-                        .map(|e| e.exposure())
+                    exposure: exposure_curve
+                        .zip(elapsed_seconds)
+                        .and_then(|(curve, elapsed_seconds)| curve.exposure_at(elapsed_seconds))
+                        .or_else(|| exposure.map(|e| e.exposure()))
                         .unwrap_or_else(|| Exposure::default().exposure()),
                     hdr: camera.hdr,
                 },
@@ -926,6 +1193,18 @@ pub fn extract_cameras(
                 commands.insert(temporal_jitter.clone());
             }
 
+            if let Some(mip_bias) = mip_bias {
+                if !mip_bias.is_valid() {
+                    warn_once!(
+                        "Camera has an out-of-range `MipBias` of {}; clamping it to {}..={}.",
+                        mip_bias.0,
+                        MipBias::MIN,
+                        MipBias::MAX
+                    );
+                }
+                commands.insert(MipBias::new(mip_bias.0));
+            }
+
             if let Some(render_layers) = render_layers {
                 commands.insert(render_layers.clone());
             }
@@ -956,19 +1235,32 @@ pub struct SortedCamera {
     pub order: isize,
     pub target: Option<NormalizedRenderTarget>,
     pub hdr: bool,
+    /// Whether the camera has [`AllowCameraOrderAmbiguity`].
+    pub allow_order_ambiguity: bool,
 }
 
+/// Marks a camera as intentionally sharing its [`Camera::order`] and render target with other
+/// active cameras, e.g. an overlay drawn on top of the main camera without a distinct order.
+///
+/// [`sort_cameras`] warns about cameras with the same order and target, since their draw order is
+/// unspecified. Cameras with this marker are left out of that check, so the warning only fires
+/// when at least two cameras without it collide.
+#[derive(Component, ExtractComponent, Clone, Copy, Default, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct AllowCameraOrderAmbiguity;
+
 pub fn sort_cameras(
     mut sorted_cameras: ResMut<SortedCameras>,
-    mut cameras: Query<(Entity, &mut ExtractedCamera)>,
+    mut cameras: Query<(Entity, &mut ExtractedCamera, Has<AllowCameraOrderAmbiguity>)>,
 ) {
     sorted_cameras.0.clear();
-    for (entity, camera) in cameras.iter() {
+    for (entity, camera, allow_order_ambiguity) in cameras.iter() {
         sorted_cameras.0.push(SortedCamera {
             entity,
             order: camera.order,
             target: camera.target.clone(),
             hdr: camera.hdr,
+            allow_order_ambiguity,
         });
     }
     // sort by order and ensure within an order, RenderTargets of the same type are packed together
@@ -978,27 +1270,19 @@ pub fn sort_cameras(
             std::cmp::Ordering::Equal => c1.target.cmp(&c2.target),
             ord => ord,
         });
-    let mut previous_order_target = None;
-    let mut ambiguities = HashSet::new();
     let mut target_counts = HashMap::new();
     for sorted_camera in &mut sorted_cameras.0 {
-        let new_order_target = (sorted_camera.order, sorted_camera.target.clone());
-        if let Some(previous_order_target) = previous_order_target {
-            if previous_order_target == new_order_target {
-                ambiguities.insert(new_order_target.clone());
-            }
-        }
         if let Some(target) = &sorted_camera.target {
             let count = target_counts
                 .entry((target.clone(), sorted_camera.hdr))
                 .or_insert(0usize);
-            let (_, mut camera) = cameras.get_mut(sorted_camera.entity).unwrap();
+            let (_, mut camera, _) = cameras.get_mut(sorted_camera.entity).unwrap();
             camera.sorted_camera_index_for_target = *count;
             *count += 1;
         }
-        previous_order_target = Some(new_order_target);
     }
 
+    let ambiguities = camera_order_ambiguities(&sorted_cameras.0);
     if !ambiguities.is_empty() {
         warn!(
             "Camera order ambiguities detected for active cameras with the following priorities: {:?}. \
@@ -1011,13 +1295,33 @@ pub fn sort_cameras(
     }
 }
 
-/// A subpixel offset to jitter a perspective camera's frustum by.
+/// Returns the orders and targets shared by more than one of the `sorted_cameras`, ignoring
+/// cameras with [`AllowCameraOrderAmbiguity`].
+fn camera_order_ambiguities(
+    sorted_cameras: &[SortedCamera],
+) -> HashSet<(isize, Option<NormalizedRenderTarget>)> {
+    let mut previous_order_target = None;
+    let mut ambiguities = HashSet::new();
+    // Cameras are sorted by order and target, so colliding cameras are next to each other even
+    // with allowed cameras in between.
+    for sorted_camera in sorted_cameras
+        .iter()
+        .filter(|sorted_camera| !sorted_camera.allow_order_ambiguity)
+    {
+        let new_order_target = (sorted_camera.order, sorted_camera.target.clone());
+        if let Some(previous_order_target) = previous_order_target {
+            if previous_order_target == new_order_target {
+                ambiguities.insert(new_order_target.clone());
+            }
+        }
+        previous_order_target = Some(new_order_target);
+    }
+    ambiguities
+}
+
+/// A subpixel offset to jitter a camera's frustum by.
 ///
 /// Useful for temporal rendering techniques.
-///
-/// Do not use with [`OrthographicProjection`].
-///
-/// [`OrthographicProjection`]: crate::camera::OrthographicProjection
 #[derive(Component, Clone, Default, Reflect)]
 #[reflect(Default, Component)]
 pub struct TemporalJitter {
@@ -1026,25 +1330,677 @@ pub struct TemporalJitter {
 }
 
 impl TemporalJitter {
+    /// The number of samples in the sequence used by [`TemporalJitter::halton`].
+    pub const HALTON_SAMPLE_COUNT: u32 = 8;
+
+    /// Returns the jitter for the given frame, following the 8-sample Halton (2, 3) sequence
+    /// commonly used for temporal antialiasing.
+    ///
+    /// The sequence skips its first element (which is always zero) and is centered so the offset
+    /// is in range [-0.5, 0.5]. It repeats every [`TemporalJitter::HALTON_SAMPLE_COUNT`] frames.
+    pub fn halton(frame_index: u32) -> Self {
+        Self {
+            offset: TemporalJitterSequence::default().sample(frame_index),
+        }
+    }
+
+    /// Jitters `clip_from_view` by [`offset`](Self::offset) pixels of a view of `view_size` pixels.
     pub fn jitter_projection(&self, clip_from_view: &mut Mat4, view_size: Vec2) {
+        self.jitter_sub_view_projection(clip_from_view, view_size, None);
+    }
+
+    /// Jitters `clip_from_view` by [`offset`](Self::offset) pixels, where `clip_from_view` may
+    /// only cover the `sub_view` rectangle of a larger full view, e.g. one tile of a split-screen
+    /// or tiled render.
+    ///
+    /// `sub_view` is given in pixels of the full view, and the offset is applied in those pixels
+    /// so that every sub view of the same full view is jittered by the same amount. Since the
+    /// jitter is a uniform translation, only the size of `sub_view` affects the result. When
+    /// `sub_view` is `None`, `clip_from_view` covers the whole view of `view_size` pixels.
+    pub fn jitter_sub_view_projection(
+        &self,
+        clip_from_view: &mut Mat4,
+        view_size: Vec2,
+        sub_view: Option<Rect>,
+    ) {
+        let pixel_size = sub_view.map_or(view_size, |sub_view| sub_view.size());
+
+        // https://github.com/GPUOpen-LibrariesAndSDKs/FidelityFX-SDK/blob/d7531ae47d8b36a5d4025663e731a47a38be882f/docs/techniques/media/super-resolution-temporal/jitter-space.svg
+        let jitter = (self.offset * vec2(2.0, -2.0)) / pixel_size;
+
         if clip_from_view.w_axis.w == 1.0 {
-            warn!(
-                "TemporalJitter not supported with OrthographicProjection. Use PerspectiveProjection instead."
-            );
+            // Orthographic projections have `w == 1`, so translate clip space directly, matching
+            // the shift of the perspective path below after the divide by `w == -z`.
+            clip_from_view.w_axis.x -= jitter.x;
+            clip_from_view.w_axis.y -= jitter.y;
             return;
         }
 
-        // https://github.com/GPUOpen-LibrariesAndSDKs/FidelityFX-SDK/blob/d7531ae47d8b36a5d4025663e731a47a38be882f/docs/techniques/media/super-resolution-temporal/jitter-space.svg
-        let jitter = (self.offset * vec2(2.0, -2.0)) / view_size;
-
         clip_from_view.z_axis.x += jitter.x;
         clip_from_view.z_axis.y += jitter.y;
     }
 }
 
+/// A repeating Halton sequence of subpixel offsets, written to the [`TemporalJitter`] on the same
+/// entity each frame by [`advance_temporal_jitter_sequences`].
+///
+/// Useful for temporal effects in both 2D and 3D that don't drive the jitter themselves.
+/// Note that temporal antialiasing overwrites the jitter of its cameras in the render world.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Default, Component)]
+pub struct TemporalJitterSequence {
+    /// The bases of the Halton sequence for the x and y axes. Values below 2 are treated as 2.
+    pub bases: UVec2,
+    /// The number of samples before the sequence repeats. A length of 0 is treated as 1.
+    pub length: u32,
+    /// The index of the next sample to write.
+    pub index: u32,
+}
+
+impl Default for TemporalJitterSequence {
+    /// The 8-sample Halton (2, 3) sequence, see [`TemporalJitter::halton`].
+    fn default() -> Self {
+        Self {
+            bases: UVec2::new(2, 3),
+            length: TemporalJitter::HALTON_SAMPLE_COUNT,
+            index: 0,
+        }
+    }
+}
+
+impl TemporalJitterSequence {
+    /// Returns the offset at `index` in the sequence, in range [-0.5, 0.5].
+    ///
+    /// The first element of the Halton sequence (which is always zero) is skipped.
+    pub fn sample(&self, index: u32) -> Vec2 {
+        let index = index % self.length.max(1) + 1;
+        let bases = self.bases.max(UVec2::splat(2));
+        Vec2::new(
+            radical_inverse(index, bases.x),
+            radical_inverse(index, bases.y),
+        ) - 0.5
+    }
+
+    /// Returns the next offset in the sequence and advances it.
+    pub fn advance(&mut self) -> Vec2 {
+        let offset = self.sample(self.index);
+        self.index = (self.index + 1) % self.length.max(1);
+        offset
+    }
+}
+
+/// Advances each [`TemporalJitterSequence`] and writes the result to its [`TemporalJitter`].
+pub fn advance_temporal_jitter_sequences(
+    mut query: Query<(&mut TemporalJitterSequence, &mut TemporalJitter)>,
+) {
+    for (mut sequence, mut jitter) in &mut query {
+        jitter.offset = sequence.advance();
+    }
+}
+
+/// Returns the element at `index` of the Halton sequence of the given `base`, in range [0, 1).
+fn radical_inverse(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0 / base as f32;
+    while index > 0 {
+        result += (index % base) as f32 * fraction;
+        index /= base;
+        fraction /= base as f32;
+    }
+    result
+}
+
 /// Camera component specifying a mip bias to apply when sampling from material textures.
 ///
 /// Often used in conjunction with antialiasing post-process effects to reduce textures blurriness.
-#[derive(Default, Component, Reflect)]
+/// Negative values select sharper mip levels, which compensates for the blur introduced by temporal
+/// antialiasing; TAA adds a `MipBias(-1.0)` to cameras that don't already have one. Positive values
+/// select blurrier mip levels.
+///
+/// Values outside of [`MipBias::MIN`]`..=`[`MipBias::MAX`] produce undefined sampling results, so
+/// prefer [`MipBias::new`], which clamps the bias. Out-of-range values are clamped during extraction.
+#[derive(Default, Component, Reflect, Clone, Copy, Debug, PartialEq)]
 #[reflect(Default, Component)]
 pub struct MipBias(pub f32);
+
+impl MipBias {
+    /// The smallest supported mip bias.
+    pub const MIN: f32 = -16.0;
+    /// The largest supported mip bias.
+    pub const MAX: f32 = 16.0;
+
+    /// Creates a new [`MipBias`], clamping `bias` to [`MipBias::MIN`]`..=`[`MipBias::MAX`].
+    ///
+    /// A `NaN` bias is replaced with `0.0`.
+    pub fn new(bias: f32) -> Self {
+        if bias.is_nan() {
+            return Self(0.0);
+        }
+        Self(bias.clamp(Self::MIN, Self::MAX))
+    }
+
+    /// Returns `true` if this bias is within [`MipBias::MIN`]`..=`[`MipBias::MAX`].
+    pub fn is_valid(&self) -> bool {
+        (Self::MIN..=Self::MAX).contains(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::{OrthographicProjection, PerspectiveProjection};
+    use bevy_ecs::{
+        schedule::{IntoSystemConfigs, Schedule},
+        world::World,
+    };
+
+    #[derive(Resource, Default)]
+    struct ExtractOrder(Vec<&'static str>);
+
+    #[test]
+    fn systems_run_after_camera_extract_systems() {
+        let mut world = World::new();
+        world.init_resource::<ExtractOrder>();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((
+            (|mut order: ResMut<ExtractOrder>| order.0.push("user")).after(CameraExtractSystems),
+            (|mut order: ResMut<ExtractOrder>| order.0.push("cameras"))
+                .in_set(CameraExtractSystems),
+        ));
+        schedule.run(&mut world);
+
+        assert_eq!(world.resource::<ExtractOrder>().0, vec!["cameras", "user"]);
+    }
+
+    #[test]
+    fn mip_level_size_halves_and_clamps() {
+        let size = UVec2::new(256, 64);
+        assert_eq!(mip_level_size(size, 0), size);
+        assert_eq!(mip_level_size(size, 1), UVec2::new(128, 32));
+        assert_eq!(mip_level_size(size, 7), UVec2::new(2, 1));
+        assert_eq!(mip_level_size(size, 40), UVec2::ONE);
+    }
+
+    #[test]
+    fn aspect_ratio_of_image_targets() {
+        use wgpu::Extent3d;
+
+        let image = |width, height| {
+            let mut image = Image::default();
+            image.resize(Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            });
+            image
+        };
+        let mut images = Assets::<Image>::default();
+        let wide = NormalizedRenderTarget::Image(images.add(image(4, 2)));
+        let empty = NormalizedRenderTarget::Image(images.add(image(0, 0)));
+        let manual_texture_views = ManualTextureViews::default();
+
+        assert_eq!(
+            wide.aspect_ratio([], &images, &manual_texture_views),
+            Some(2.0)
+        );
+        assert_eq!(empty.aspect_ratio([], &images, &manual_texture_views), None);
+    }
+
+    #[test]
+    fn image_mip_level_target_is_validated() {
+        use wgpu::Extent3d;
+
+        let mut image = Image::default();
+        image.resize(Extent3d {
+            width: 64,
+            height: 32,
+            depth_or_array_layers: 1,
+        });
+        image.texture_descriptor.mip_level_count = 2;
+        let mut images = Assets::<Image>::default();
+        let handle = images.add(image);
+        let manual_texture_views = ManualTextureViews::default();
+
+        let info = NormalizedRenderTarget::ImageMipLevel(handle.clone(), 1)
+            .get_render_target_info([], &images, &manual_texture_views)
+            .unwrap();
+        assert_eq!(info.physical_size, UVec2::new(32, 16));
+        assert!(NormalizedRenderTarget::ImageMipLevel(handle, 2)
+            .get_render_target_info([], &images, &manual_texture_views)
+            .is_none());
+    }
+
+    #[test]
+    fn mip_bias_new_clamps() {
+        assert_eq!(MipBias::new(-1.0), MipBias(-1.0));
+        assert_eq!(MipBias::new(100.0), MipBias(MipBias::MAX));
+        assert_eq!(MipBias::new(f32::NEG_INFINITY), MipBias(MipBias::MIN));
+        assert_eq!(MipBias::new(f32::NAN), MipBias(0.0));
+        assert!(!MipBias(f32::NAN).is_valid());
+        assert!(MipBias(MipBias::MAX).is_valid());
+    }
+
+    #[test]
+    fn image_target_srgb_view_format() {
+        use crate::render_asset::RenderAssetUsages;
+        use wgpu::{Extent3d, TextureDimension};
+
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.view_formats = &[TextureFormat::Rgba8UnormSrgb];
+
+        let view_formats = RenderTargetViewFormats(vec![TextureFormat::Rgba8UnormSrgb]);
+        assert_eq!(view_formats.validate(&image), Ok(()));
+        assert_eq!(
+            view_formats.first_supported(image.texture_descriptor.format, |format| {
+                image.texture_descriptor.view_formats.contains(&format)
+            }),
+            Some(TextureFormat::Rgba8UnormSrgb)
+        );
+
+        let incompatible = RenderTargetViewFormats(vec![TextureFormat::Bgra8UnormSrgb]);
+        assert_eq!(
+            incompatible.validate(&image),
+            Err(RenderTargetViewFormatError::Incompatible {
+                texture_format: TextureFormat::Rgba8Unorm,
+                view_format: TextureFormat::Bgra8UnormSrgb,
+            })
+        );
+        assert_eq!(
+            incompatible.first_supported(TextureFormat::Rgba8Unorm, |_| true),
+            None
+        );
+
+        image.texture_descriptor.view_formats = &[];
+        assert_eq!(
+            view_formats.validate(&image),
+            Err(RenderTargetViewFormatError::Missing(
+                TextureFormat::Rgba8UnormSrgb
+            ))
+        );
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn image_target_view_format_is_selected() {
+        let (device, _, _) = crate::renderer::test_device();
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[TextureFormat::Rgba8UnormSrgb],
+        });
+        let srgb_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(TextureFormat::Rgba8UnormSrgb),
+            ..Default::default()
+        });
+        let gpu_image = GpuImage {
+            texture_view: texture.create_view(&Default::default()),
+            texture,
+            texture_format: TextureFormat::Rgba8Unorm,
+            sampler: device.create_sampler(&Default::default()),
+            size: UVec2::splat(4),
+            mip_level_count: 1,
+            mip_level_views: Vec::new(),
+            format_views: vec![(TextureFormat::Rgba8UnormSrgb, srgb_view)],
+            format_mip_level_views: Vec::new(),
+        };
+        let handle = Handle::<Image>::weak_from_u128(0x5e1ec7ed);
+        let mut images = RenderAssets::<GpuImage>::default();
+        images.insert(&handle, gpu_image);
+        let target = NormalizedRenderTarget::Image(handle.clone());
+
+        let srgb = RenderTargetViewFormats(vec![
+            TextureFormat::Bgra8UnormSrgb,
+            TextureFormat::Rgba8UnormSrgb,
+        ]);
+        assert_eq!(
+            target.get_texture_view_format(&images, &srgb),
+            Some(TextureFormat::Rgba8UnormSrgb)
+        );
+        assert_eq!(
+            srgb.validate_gpu_image(images.get(&handle).unwrap()),
+            Err(RenderTargetViewFormatError::Incompatible {
+                texture_format: TextureFormat::Rgba8Unorm,
+                view_format: TextureFormat::Bgra8UnormSrgb,
+            })
+        );
+
+        // The image's own format is always supported.
+        let own = RenderTargetViewFormats(vec![TextureFormat::Rgba8Unorm]);
+        assert_eq!(
+            target.get_texture_view_format(&images, &own),
+            Some(TextureFormat::Rgba8Unorm)
+        );
+        let incompatible = RenderTargetViewFormats(vec![TextureFormat::Bgra8UnormSrgb]);
+        assert_eq!(target.get_texture_view_format(&images, &incompatible), None);
+    }
+
+    #[test]
+    fn temporal_jitter_halton() {
+        assert!(TemporalJitter::halton(0)
+            .offset
+            .abs_diff_eq(Vec2::new(0.0, -1.0 / 6.0), 1e-6));
+        assert!(TemporalJitter::halton(1)
+            .offset
+            .abs_diff_eq(Vec2::new(-0.25, 1.0 / 6.0), 1e-6));
+
+        for frame in 0..2 * TemporalJitter::HALTON_SAMPLE_COUNT {
+            let offset = TemporalJitter::halton(frame).offset;
+            assert!(offset.abs().cmple(Vec2::splat(0.5)).all());
+            assert_eq!(
+                offset,
+                TemporalJitter::halton(frame + TemporalJitter::HALTON_SAMPLE_COUNT).offset
+            );
+        }
+    }
+
+    /// Projects the view-space `point` with `clip_from_view` into normalized device coordinates.
+    fn project_ndc(clip_from_view: Mat4, point: Vec3) -> Vec2 {
+        let clip = clip_from_view * point.extend(1.0);
+        clip.truncate().truncate() / clip.w
+    }
+
+    #[test]
+    fn temporal_jitter_projection() {
+        let jitter = TemporalJitter {
+            offset: Vec2::new(0.25, -0.5),
+        };
+        let view_size = Vec2::new(800.0, 600.0);
+        let point = Vec3::new(1.0, 2.0, -5.0);
+        // Half a pixel of jitter moves NDC by one pixel's worth of the [-1, 1] range.
+        let expected_shift = Vec2::new(-0.5 / 800.0, -1.0 / 600.0);
+
+        let perspective = Mat4::perspective_infinite_reverse_rh(1.0, 800.0 / 600.0, 0.1);
+        let mut jittered = perspective;
+        jitter.jitter_projection(&mut jittered, view_size);
+        assert_eq!(jittered.w_axis, perspective.w_axis);
+        assert!(
+            (project_ndc(jittered, point) - project_ndc(perspective, point))
+                .abs_diff_eq(expected_shift, 1e-6)
+        );
+
+        let orthographic = Mat4::orthographic_rh(-4.0, 4.0, -3.0, 3.0, 0.0, 100.0);
+        let mut jittered = orthographic;
+        jitter.jitter_projection(&mut jittered, view_size);
+        assert_eq!(jittered.z_axis, orthographic.z_axis);
+        assert!(
+            (project_ndc(jittered, point) - project_ndc(orthographic, point))
+                .abs_diff_eq(expected_shift, 1e-6)
+        );
+    }
+
+    #[test]
+    fn temporal_jitter_sub_view_projection() {
+        let jitter = TemporalJitter {
+            offset: Vec2::new(0.5, 0.5),
+        };
+        let view_size = Vec2::new(800.0, 600.0);
+        let point = Vec3::new(1.0, 2.0, -5.0);
+
+        for clip_from_view in [
+            Mat4::perspective_infinite_reverse_rh(1.0, 800.0 / 600.0, 0.1),
+            Mat4::orthographic_rh(-4.0, 4.0, -3.0, 3.0, 0.0, 100.0),
+        ] {
+            let mut full = clip_from_view;
+            jitter.jitter_sub_view_projection(&mut full, view_size, None);
+            let full_shift = project_ndc(full, point) - project_ndc(clip_from_view, point);
+
+            // A sub view covering the full view is the same as no sub view.
+            let mut whole = clip_from_view;
+            jitter.jitter_sub_view_projection(
+                &mut whole,
+                view_size,
+                Some(Rect::from_corners(Vec2::ZERO, view_size)),
+            );
+            assert_eq!(whole, full);
+
+            // The right half of a view twice as wide, rendered at `view_size`: a pixel of the full
+            // view is half a pixel of the sub view, so the NDC shift of the sub view halves along x.
+            let mut sub = clip_from_view;
+            jitter.jitter_sub_view_projection(
+                &mut sub,
+                view_size,
+                Some(Rect::new(1600.0, 0.0, 3200.0, 600.0)),
+            );
+            let sub_shift = project_ndc(sub, point) - project_ndc(clip_from_view, point);
+            assert!(sub_shift.abs_diff_eq(full_shift * Vec2::new(0.5, 1.0), 1e-6));
+
+            // Only the size of the sub view matters, not its position.
+            let mut moved = clip_from_view;
+            jitter.jitter_sub_view_projection(
+                &mut moved,
+                view_size,
+                Some(Rect::new(0.0, 0.0, 1600.0, 600.0)),
+            );
+            assert_eq!(moved, sub);
+        }
+    }
+
+    /// A camera with the given projection, targeting a 800x600 target with a scale factor of 2.
+    fn camera_with_projection(mut projection: impl CameraProjection) -> Camera {
+        projection.update(400.0, 300.0);
+        let mut camera = Camera::default();
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(800, 600),
+            scale_factor: 2.0,
+        });
+        camera
+    }
+
+    #[test]
+    fn viewport_to_world_perspective() {
+        let camera = camera_with_projection(PerspectiveProjection::default());
+        let transform = GlobalTransform::from_xyz(1.0, 2.0, 3.0);
+        let center = Vec2::new(200.0, 150.0);
+
+        let ray = camera.viewport_to_world(&transform, center).unwrap();
+        assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!(ray.origin.abs_diff_eq(Vec3::new(1.0, 2.0, 2.9), 1e-5));
+
+        // Rays fan out from the camera and project back to where they were cast from.
+        let corner = Vec2::new(0.0, 0.0);
+        let ray = camera.viewport_to_world(&transform, corner).unwrap();
+        assert!(ray.direction.x < 0.0 && ray.direction.y > 0.0);
+        let viewport_position = camera
+            .world_to_viewport(&transform, ray.get_point(10.0))
+            .unwrap();
+        assert!(viewport_position.abs_diff_eq(corner, 1e-2));
+    }
+
+    #[test]
+    fn viewport_to_world_orthographic() {
+        let camera = camera_with_projection(OrthographicProjection::default());
+        let transform = GlobalTransform::from_xyz(1.0, 2.0, 3.0);
+
+        let ray = camera
+            .viewport_to_world(&transform, Vec2::new(200.0, 150.0))
+            .unwrap();
+        assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!(ray.origin.truncate().abs_diff_eq(Vec2::new(1.0, 2.0), 1e-5));
+
+        // All rays are parallel, offset by the logical viewport position.
+        let ray = camera
+            .viewport_to_world(&transform, Vec2::new(0.0, 0.0))
+            .unwrap();
+        assert!(ray.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!(ray
+            .origin
+            .truncate()
+            .abs_diff_eq(Vec2::new(1.0 - 200.0, 2.0 + 150.0), 1e-3));
+    }
+
+    #[test]
+    fn temporal_jitter_sequence() {
+        let mut sequence = TemporalJitterSequence::default();
+        // Halton (2, 3) starting from its second element.
+        let expected = [
+            Vec2::new(1.0 / 2.0, 1.0 / 3.0),
+            Vec2::new(1.0 / 4.0, 2.0 / 3.0),
+            Vec2::new(3.0 / 4.0, 1.0 / 9.0),
+            Vec2::new(1.0 / 8.0, 4.0 / 9.0),
+            Vec2::new(5.0 / 8.0, 7.0 / 9.0),
+        ];
+        for expected in expected {
+            assert!(sequence.advance().abs_diff_eq(expected - 0.5, 1e-6));
+        }
+
+        let mut sequence = TemporalJitterSequence {
+            bases: UVec2::new(3, 5),
+            length: 2,
+            index: 0,
+        };
+        let first = sequence.advance();
+        assert!(first.abs_diff_eq(Vec2::new(1.0 / 3.0, 1.0 / 5.0) - 0.5, 1e-6));
+        assert!(sequence
+            .advance()
+            .abs_diff_eq(Vec2::new(2.0 / 3.0, 2.0 / 5.0) - 0.5, 1e-6));
+        assert_eq!(sequence.index, 0);
+        assert_eq!(sequence.advance(), first);
+    }
+
+    #[test]
+    fn temporal_jitter_sequence_system() {
+        let mut world = World::new();
+        let entity = world
+            .spawn((TemporalJitter::default(), TemporalJitterSequence::default()))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(advance_temporal_jitter_sequences);
+        for frame in 0..3 {
+            schedule.run(&mut world);
+            assert_eq!(
+                world.get::<TemporalJitter>(entity).unwrap().offset,
+                TemporalJitter::halton(frame).offset
+            );
+        }
+    }
+
+    fn viewport(size: UVec2) -> Viewport {
+        Viewport {
+            physical_position: UVec2::new(7, 3),
+            physical_size: size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn viewport_clamp_to_size() {
+        let mut v = viewport(UVec2::new(1920, 1080));
+        v.clamp_to_size(UVec2::new(1280, 1024));
+        assert_eq!(v.physical_size, UVec2::new(1280, 1024));
+        assert_eq!(v.physical_position, UVec2::new(7, 3));
+    }
+
+    #[test]
+    fn viewport_clamp_to_size_keep_aspect() {
+        // Already fits: untouched.
+        let mut v = viewport(UVec2::new(800, 600));
+        v.clamp_to_size_keep_aspect(UVec2::new(1920, 1080));
+        assert_eq!(v.physical_size, UVec2::new(800, 600));
+        assert_eq!(v.physical_position, UVec2::new(7, 3));
+
+        // Width-limited: shrinks by half and is centered vertically.
+        let mut v = viewport(UVec2::new(1920, 1080));
+        v.clamp_to_size_keep_aspect(UVec2::new(960, 1080));
+        assert_eq!(v.physical_size, UVec2::new(960, 540));
+        assert_eq!(v.physical_position, UVec2::new(0, 270));
+
+        // Height-limited: shrinks by half and is centered horizontally.
+        let mut v = viewport(UVec2::new(1920, 1080));
+        v.clamp_to_size_keep_aspect(UVec2::new(1920, 540));
+        assert_eq!(v.physical_size, UVec2::new(960, 540));
+        assert_eq!(v.physical_position, UVec2::new(480, 0));
+
+        // Both axes too large: the most constrained axis wins.
+        let mut v = viewport(UVec2::new(1000, 1000));
+        v.clamp_to_size_keep_aspect(UVec2::new(800, 500));
+        assert_eq!(v.physical_size, UVec2::new(500, 500));
+        assert_eq!(v.physical_position, UVec2::new(150, 0));
+    }
+
+    #[test]
+    fn exposure_curve_is_held_outside_its_domain() {
+        use bevy_math::cubic_splines::{CubicBezier, CubicGenerator};
+
+        let curve = ExposureCurve::new(
+            CubicBezier::new([[Exposure::EV100_BLENDER, 9.0, 13.0, Exposure::EV100_SUNLIGHT]])
+                .to_curve(),
+            2.0,
+        );
+        // Inverts `Exposure::exposure` to compare in EV100, where the values are well scaled.
+        let ev100_at =
+            |elapsed_seconds| -(curve.exposure_at(elapsed_seconds).unwrap() * 1.2).log2();
+        let assert_ev100 = |elapsed_seconds, expected: f32| {
+            let ev100 = ev100_at(elapsed_seconds);
+            assert!(
+                (ev100 - expected).abs() < 1e-4,
+                "expected {expected} at {elapsed_seconds}s, got {ev100}"
+            );
+        };
+
+        assert_ev100(0.0, Exposure::EV100_BLENDER);
+        assert_ev100(2.0, Exposure::EV100_BLENDER);
+        assert_ev100(3.0, Exposure::EV100_SUNLIGHT);
+        assert_ev100(10.0, Exposure::EV100_SUNLIGHT);
+        assert_ev100(2.5, 11.3375);
+
+        let empty = ExposureCurve::new(
+            CubicCurve {
+                segments: Vec::new(),
+            },
+            0.0,
+        );
+        assert_eq!(empty.exposure_at(1.0), None);
+    }
+
+    #[test]
+    fn allowed_cameras_are_not_order_ambiguities() {
+        let camera = |index, order, allow_order_ambiguity| SortedCamera {
+            entity: Entity::from_raw(index),
+            order,
+            target: Some(NormalizedRenderTarget::Image(Handle::default())),
+            hdr: false,
+            allow_order_ambiguity,
+        };
+        let ambiguous = (0, Some(NormalizedRenderTarget::Image(Handle::default())));
+
+        // An overlay sharing the main camera's order.
+        assert!(camera_order_ambiguities(&[camera(0, 0, false), camera(1, 0, true)]).is_empty());
+        assert!(camera_order_ambiguities(&[camera(0, 0, true), camera(1, 0, true)]).is_empty());
+        assert_eq!(
+            camera_order_ambiguities(&[camera(0, 0, false), camera(1, 0, false)]),
+            HashSet::from_iter([ambiguous.clone()])
+        );
+        // An allowed camera sorted in between doesn't hide an accidental collision.
+        assert_eq!(
+            camera_order_ambiguities(&[
+                camera(0, 0, false),
+                camera(1, 0, true),
+                camera(2, 0, false),
+                camera(3, 1, false),
+            ]),
+            HashSet::from_iter([ambiguous])
+        );
+    }
+}
@@ -1,8 +1,9 @@
-use crate::extract_resource::ExtractResource;
+use crate::{camera::NormalizedRenderTarget, extract_resource::ExtractResource};
 use bevy_color::Color;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::prelude::*;
 use bevy_reflect::prelude::*;
+use bevy_utils::HashMap;
 use serde::{Deserialize, Serialize};
 
 /// For a camera, specifies the color used to clear the viewport before rendering.
@@ -40,3 +41,80 @@ impl Default for ClearColor {
         Self(Color::srgb_u8(43, 44, 47))
     }
 }
+
+/// A [`Resource`] that overrides the clear color of specific render targets.
+///
+/// When several cameras render to the same target, the color set here is used to clear it for the
+/// first camera (by [`Camera::order`](super::Camera::order)) rendering to that target; later cameras
+/// are unaffected.
+///
+/// The clear color of a camera is chosen with the following precedence:
+/// 1. The override for its target in this resource, if it is the first camera on that target.
+/// 2. Its [`ClearColorConfig`], where [`ClearColorConfig::None`] disables clearing.
+/// 3. The global [`ClearColor`] resource, for [`ClearColorConfig::Default`].
+#[derive(Resource, Clone, Debug, Default, Deref, DerefMut, ExtractResource)]
+pub struct RenderTargetClearColor(pub HashMap<NormalizedRenderTarget, Color>);
+
+impl RenderTargetClearColor {
+    /// Returns the color a camera should clear its target with, or `None` if it shouldn't clear.
+    ///
+    /// See [`RenderTargetClearColor`] for the precedence rules.
+    pub fn resolve(
+        &self,
+        target: &NormalizedRenderTarget,
+        sorted_camera_index_for_target: usize,
+        clear_color_config: ClearColorConfig,
+        clear_color_global: &ClearColor,
+    ) -> Option<Color> {
+        if sorted_camera_index_for_target == 0 {
+            if let Some(color) = self.get(target) {
+                return Some(*color);
+            }
+        }
+        match clear_color_config {
+            ClearColorConfig::Custom(color) => Some(color),
+            ClearColorConfig::None => None,
+            ClearColorConfig::Default => Some(clear_color_global.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::ManualTextureViewHandle;
+
+    #[test]
+    fn render_target_clear_color_precedence() {
+        let target = NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0));
+        let global = ClearColor(Color::BLACK);
+        let custom = ClearColorConfig::Custom(Color::WHITE);
+
+        let mut overrides = RenderTargetClearColor::default();
+        assert_eq!(
+            overrides.resolve(&target, 0, ClearColorConfig::Default, &global),
+            Some(Color::BLACK)
+        );
+        assert_eq!(
+            overrides.resolve(&target, 0, custom, &global),
+            Some(Color::WHITE)
+        );
+
+        let red = Color::srgb(1.0, 0.0, 0.0);
+        overrides.insert(target.clone(), red);
+        assert_eq!(overrides.resolve(&target, 0, custom, &global), Some(red));
+        assert_eq!(
+            overrides.resolve(&target, 0, ClearColorConfig::None, &global),
+            Some(red)
+        );
+        // Only the first camera on the target uses the override.
+        assert_eq!(
+            overrides.resolve(&target, 1, custom, &global),
+            Some(Color::WHITE)
+        );
+        assert_eq!(
+            overrides.resolve(&target, 1, ClearColorConfig::None, &global),
+            None
+        );
+    }
+}
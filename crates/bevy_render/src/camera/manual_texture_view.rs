@@ -1,7 +1,8 @@
 use crate::extract_resource::ExtractResource;
 use crate::render_resource::TextureView;
 use crate::texture::BevyDefault;
-use bevy_ecs::system::Resource;
+use bevy_ecs::event::{Event, Events};
+use bevy_ecs::system::{ResMut, Resource};
 use bevy_ecs::{prelude::Component, reflect::ReflectComponent};
 use bevy_math::UVec2;
 use bevy_reflect::prelude::*;
@@ -18,7 +19,17 @@ pub struct ManualTextureViewHandle(pub u32);
 pub struct ManualTextureView {
     pub texture_view: TextureView,
     pub size: UVec2,
+    /// The format of [`Self::texture_view`].
     pub format: TextureFormat,
+    /// The format the pipelines rendering into this view are specialized for, if it isn't
+    /// [`Self::format`] with an sRGB suffix added.
+    ///
+    /// Render pipelines must match the format of the attachment they render into, so this must be
+    /// the actual format of [`Self::texture_view`], otherwise rendering into the view fails
+    /// validation. Setting it doesn't reinterpret the texture: to render into e.g. an
+    /// `Rgba8Unorm` texture as `Rgba8UnormSrgb`, create [`Self::texture_view`] with that format,
+    /// which must be listed in the texture's `view_formats`, and set this to match it.
+    pub render_format: Option<TextureFormat>,
 }
 
 impl ManualTextureView {
@@ -27,24 +38,150 @@ impl ManualTextureView {
             texture_view,
             size,
             format: TextureFormat::bevy_default(),
+            render_format: None,
         }
     }
+
+    /// Sets [`Self::render_format`].
+    pub fn with_render_format(mut self, render_format: TextureFormat) -> Self {
+        self.render_format = Some(render_format);
+        self
+    }
+
+    /// The format cameras rendering into this view output to: [`Self::render_format`] if set,
+    /// otherwise [`Self::format`] with an sRGB suffix added where one exists.
+    pub fn output_format(&self) -> TextureFormat {
+        self.render_format
+            .unwrap_or_else(|| self.format.add_srgb_suffix())
+    }
+}
+
+/// An event that is sent whenever a [`ManualTextureView`] is added to or removed from
+/// [`ManualTextureViews`] through [`ManualTextureViews::insert`] or [`ManualTextureViews::remove`].
+///
+/// Mutating the underlying map directly will not send these events.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManualTextureViewEvent {
+    /// A view was inserted for the given handle, possibly replacing an existing one.
+    Added(ManualTextureViewHandle),
+    /// The view for the given handle was removed.
+    Removed(ManualTextureViewHandle),
 }
 
 /// Stores manually managed [`ManualTextureView`]s for use as a [`crate::camera::RenderTarget`].
 #[derive(Default, Clone, Resource, ExtractResource)]
-pub struct ManualTextureViews(HashMap<ManualTextureViewHandle, ManualTextureView>);
+pub struct ManualTextureViews {
+    views: HashMap<ManualTextureViewHandle, ManualTextureView>,
+    pending_events: Vec<ManualTextureViewEvent>,
+}
+
+impl ManualTextureViews {
+    /// Inserts a [`ManualTextureView`] for the given handle, returning the previous view if there was one.
+    ///
+    /// Sends a [`ManualTextureViewEvent::Added`] event.
+    pub fn insert(
+        &mut self,
+        handle: ManualTextureViewHandle,
+        view: ManualTextureView,
+    ) -> Option<ManualTextureView> {
+        self.pending_events
+            .push(ManualTextureViewEvent::Added(handle));
+        self.views.insert(handle, view)
+    }
+
+    /// Removes the [`ManualTextureView`] for the given handle, returning it if it existed.
+    ///
+    /// Sends a [`ManualTextureViewEvent::Removed`] event if a view was removed.
+    pub fn remove(&mut self, handle: &ManualTextureViewHandle) -> Option<ManualTextureView> {
+        let removed = self.views.remove(handle);
+        if removed.is_some() {
+            self.pending_events
+                .push(ManualTextureViewEvent::Removed(*handle));
+        }
+        removed
+    }
+}
 
 impl std::ops::Deref for ManualTextureViews {
     type Target = HashMap<ManualTextureViewHandle, ManualTextureView>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.views
     }
 }
 
 impl std::ops::DerefMut for ManualTextureViews {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.views
+    }
+}
+
+/// Sends the [`ManualTextureViewEvent`]s queued by [`ManualTextureViews::insert`] and
+/// [`ManualTextureViews::remove`], in the order the mutations happened.
+pub fn send_manual_texture_view_events(
+    mut manual_texture_views: ResMut<ManualTextureViews>,
+    mut events: ResMut<Events<ManualTextureViewEvent>>,
+) {
+    if manual_texture_views.pending_events.is_empty() {
+        return;
+    }
+    // Bypass change detection so draining the queue doesn't trigger a re-extraction.
+    let manual_texture_views = manual_texture_views.bypass_change_detection();
+    events.extend(manual_texture_views.pending_events.drain(..));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{event::ManualEventReader, system::RunSystemOnce, world::World};
+
+    /// Creates a 1x1 [`ManualTextureView`].
+    fn test_view() -> ManualTextureView {
+        let (device, _, _) = crate::renderer::test_device();
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::bevy_default(),
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        ManualTextureView::with_default_format(view.into(), UVec2::ONE)
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn insert_and_remove_send_events_in_order() {
+        let view = test_view();
+
+        let mut world = World::new();
+        world.init_resource::<ManualTextureViews>();
+        world.init_resource::<Events<ManualTextureViewEvent>>();
+
+        let handle = ManualTextureViewHandle(7);
+        let mut views = world.resource_mut::<ManualTextureViews>();
+        assert!(views.insert(handle, view).is_none());
+        assert!(views.remove(&handle).is_some());
+        // Removing a handle that has no view must not send an event.
+        assert!(views.remove(&ManualTextureViewHandle(8)).is_none());
+
+        world.run_system_once(send_manual_texture_view_events);
+
+        let events = world.resource::<Events<ManualTextureViewEvent>>();
+        let mut reader = ManualEventReader::<ManualTextureViewEvent>::default();
+        assert_eq!(
+            reader.read(events).copied().collect::<Vec<_>>(),
+            vec![
+                ManualTextureViewEvent::Added(handle),
+                ManualTextureViewEvent::Removed(handle),
+            ]
+        );
     }
 }
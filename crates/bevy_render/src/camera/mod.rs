@@ -15,7 +15,7 @@ use crate::{
     extract_component::ExtractComponentPlugin, extract_resource::ExtractResourcePlugin,
     render_graph::RenderGraph, ExtractSchedule, Render, RenderApp, RenderSet,
 };
-use bevy_app::{App, Plugin};
+use bevy_app::{App, Plugin, PostUpdate};
 use bevy_ecs::schedule::IntoSystemConfigs;
 
 #[derive(Default)]
@@ -28,23 +28,42 @@ impl Plugin for CameraPlugin {
             .register_type::<CameraRenderGraph>()
             .register_type::<CameraMainTextureUsages>()
             .register_type::<Exposure>()
+            .register_type::<ExposureCurve>()
             .register_type::<TemporalJitter>()
+            .register_type::<TemporalJitterSequence>()
             .register_type::<MipBias>()
+            .register_type::<RenderTargetViewFormats>()
+            .register_type::<AllowCameraOrderAmbiguity>()
             .init_resource::<ManualTextureViews>()
             .init_resource::<ClearColor>()
+            .init_resource::<RenderTargetClearColor>()
+            .add_event::<ManualTextureViewEvent>()
+            .add_systems(
+                PostUpdate,
+                (
+                    send_manual_texture_view_events.before(CameraUpdateSystem),
+                    advance_temporal_jitter_sequences,
+                ),
+            )
             .add_plugins((
                 CameraProjectionPlugin::<Projection>::default(),
                 CameraProjectionPlugin::<OrthographicProjection>::default(),
                 CameraProjectionPlugin::<PerspectiveProjection>::default(),
                 ExtractResourcePlugin::<ManualTextureViews>::default(),
                 ExtractResourcePlugin::<ClearColor>::default(),
+                ExtractResourcePlugin::<RenderTargetClearColor>::default(),
                 ExtractComponentPlugin::<CameraMainTextureUsages>::default(),
+                ExtractComponentPlugin::<RenderTargetViewFormats>::default(),
+                ExtractComponentPlugin::<AllowCameraOrderAmbiguity>::default(),
             ));
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<SortedCameras>()
-                .add_systems(ExtractSchedule, extract_cameras)
+                .add_systems(
+                    ExtractSchedule,
+                    extract_cameras.in_set(CameraExtractSystems),
+                )
                 .add_systems(Render, sort_cameras.in_set(RenderSet::ManageViews));
             let camera_driver_node = CameraDriverNode::new(render_app.world_mut());
             let mut render_graph = render_app.world_mut().resource_mut::<RenderGraph>();
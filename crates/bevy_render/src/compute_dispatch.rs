@@ -0,0 +1,182 @@
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::*;
+use bevy_math::UVec3;
+
+use crate::{
+    render_resource::{
+        BindGroup, CachedComputePipelineId, CommandEncoderDescriptor, ComputePassDescriptor,
+        PipelineCache,
+    },
+    renderer::{render_system, PendingCommandBuffers, RenderDevice},
+    Render, RenderApp, RenderSet,
+};
+
+/// Runs [`ComputeDispatch`]es every frame, see its documentation.
+pub struct ComputeDispatchPlugin;
+
+impl Plugin for ComputeDispatchPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(
+                Render,
+                dispatch_compute
+                    .in_set(RenderSet::Render)
+                    .before(render_system),
+            );
+        }
+    }
+}
+
+/// A compute shader dispatch run once this frame, without writing a render graph node.
+///
+/// This is a render world component, meant for GPGPU experiments and simple per-frame compute
+/// work on storage buffers. Spawn entities with it from a system in the
+/// [`ExtractSchedule`](crate::ExtractSchedule) or before [`RenderSet::Render`], as render world
+/// entities are cleared every frame. All dispatches of a frame are recorded into one compute pass,
+/// in no particular order, and submitted before the render graph with order `-1`, see
+/// [`PendingCommandBuffers`]. Dispatches whose pipeline isn't ready yet are skipped.
+#[derive(Component, Clone)]
+pub struct ComputeDispatch {
+    /// The compute pipeline to dispatch.
+    pub pipeline: CachedComputePipelineId,
+    /// The bind group bound at index `0`.
+    pub bind_group: BindGroup,
+    /// The number of workgroups to dispatch in each dimension.
+    pub workgroups: UVec3,
+}
+
+fn dispatch_compute(
+    dispatches: Query<&ComputeDispatch>,
+    pipeline_cache: Res<PipelineCache>,
+    render_device: Res<RenderDevice>,
+    mut pending_command_buffers: ResMut<PendingCommandBuffers>,
+) {
+    if dispatches.is_empty() {
+        return;
+    }
+
+    let mut command_encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("compute_dispatch_command_encoder"),
+    });
+    {
+        let mut compute_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("compute_dispatch"),
+            timestamp_writes: None,
+        });
+        for dispatch in &dispatches {
+            let Some(pipeline) = pipeline_cache.get_compute_pipeline(dispatch.pipeline) else {
+                continue;
+            };
+            compute_pass.set_pipeline(pipeline);
+            compute_pass.set_bind_group(0, &dispatch.bind_group, &[]);
+            compute_pass.dispatch_workgroups(
+                dispatch.workgroups.x,
+                dispatch.workgroups.y,
+                dispatch.workgroups.z,
+            );
+        }
+    }
+    pending_command_buffers.submit_ordered(-1, command_encoder.finish());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        render_resource::{
+            binding_types::storage_buffer_sized, BindGroupEntries, BindGroupLayoutEntries,
+            BufferDescriptor, BufferUsages, ComputePipelineDescriptor, Shader, ShaderStages,
+        },
+        renderer::{test_device, test_read_buffer},
+    };
+    use bevy_asset::Handle;
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn dispatch_writes_storage_buffer() {
+        const VALUES: u64 = 16;
+
+        let (device, queue, adapter) = test_device();
+        let mut pipeline_cache = PipelineCache::new(device.clone(), adapter, true);
+        let shader = Handle::<Shader>::weak_from_u128(1);
+        pipeline_cache.set_shader(
+            shader.id(),
+            &Shader::from_wgsl(
+                "@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+
+                @compute @workgroup_size(4)
+                fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    values[id.x] = id.x * 2u;
+                }",
+                "compute_dispatch.wgsl",
+            ),
+        );
+        let layout = device.create_bind_group_layout(
+            None,
+            &BindGroupLayoutEntries::single(
+                ShaderStages::COMPUTE,
+                storage_buffer_sized(false, None),
+            ),
+        );
+        let pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: None,
+            layout: vec![layout.clone()],
+            push_constant_ranges: vec![],
+            shader,
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            constants: vec![],
+        });
+        pipeline_cache.process_queue();
+
+        let size = VALUES * 4;
+        let storage_buffer = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(
+            None,
+            &layout,
+            &BindGroupEntries::single(storage_buffer.as_entire_binding()),
+        );
+
+        let mut world = World::new();
+        world.insert_resource(pipeline_cache);
+        world.insert_resource(device.clone());
+        world.init_resource::<PendingCommandBuffers>();
+        world.spawn(ComputeDispatch {
+            pipeline,
+            bind_group,
+            workgroups: UVec3::new(VALUES as u32 / 4, 1, 1),
+        });
+        world.run_system_once(dispatch_compute);
+
+        let (pre_graph, post_graph) = world
+            .resource_mut::<PendingCommandBuffers>()
+            .take_around_graph();
+        assert_eq!(pre_graph.len(), 1);
+        assert!(post_graph.is_empty());
+        queue.submit(pre_graph);
+
+        let readback_buffer = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut command_encoder =
+            device.create_command_encoder(&CommandEncoderDescriptor::default());
+        command_encoder.copy_buffer_to_buffer(&storage_buffer, 0, &readback_buffer, 0, size);
+        queue.submit([command_encoder.finish()]);
+
+        let data = test_read_buffer(&device, &readback_buffer);
+        let values: &[u32] = bytemuck::cast_slice(&data);
+        assert_eq!(
+            values,
+            (0..VALUES as u32).map(|i| i * 2).collect::<Vec<_>>()
+        );
+    }
+}
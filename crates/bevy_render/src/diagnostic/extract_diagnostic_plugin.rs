@@ -0,0 +1,68 @@
+use bevy_app::{App, Plugin};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, RegisterDiagnostic};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use bevy_utils::Duration;
+
+use crate::{Render, RenderApp, RenderSet};
+
+use super::RenderWorldMeasurements;
+
+/// Adds a diagnostic measuring how long extracting the main world into the render world takes.
+///
+/// Extraction blocks both the main app and the render app, so it has to stay short for pipelined
+/// rendering to overlap their frames. This measures the whole extraction: reserving the main world
+/// entities in the render world and running the [`ExtractSchedule`](crate::ExtractSchedule).
+/// Frames that aren't extracted, e.g. while [`RenderEnabled`](crate::RenderEnabled) is `false`,
+/// aren't measured.
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](bevy_diagnostic::LogDiagnosticsPlugin) to output diagnostics to the console.
+#[derive(Default)]
+pub struct ExtractDiagnosticPlugin;
+
+impl Plugin for ExtractDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::EXTRACT_TIME).with_suffix("ms"));
+
+        RenderWorldMeasurements::init(app);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ExtractDuration>()
+                .add_systems(Render, Self::diagnostic_system.in_set(RenderSet::Cleanup));
+        }
+    }
+}
+
+impl ExtractDiagnosticPlugin {
+    /// The time spent extracting the main world, in milliseconds.
+    pub const EXTRACT_TIME: DiagnosticPath = DiagnosticPath::const_new("render/extract_time");
+
+    /// Records the duration of the last extraction from [`ExtractDuration`], in the render world.
+    pub fn diagnostic_system(
+        mut extract_duration: ResMut<ExtractDuration>,
+        measurements: Res<RenderWorldMeasurements>,
+    ) {
+        if let Some(duration) = extract_duration.0.take() {
+            measurements.add_measurement(&Self::EXTRACT_TIME, duration.as_secs_f64() * 1000.0);
+        }
+    }
+}
+
+/// A render world resource holding how long the last extraction took, if it hasn't been recorded
+/// by [`ExtractDiagnosticPlugin`] yet.
+///
+/// The extraction is only timed while this resource exists.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct ExtractDuration(pub(crate) Option<Duration>);
+
+impl ExtractDuration {
+    /// Returns the duration of the last extraction, unless it was already recorded.
+    pub fn get(&self) -> Option<Duration> {
+        self.0
+    }
+}
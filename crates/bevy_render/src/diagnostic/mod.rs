@@ -2,11 +2,28 @@
 //!
 //! For more info, see [`RenderDiagnosticsPlugin`].
 
+mod extract_diagnostic_plugin;
 pub(crate) mod internal;
-
-use std::{borrow::Cow, marker::PhantomData, sync::Arc};
+mod phase_batch_diagnostic;
+mod phase_item_count_diagnostic;
+mod render_asset_diagnostic_plugin;
+#[cfg(test)]
+mod test_phase_item;
+
+pub use extract_diagnostic_plugin::*;
+pub use phase_batch_diagnostic::*;
+pub use phase_item_count_diagnostic::*;
+pub use render_asset_diagnostic_plugin::*;
+
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use bevy_app::{App, Plugin, PreUpdate};
+use bevy_diagnostic::{DiagnosticPath, Diagnostics};
+use bevy_ecs::system::{Res, Resource};
 
 use crate::RenderApp;
 
@@ -183,3 +200,51 @@ impl<T: RecordDiagnostics> RecordDiagnostics for Option<Arc<T>> {
         }
     }
 }
+
+/// Measurements recorded by systems in the render world, forwarded to the main world's
+/// [`DiagnosticsStore`](bevy_diagnostic::DiagnosticsStore) during [`PreUpdate`].
+///
+/// The same resource is present in both worlds. Measurements for diagnostics that were not
+/// registered in the main world are dropped.
+#[derive(Resource, Clone, Default)]
+pub struct RenderWorldMeasurements(Arc<Mutex<Vec<(DiagnosticPath, f64)>>>);
+
+impl RenderWorldMeasurements {
+    /// Queues a measurement to be added to the diagnostic at `path`.
+    pub fn add_measurement(&self, path: &DiagnosticPath, value: f64) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((path.clone(), value));
+    }
+
+    /// Inserts the shared measurements into the main and render apps, unless already present.
+    pub(crate) fn init(app: &mut App) {
+        if app.world().contains_resource::<Self>() {
+            return;
+        }
+
+        let measurements = Self::default();
+        app.insert_resource(measurements.clone())
+            .add_systems(PreUpdate, sync_render_world_measurements);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(measurements);
+        }
+    }
+}
+
+fn sync_render_world_measurements(
+    measurements: Res<RenderWorldMeasurements>,
+    mut diagnostics: Diagnostics,
+) {
+    let measurements = std::mem::take(
+        &mut *measurements
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner),
+    );
+    for (path, value) in measurements {
+        diagnostics.add_measurement(&path, || value);
+    }
+}
@@ -0,0 +1,134 @@
+use bevy_app::App;
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, RegisterDiagnostic};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Local, Res},
+};
+use bevy_utils::get_short_name;
+
+use crate::{render_phase::PhaseItem, Render, RenderApp, RenderSet};
+
+use super::{RenderWorldMeasurements, ViewPhaseItemCount};
+
+/// Returns the path of the diagnostic counting the batches of the phase item `I`,
+/// e.g. `render/batches/Transparent2d`.
+pub fn phase_batch_count_diagnostic_path<I: PhaseItem>() -> DiagnosticPath {
+    DiagnosticPath::from_components([
+        "render",
+        "batches",
+        get_short_name(std::any::type_name::<I>()).as_str(),
+    ])
+}
+
+/// Returns the path of the diagnostic measuring the average number of items per batch of the
+/// phase item `I`, e.g. `render/batch_size/Transparent2d`.
+pub fn phase_batch_size_diagnostic_path<I: PhaseItem>() -> DiagnosticPath {
+    DiagnosticPath::from_components([
+        "render",
+        "batch_size",
+        get_short_name(std::any::type_name::<I>()).as_str(),
+    ])
+}
+
+/// Adds diagnostics reporting how well the items of a render phase are batched each frame.
+pub trait PhaseBatchDiagnosticApp {
+    /// Adds diagnostics reporting the number of batches the phases `P` of all views are drawn
+    /// in, at [`phase_batch_count_diagnostic_path`], and the average number of items per batch,
+    /// at [`phase_batch_size_diagnostic_path`].
+    ///
+    /// Nothing is computed unless this is called, so it can be left out of release builds.
+    ///
+    /// ```ignore
+    /// app.add_phase_batch_diagnostic::<ViewSortedRenderPhases<Transparent2d>>();
+    /// ```
+    fn add_phase_batch_diagnostic<P: ViewPhaseItemCount>(&mut self) -> &mut Self;
+}
+
+impl PhaseBatchDiagnosticApp for App {
+    fn add_phase_batch_diagnostic<P: ViewPhaseItemCount>(&mut self) -> &mut Self {
+        self.register_diagnostic(Diagnostic::new(
+            phase_batch_count_diagnostic_path::<P::Item>(),
+        ))
+        .register_diagnostic(Diagnostic::new(
+            phase_batch_size_diagnostic_path::<P::Item>(),
+        ));
+
+        RenderWorldMeasurements::init(self);
+
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(Render, record_phase_batches::<P>.in_set(RenderSet::Cleanup));
+        }
+        self
+    }
+}
+
+fn record_phase_batches<P: ViewPhaseItemCount>(
+    phases: Option<Res<P>>,
+    measurements: Res<RenderWorldMeasurements>,
+    mut paths: Local<Option<(DiagnosticPath, DiagnosticPath)>>,
+) {
+    let Some(phases) = phases else {
+        return;
+    };
+    let (count_path, size_path) = paths.get_or_insert_with(|| {
+        (
+            phase_batch_count_diagnostic_path::<P::Item>(),
+            phase_batch_size_diagnostic_path::<P::Item>(),
+        )
+    });
+
+    let batch_count = phases.batch_count();
+    measurements.add_measurement(count_path, batch_count as f64);
+    // An average is meaningless without any batches, so skip the measurement instead of
+    // recording a NaN.
+    if batch_count > 0 {
+        measurements.add_measurement(size_path, phases.item_count() as f64 / batch_count as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{entity::Entity, system::RunSystemOnce, world::World};
+
+    use super::*;
+    use crate::{
+        diagnostic::test_phase_item::TestItem,
+        render_phase::{PhaseItemExtraIndex, SortedRenderPhase, ViewSortedRenderPhases},
+    };
+
+    #[test]
+    fn sorted_phase_batches() {
+        // Like sprites, the first item of each batch covers the whole batch and the items it
+        // covers keep an empty range: batches of 3, 1 and 2 items.
+        let batch_ranges = [0..3, 0..0, 0..0, 3..4, 4..6, 0..0];
+        let mut phase = SortedRenderPhase::default();
+        for (index, batch_range) in batch_ranges.into_iter().enumerate() {
+            phase.add(TestItem {
+                entity: Entity::from_raw(index as u32),
+                batch_range,
+                extra_index: PhaseItemExtraIndex::NONE,
+            });
+        }
+        let mut phases = ViewSortedRenderPhases::<TestItem>::default();
+        phases.insert(Entity::from_raw(100), phase);
+        assert_eq!(phases.batch_count(), 3);
+
+        let mut world = World::new();
+        world.insert_resource(phases);
+        world.init_resource::<RenderWorldMeasurements>();
+        world.run_system_once(record_phase_batches::<ViewSortedRenderPhases<TestItem>>);
+
+        let measurements = world
+            .resource::<RenderWorldMeasurements>()
+            .0
+            .lock()
+            .unwrap();
+        assert_eq!(
+            *measurements,
+            vec![
+                (phase_batch_count_diagnostic_path::<TestItem>(), 3.0),
+                (phase_batch_size_diagnostic_path::<TestItem>(), 2.0),
+            ]
+        );
+    }
+}
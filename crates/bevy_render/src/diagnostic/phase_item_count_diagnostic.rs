@@ -0,0 +1,162 @@
+use bevy_app::App;
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, RegisterDiagnostic};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Local, Res, Resource},
+};
+use bevy_utils::get_short_name;
+
+use crate::{
+    render_phase::{
+        BinnedPhaseItem, PhaseItem, SortedPhaseItem, ViewBinnedRenderPhases, ViewSortedRenderPhases,
+    },
+    Render, RenderApp, RenderSet,
+};
+
+use super::RenderWorldMeasurements;
+
+/// A render world resource storing a render phase per view, whose items can be counted.
+///
+/// Implemented for both [`ViewSortedRenderPhases`] and [`ViewBinnedRenderPhases`].
+pub trait ViewPhaseItemCount: Resource {
+    /// The phase item stored in these phases.
+    type Item: PhaseItem;
+
+    /// Returns the number of items queued in the phases of all views.
+    fn item_count(&self) -> usize;
+
+    /// Returns the number of batches the items of all views are drawn in.
+    ///
+    /// This is only meaningful once the phases have been batched in
+    /// [`RenderSet::PrepareResources`].
+    fn batch_count(&self) -> usize;
+}
+
+impl<I: SortedPhaseItem> ViewPhaseItemCount for ViewSortedRenderPhases<I> {
+    type Item = I;
+
+    fn item_count(&self) -> usize {
+        self.values().map(|phase| phase.items.len()).sum()
+    }
+
+    fn batch_count(&self) -> usize {
+        self.values()
+            .map(|phase| {
+                // Mirrors `SortedRenderPhase::render_range`: an item with a non-empty batch
+                // range issues one draw covering the following `batch_range.len()` items.
+                let mut batches = 0;
+                let mut index = 0;
+                while index < phase.items.len() {
+                    let batch_len = phase.items[index].batch_range().len();
+                    if batch_len == 0 {
+                        index += 1;
+                    } else {
+                        batches += 1;
+                        index += batch_len;
+                    }
+                }
+                batches
+            })
+            .sum()
+    }
+}
+
+impl<I: BinnedPhaseItem> ViewPhaseItemCount for ViewBinnedRenderPhases<I> {
+    type Item = I;
+
+    fn item_count(&self) -> usize {
+        self.values().map(|phase| phase.len()).sum()
+    }
+
+    fn batch_count(&self) -> usize {
+        self.values().map(|phase| phase.batch_count()).sum()
+    }
+}
+
+/// Returns the path of the diagnostic added by
+/// [`add_phase_item_count_diagnostic`](PhaseItemCountDiagnosticApp::add_phase_item_count_diagnostic)
+/// for the phase item `I`, e.g. `render/phase_items/Transparent2d`.
+pub fn phase_item_count_diagnostic_path<I: PhaseItem>() -> DiagnosticPath {
+    DiagnosticPath::from_components([
+        "render",
+        "phase_items",
+        get_short_name(std::any::type_name::<I>()).as_str(),
+    ])
+}
+
+/// Adds diagnostics counting the items queued in a render phase each frame.
+pub trait PhaseItemCountDiagnosticApp {
+    /// Adds a diagnostic reporting the number of items queued in the phases `P` of all views,
+    /// at [`phase_item_count_diagnostic_path`].
+    ///
+    /// ```ignore
+    /// app.add_phase_item_count_diagnostic::<ViewSortedRenderPhases<Transparent2d>>();
+    /// ```
+    fn add_phase_item_count_diagnostic<P: ViewPhaseItemCount>(&mut self) -> &mut Self;
+}
+
+impl PhaseItemCountDiagnosticApp for App {
+    fn add_phase_item_count_diagnostic<P: ViewPhaseItemCount>(&mut self) -> &mut Self {
+        self.register_diagnostic(Diagnostic::new(
+            phase_item_count_diagnostic_path::<P::Item>(),
+        ));
+
+        RenderWorldMeasurements::init(self);
+
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.add_systems(
+                Render,
+                record_phase_item_count::<P>.in_set(RenderSet::Cleanup),
+            );
+        }
+        self
+    }
+}
+
+fn record_phase_item_count<P: ViewPhaseItemCount>(
+    phases: Option<Res<P>>,
+    measurements: Res<RenderWorldMeasurements>,
+    mut path: Local<Option<DiagnosticPath>>,
+) {
+    let Some(phases) = phases else {
+        return;
+    };
+    let path = path.get_or_insert_with(phase_item_count_diagnostic_path::<P::Item>);
+    measurements.add_measurement(path, phases.item_count() as f64);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{entity::Entity, system::RunSystemOnce, world::World};
+
+    use super::*;
+    use crate::{diagnostic::test_phase_item::TestItem, render_phase::SortedRenderPhase};
+
+    #[test]
+    fn sorted_phase_item_count() {
+        let mut phases = ViewSortedRenderPhases::<TestItem>::default();
+        for (view, item_count) in [(100, 2), (101, 3)] {
+            let mut phase = SortedRenderPhase::default();
+            for index in 0..item_count {
+                phase.add(TestItem::new(index));
+            }
+            phases.insert(Entity::from_raw(view), phase);
+        }
+        assert_eq!(phases.item_count(), 5);
+
+        let mut world = World::new();
+        world.insert_resource(phases);
+        world.init_resource::<RenderWorldMeasurements>();
+        world.run_system_once(record_phase_item_count::<ViewSortedRenderPhases<TestItem>>);
+
+        let measurements = world
+            .resource::<RenderWorldMeasurements>()
+            .0
+            .lock()
+            .unwrap();
+        assert_eq!(
+            *measurements,
+            vec![(phase_item_count_diagnostic_path::<TestItem>(), 5.0)]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use bevy_app::{App, Plugin};
+use bevy_diagnostic::{Diagnostic, DiagnosticPath, RegisterDiagnostic};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Local, Res},
+};
+
+use crate::{render_asset::RenderAssetBytesResident, Render, RenderApp, RenderSet};
+
+use super::RenderWorldMeasurements;
+
+/// Adds diagnostics tracking the GPU memory used by prepared render assets.
+///
+/// Only assets implementing [`RenderAsset::byte_len`](crate::render_asset::RenderAsset::byte_len)
+/// are tracked. See [`RenderAssetBytesResident`].
+///
+/// # See also
+///
+/// [`LogDiagnosticsPlugin`](bevy_diagnostic::LogDiagnosticsPlugin) to output diagnostics to the console.
+#[derive(Default)]
+pub struct RenderAssetDiagnosticPlugin;
+
+impl Plugin for RenderAssetDiagnosticPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::BYTES_RESIDENT).with_suffix(" B"))
+            .register_diagnostic(Diagnostic::new(Self::BYTES_WRITTEN).with_suffix(" B"));
+
+        RenderWorldMeasurements::init(app);
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<RenderAssetBytesResident>()
+                .add_systems(Render, Self::diagnostic_system.in_set(RenderSet::Cleanup));
+        }
+    }
+}
+
+impl RenderAssetDiagnosticPlugin {
+    /// The total number of bytes of all prepared render assets.
+    pub const BYTES_RESIDENT: DiagnosticPath =
+        DiagnosticPath::const_new("render/render_asset/bytes_resident");
+    /// The number of bytes of render assets prepared this frame.
+    pub const BYTES_WRITTEN: DiagnosticPath =
+        DiagnosticPath::const_new("render/render_asset/bytes_written");
+
+    /// Records the render asset memory diagnostics from [`RenderAssetBytesResident`], in the
+    /// render world.
+    pub fn diagnostic_system(
+        resident: Res<RenderAssetBytesResident>,
+        measurements: Res<RenderWorldMeasurements>,
+        mut last_bytes_written: Local<u64>,
+    ) {
+        measurements.add_measurement(&Self::BYTES_RESIDENT, resident.bytes() as f64);
+        measurements.add_measurement(
+            &Self::BYTES_WRITTEN,
+            (resident.bytes_written() - *last_bytes_written) as f64,
+        );
+        *last_bytes_written = resident.bytes_written();
+    }
+}
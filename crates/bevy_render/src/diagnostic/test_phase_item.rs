@@ -0,0 +1,59 @@
+//! A sorted phase item for testing the phase diagnostics without a renderer.
+
+use std::ops::Range;
+
+use bevy_ecs::entity::Entity;
+
+use crate::render_phase::{DrawFunctionId, PhaseItem, PhaseItemExtraIndex, SortedPhaseItem};
+
+/// A phase item that's sorted by the index of its entity, and never drawn.
+pub(super) struct TestItem {
+    pub(super) entity: Entity,
+    pub(super) batch_range: Range<u32>,
+    pub(super) extra_index: PhaseItemExtraIndex,
+}
+
+impl TestItem {
+    /// An item covering a batch of its own.
+    pub(super) fn new(index: u32) -> Self {
+        Self {
+            entity: Entity::from_raw(index),
+            batch_range: 0..1,
+            extra_index: PhaseItemExtraIndex::NONE,
+        }
+    }
+}
+
+impl PhaseItem for TestItem {
+    fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    fn draw_function(&self) -> DrawFunctionId {
+        unreachable!("test items are never drawn")
+    }
+
+    fn batch_range(&self) -> &Range<u32> {
+        &self.batch_range
+    }
+
+    fn batch_range_mut(&mut self) -> &mut Range<u32> {
+        &mut self.batch_range
+    }
+
+    fn extra_index(&self) -> PhaseItemExtraIndex {
+        self.extra_index
+    }
+
+    fn batch_range_and_extra_index_mut(&mut self) -> (&mut Range<u32>, &mut PhaseItemExtraIndex) {
+        (&mut self.batch_range, &mut self.extra_index)
+    }
+}
+
+impl SortedPhaseItem for TestItem {
+    type SortKey = u32;
+
+    fn sort_key(&self) -> Self::SortKey {
+        self.entity.index()
+    }
+}
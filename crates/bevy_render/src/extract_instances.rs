@@ -13,10 +13,17 @@ use bevy_ecs::{
     entity::EntityHashMap,
     prelude::Entity,
     query::{QueryFilter, QueryItem, ReadOnlyQueryData},
-    system::{lifetimeless::Read, Query, ResMut, Resource},
+    schedule::IntoSystemConfigs,
+    system::{lifetimeless::Read, Query, Res, ResMut, Resource},
 };
+use bytemuck::NoUninit;
 
-use crate::{prelude::ViewVisibility, Extract, ExtractSchedule, RenderApp};
+use crate::{
+    prelude::ViewVisibility,
+    render_resource::{BindingResource, Buffer, BufferUsages, RawBufferVec},
+    renderer::{RenderDevice, RenderQueue},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
 
 /// Describes how to extract data needed for rendering from a component or
 /// components.
@@ -135,6 +142,115 @@ fn extract_visible<EI>(
     }
 }
 
+/// This plugin writes the [`ExtractedInstances`] of a GPU-compatible type into a single
+/// [`ExtractedInstanceBuffer`], so that custom draw commands can look them up by entity.
+///
+/// The instances themselves are extracted by [`ExtractInstancesPlugin`], which must be added as
+/// well.
+pub struct InstanceBufferPlugin<EI>(PhantomData<fn() -> EI>);
+
+impl<EI> Default for InstanceBufferPlugin<EI> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<EI> Plugin for InstanceBufferPlugin<EI>
+where
+    EI: ExtractInstance + NoUninit,
+{
+    fn build(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ExtractedInstanceBuffer<EI>>()
+                .add_systems(
+                    Render,
+                    prepare_instance_buffer::<EI>.in_set(RenderSet::PrepareResources),
+                );
+        }
+    }
+}
+
+/// Stores all extracted instances of a type contiguously in a storage buffer.
+///
+/// Instances are laid out in entity order, so an instance keeps its index from frame to frame as
+/// long as the set of extracted entities doesn't change.
+#[derive(Resource)]
+pub struct ExtractedInstanceBuffer<EI>
+where
+    EI: ExtractInstance + NoUninit,
+{
+    buffer: RawBufferVec<EI>,
+    indices: EntityHashMap<u32>,
+}
+
+impl<EI> Default for ExtractedInstanceBuffer<EI>
+where
+    EI: ExtractInstance + NoUninit,
+{
+    fn default() -> Self {
+        Self {
+            buffer: RawBufferVec::new(BufferUsages::STORAGE),
+            indices: Default::default(),
+        }
+    }
+}
+
+impl<EI> ExtractedInstanceBuffer<EI>
+where
+    EI: ExtractInstance + NoUninit,
+{
+    /// Returns the storage buffer, if any instances have been written to it.
+    #[inline]
+    pub fn buffer(&self) -> Option<&Buffer> {
+        self.buffer.buffer()
+    }
+
+    /// Returns the binding of the whole storage buffer, if any instances have been written to it.
+    #[inline]
+    pub fn binding(&self) -> Option<BindingResource> {
+        self.buffer.binding()
+    }
+
+    /// Returns the index of the instance extracted from `entity` in the buffer.
+    #[inline]
+    pub fn index(&self, entity: Entity) -> Option<u32> {
+        self.indices.get(&entity).copied()
+    }
+
+    /// Returns the instances in the order they're stored in the buffer.
+    #[inline]
+    pub fn values(&self) -> &[EI] {
+        self.buffer.values()
+    }
+
+    fn gather(&mut self, extracted_instances: &ExtractedInstances<EI>) {
+        self.buffer.clear();
+        self.indices.clear();
+
+        let mut instances: Vec<_> = extracted_instances.iter().collect();
+        instances.sort_unstable_by_key(|(entity, _)| **entity);
+        for (entity, instance) in instances {
+            let index = self.buffer.push(*instance);
+            self.indices.insert(*entity, index as u32);
+        }
+    }
+}
+
+fn prepare_instance_buffer<EI>(
+    mut instance_buffer: ResMut<ExtractedInstanceBuffer<EI>>,
+    extracted_instances: Res<ExtractedInstances<EI>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) where
+    EI: ExtractInstance + NoUninit,
+{
+    instance_buffer.gather(&extracted_instances);
+    instance_buffer
+        .buffer
+        .write_buffer(&render_device, &render_queue);
+}
+
 impl<A> ExtractInstance for AssetId<A>
 where
     A: Asset,
@@ -146,3 +262,73 @@ where
         Some(item.id())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MainWorld;
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use bevy_math::Vec3;
+    use bevy_transform::components::Transform;
+    use bytemuck::{Pod, Zeroable};
+
+    #[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable)]
+    #[repr(C)]
+    struct InstanceTranslation([f32; 4]);
+
+    impl ExtractInstance for InstanceTranslation {
+        type QueryData = Read<Transform>;
+        type QueryFilter = ();
+
+        fn extract(transform: QueryItem<'_, Self::QueryData>) -> Option<Self> {
+            Some(Self(transform.translation.extend(1.0).to_array()))
+        }
+    }
+
+    #[test]
+    fn instance_buffer_is_ordered_by_entity() {
+        let mut main_world = MainWorld::default();
+        let world = main_world.world_mut();
+        let entities: Vec<_> = (0..3)
+            .map(|i| {
+                world
+                    .spawn(Transform::from_translation(Vec3::splat(i as f32)))
+                    .id()
+            })
+            .collect();
+        // The respawned entity reuses the despawned one's index with a newer generation.
+        world.despawn(entities[0]);
+        let respawned = world.spawn(Transform::from_xyz(5.0, 0.0, 0.0)).id();
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<ExtractedInstances<InstanceTranslation>>();
+        render_world.run_system_once(extract_all::<InstanceTranslation>);
+
+        let mut instance_buffer = ExtractedInstanceBuffer::<InstanceTranslation>::default();
+        instance_buffer.gather(render_world.resource::<ExtractedInstances<InstanceTranslation>>());
+
+        let mut expected = vec![
+            (entities[1], InstanceTranslation([1.0, 1.0, 1.0, 1.0])),
+            (entities[2], InstanceTranslation([2.0, 2.0, 2.0, 1.0])),
+            (respawned, InstanceTranslation([5.0, 0.0, 0.0, 1.0])),
+        ];
+        expected.sort_unstable_by_key(|(entity, _)| *entity);
+        assert_eq!(
+            instance_buffer.values(),
+            expected
+                .iter()
+                .map(|(_, instance)| *instance)
+                .collect::<Vec<_>>()
+        );
+        for (index, (entity, _)) in expected.iter().enumerate() {
+            assert_eq!(instance_buffer.index(*entity), Some(index as u32));
+        }
+        assert_eq!(instance_buffer.index(entities[0]), None);
+
+        // Gathering again doesn't move any instance.
+        instance_buffer.gather(render_world.resource::<ExtractedInstances<InstanceTranslation>>());
+        assert_eq!(instance_buffer.values().len(), 3);
+        assert_eq!(instance_buffer.index(expected[0].0), Some(0));
+    }
+}
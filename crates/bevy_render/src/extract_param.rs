@@ -69,7 +69,7 @@ where
     fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
         let mut main_world = world.resource_mut::<MainWorld>();
         ExtractState {
-            state: SystemState::new(&mut main_world),
+            state: SystemState::new(main_world.world_mut()),
             main_world_state: Res::<MainWorld>::init_state(world, system_meta),
         }
     }
@@ -130,3 +130,33 @@ where
         (&self.item).into_iter()
     }
 }
+
+/// Read-only access to the whole [`MainWorld`] from an extract system.
+///
+/// Unlike `Res<MainWorld>`, this documents at the call site that the system only reads the main
+/// world. Use [`Extract`] instead when the data can be fetched with a regular [`SystemParam`],
+/// and [`MainWorld::world_mut`] in the rare cases the main world has to be changed.
+///
+/// ```
+/// use bevy_ecs::prelude::*;
+/// use bevy_render::MainWorldRef;
+/// # #[derive(Component)]
+/// # struct Cloud;
+/// fn count_clouds(main_world: MainWorldRef) {
+///     let clouds = main_world.iter_entities().filter(|entity| entity.contains::<Cloud>());
+///     bevy_utils::tracing::info!("{} clouds", clouds.count());
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct MainWorldRef<'w> {
+    main_world: Res<'w, MainWorld>,
+}
+
+impl Deref for MainWorldRef<'_> {
+    type Target = World;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.main_world
+    }
+}
@@ -10,6 +10,28 @@ use crate::{Extract, ExtractSchedule, RenderApp};
 ///
 /// Therefore the resource is transferred from the "main world" into the "render world"
 /// in the [`ExtractSchedule`] step.
+///
+/// The render world resource doesn't have to be the main world resource itself: setting
+/// [`ExtractResource::Source`] to another type extracts from that type instead, converting
+/// it in [`ExtractResource::extract_resource`]. [`ExtractResourcePlugin`] works the same for
+/// both.
+///
+/// ```
+/// # use bevy_color::LinearRgba;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_render::{camera::ClearColor, extract_resource::ExtractResource};
+/// /// The clear color, converted to linear space once per change instead of every frame.
+/// #[derive(Resource)]
+/// struct RenderClearColor(LinearRgba);
+///
+/// impl ExtractResource for RenderClearColor {
+///     type Source = ClearColor;
+///
+///     fn extract_resource(source: &ClearColor) -> Self {
+///         RenderClearColor(source.0.into())
+///     }
+/// }
+/// ```
 pub trait ExtractResource: Resource {
     type Source: Resource;
 
@@ -66,3 +88,49 @@ pub fn extract_resource<R: ExtractResource>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{camera::ClearColor, MainWorld};
+    use bevy_color::{Color, LinearRgba};
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[derive(Resource, Debug, PartialEq)]
+    struct RenderClearColor(LinearRgba);
+
+    impl ExtractResource for RenderClearColor {
+        type Source = ClearColor;
+
+        fn extract_resource(source: &ClearColor) -> Self {
+            RenderClearColor(source.0.into())
+        }
+    }
+
+    #[test]
+    fn extract_into_a_different_resource_type() {
+        let mut main_world = MainWorld::default();
+        main_world
+            .world_mut()
+            .insert_resource(ClearColor(Color::WHITE));
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+
+        render_world.run_system_once(extract_resource::<RenderClearColor>);
+        assert_eq!(
+            render_world.get_resource::<RenderClearColor>(),
+            Some(&RenderClearColor(LinearRgba::WHITE))
+        );
+
+        render_world
+            .resource_mut::<MainWorld>()
+            .world_mut()
+            .resource_mut::<ClearColor>()
+            .0 = Color::BLACK;
+        render_world.run_system_once(extract_resource::<RenderClearColor>);
+        assert_eq!(
+            render_world.get_resource::<RenderClearColor>(),
+            Some(&RenderClearColor(LinearRgba::BLACK))
+        );
+    }
+}
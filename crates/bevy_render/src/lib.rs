@@ -15,6 +15,7 @@ extern crate core;
 pub mod alpha;
 pub mod batching;
 pub mod camera;
+pub mod compute_dispatch;
 pub mod diagnostic;
 pub mod extract_component;
 pub mod extract_instances;
@@ -26,6 +27,7 @@ pub mod mesh;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod pipelined_rendering;
 pub mod primitives;
+pub mod redraw;
 pub mod render_asset;
 pub mod render_graph;
 pub mod render_phase;
@@ -55,32 +57,41 @@ pub mod prelude {
 use batching::gpu_preprocessing::BatchingPlugin;
 use bevy_ecs::schedule::ScheduleBuildSettings;
 use bevy_utils::prelude::default;
-pub use extract_param::Extract;
+pub use extract_param::{Extract, MainWorldRef};
 
 use bevy_hierarchy::ValidParentCheckPlugin;
-use bevy_window::{PrimaryWindow, RawHandleWrapperHolder};
+use bevy_transform::components::GlobalTransform;
+use bevy_window::{PrimaryWindow, RawHandleWrapperHolder, RequestRedraw};
 use extract_resource::ExtractResourcePlugin;
 use globals::GlobalsPlugin;
 use render_asset::RenderAssetBytesPerFrame;
-use renderer::{RenderAdapter, RenderAdapterInfo, RenderDevice, RenderQueue};
+use renderer::{
+    HardwareWorkarounds, RenderAdapter, RenderAdapterInfo, RenderBackend, RenderCapabilities,
+    RenderDevice, RenderQueue, TextureReadbacks,
+};
 
 use crate::mesh::GpuMesh;
 use crate::renderer::WgpuWrapper;
 use crate::{
-    camera::CameraPlugin,
-    mesh::{morph::MorphPlugin, MeshPlugin},
+    camera::{Camera, CameraPlugin, ClearColor},
+    diagnostic::ExtractDuration,
+    mesh::{morph::MorphPlugin, Mesh, MeshPlugin},
+    redraw::{take_redraw_request, RedrawPolicy, RedrawPolicyApp, RedrawRequested},
     render_asset::prepare_assets,
     render_resource::{PipelineCache, Shader, ShaderLoader},
     renderer::{render_system, RenderInstance},
     settings::RenderCreation,
-    view::{ViewPlugin, WindowRenderPlugin},
+    texture::Image,
+    view::{ViewPlugin, Visibility, WindowRenderPlugin},
 };
 use bevy_app::{App, AppLabel, Plugin, SubApp};
 use bevy_asset::{load_internal_asset, AssetApp, AssetServer, Handle};
-use bevy_ecs::{prelude::*, schedule::ScheduleLabel, system::SystemState};
-use bevy_utils::tracing::debug;
+use bevy_ecs::{
+    batching::BatchingStrategy, prelude::*, schedule::ScheduleLabel, system::SystemState,
+};
+use bevy_utils::{tracing::debug, Instant};
 use std::{
-    ops::{Deref, DerefMut},
+    ops::Deref,
     sync::{Arc, Mutex},
 };
 
@@ -190,9 +201,26 @@ pub struct ExtractSchedule;
 /// This resource is only available during [`ExtractSchedule`] and not
 /// during command application of that schedule.
 /// See [`Extract`] for more details.
+///
+/// This only derefs to `&World`: prefer [`Extract`] or [`MainWorldRef`] to read from it. Extract
+/// systems that really need to change the main world must opt in with [`MainWorld::world_mut`].
 #[derive(Resource, Default)]
 pub struct MainWorld(World);
 
+impl MainWorld {
+    /// Returns mutable access to the main world.
+    ///
+    /// Changes made through the returned [`World`] are applied to the main world directly, but
+    /// [`Commands`] queued by extract systems are applied to the render world, and the main
+    /// world's own command queue isn't flushed during extraction. For example, spawning with
+    /// `main_world.commands()` silently does nothing. Only use this for state the main world
+    /// reads back, such as caching a [`SystemState`] or
+    /// reporting render state to the app.
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.0
+    }
+}
+
 impl Deref for MainWorld {
     type Target = World;
 
@@ -201,9 +229,64 @@ impl Deref for MainWorld {
     }
 }
 
-impl DerefMut for MainWorld {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+/// Controls whether the render sub-app does any work each frame.
+///
+/// This resource lives in the main world and defaults to `true`. While it is `false`,
+/// extraction and the render graph are skipped, but the render sub-app and all of its
+/// resources are kept intact so rendering resumes as soon as it is set back to `true`.
+/// This is useful for dedicated servers built with [`RenderPlugin`], or for pausing
+/// rendering while the window is minimized.
+///
+/// The value is mirrored into the render world at the start of every extraction, where it is also
+/// `false` for frames skipped by [`RedrawPolicy::OnlyWhenChanged`].
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderEnabled(pub bool);
+
+impl Default for RenderEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// A run condition that returns `true` unless [`RenderEnabled`] is present and set to `false`.
+pub fn render_enabled(render_enabled: Option<Res<RenderEnabled>>) -> bool {
+    render_enabled.map_or(true, |render_enabled| render_enabled.0)
+}
+
+/// Lets extraction systems that support it spread their work over the
+/// [`ComputeTaskPool`](bevy_tasks::ComputeTaskPool).
+///
+/// This resource lives in the render world. The main world is locked for the whole
+/// [`ExtractSchedule`], so scenes with many entities can shorten that window by extracting in
+/// parallel, while small scenes are usually faster without the task overhead. Defaults to
+/// extracting on the render thread only.
+///
+/// Currently honored by sprite extraction.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExtractParallelism {
+    /// The number of entities extracted by each task, or `None` to extract all of them on the
+    /// render thread.
+    pub batch_size: Option<usize>,
+}
+
+impl ExtractParallelism {
+    /// Extracts on the render thread only.
+    pub const SINGLE_THREADED: Self = Self { batch_size: None };
+
+    /// Extracts in parallel tasks of `batch_size` entities each.
+    pub const fn parallel(batch_size: usize) -> Self {
+        Self {
+            batch_size: Some(batch_size),
+        }
+    }
+
+    /// Returns the strategy to pass to [`QueryParIter::batching_strategy`], or `None` if the
+    /// query should be iterated on the current thread.
+    ///
+    /// [`QueryParIter::batching_strategy`]: bevy_ecs::query::QueryParIter::batching_strategy
+    pub fn batching_strategy(&self) -> Option<BatchingStrategy> {
+        self.batch_size
+            .map(|batch_size| BatchingStrategy::fixed(batch_size.max(1)))
     }
 }
 
@@ -214,17 +297,25 @@ pub mod graph {
     pub struct CameraDriverLabel;
 }
 
+/// Holds the result of the (possibly asynchronous) renderer initialization until
+/// [`RenderPlugin::finish`] inserts it into the app. A stored error still makes
+/// [`RenderPlugin::ready`] return `true`, so the failure is reported instead of waiting forever.
 #[derive(Resource)]
 struct FutureRendererResources(
     Arc<
         Mutex<
-            Option<(
-                RenderDevice,
-                RenderQueue,
-                RenderAdapterInfo,
-                RenderAdapter,
-                RenderInstance,
-            )>,
+            Option<
+                Result<
+                    (
+                        RenderDevice,
+                        RenderQueue,
+                        RenderAdapterInfo,
+                        RenderAdapter,
+                        RenderInstance,
+                    ),
+                    renderer::RenderInitializationError,
+                >,
+            >,
         >,
     >,
 );
@@ -247,13 +338,13 @@ impl Plugin for RenderPlugin {
 
         match &self.render_creation {
             RenderCreation::Manual(device, queue, adapter_info, adapter, instance) => {
-                let future_renderer_resources_wrapper = Arc::new(Mutex::new(Some((
+                let future_renderer_resources_wrapper = Arc::new(Mutex::new(Some(Ok((
                     device.clone(),
                     queue.clone(),
                     adapter_info.clone(),
                     adapter.clone(),
                     instance.clone(),
-                ))));
+                )))));
                 app.insert_resource(FutureRendererResources(
                     future_renderer_resources_wrapper.clone(),
                 ));
@@ -303,24 +394,31 @@ impl Plugin for RenderPlugin {
                             ..Default::default()
                         };
 
-                        let (device, queue, adapter_info, render_adapter) =
-                            renderer::initialize_renderer(
-                                &instance,
-                                &settings,
-                                &request_adapter_options,
-                            )
-                            .await;
-                        debug!("Configured wgpu adapter Limits: {:#?}", device.limits());
-                        debug!("Configured wgpu adapter Features: {:#?}", device.features());
+                        let render_resources = renderer::initialize_renderer(
+                            &instance,
+                            &settings,
+                            &request_adapter_options,
+                        )
+                        .await
+                        .map(
+                            |(device, queue, adapter_info, render_adapter)| {
+                                debug!("Configured wgpu adapter Limits: {:#?}", device.limits());
+                                debug!(
+                                    "Configured wgpu adapter Features: {:#?}",
+                                    device.features()
+                                );
+                                (
+                                    device,
+                                    queue,
+                                    adapter_info,
+                                    render_adapter,
+                                    RenderInstance(Arc::new(WgpuWrapper::new(instance))),
+                                )
+                            },
+                        );
                         let mut future_renderer_resources_inner =
                             future_renderer_resources_wrapper.lock().unwrap();
-                        *future_renderer_resources_inner = Some((
-                            device,
-                            queue,
-                            adapter_info,
-                            render_adapter,
-                            RenderInstance(Arc::new(WgpuWrapper::new(instance))),
-                        ));
+                        *future_renderer_resources_inner = Some(render_resources);
                     };
                     // In wasm, spawn a task and detach it for execution
                     #[cfg(target_arch = "wasm32")]
@@ -346,11 +444,31 @@ impl Plugin for RenderPlugin {
             GlobalsPlugin,
             MorphPlugin,
             BatchingPlugin,
+            compute_dispatch::ComputeDispatchPlugin,
         ));
 
         app.init_resource::<RenderAssetBytesPerFrame>()
             .add_plugins(ExtractResourcePlugin::<RenderAssetBytesPerFrame>::default());
 
+        app.register_type::<RedrawPolicy>()
+            .init_resource::<RedrawPolicy>()
+            .init_resource::<RedrawRequested>()
+            .redraw_on_change::<Camera>()
+            .redraw_on_change::<GlobalTransform>()
+            .redraw_on_change::<Visibility>()
+            .redraw_on_change::<Handle<Mesh>>()
+            .redraw_on_change::<Handle<Image>>()
+            .redraw_on_resource_change::<ClearColor>()
+            .redraw_on_event::<RequestRedraw>()
+            .redraw_while_pending(|render_world| {
+                render_world
+                    .get_resource::<PipelineCache>()
+                    .is_some_and(|pipeline_cache| {
+                        pipeline_cache.pending_count() > 0
+                            || pipeline_cache.waiting_pipelines().next().is_some()
+                    })
+            });
+
         app.register_type::<alpha::AlphaMode>()
             // These types cannot be registered in bevy_color, as it does not depend on the rest of Bevy
             .register_type::<bevy_color::Color>()
@@ -379,17 +497,30 @@ impl Plugin for RenderPlugin {
             app.world_mut().remove_resource::<FutureRendererResources>()
         {
             let (device, queue, adapter_info, render_adapter, instance) =
-                future_renderer_resources.0.lock().unwrap().take().unwrap();
+                match future_renderer_resources.0.lock().unwrap().take().unwrap() {
+                    Ok(render_resources) => render_resources,
+                    Err(err) => panic!("Failed to initialize the renderer: {err}"),
+                };
 
             app.insert_resource(device.clone())
                 .insert_resource(queue.clone())
                 .insert_resource(adapter_info.clone())
                 .insert_resource(render_adapter.clone());
 
+            let render_capabilities = RenderCapabilities::new(&device, &render_adapter);
+            let render_backend = RenderBackend::from_backend(adapter_info.backend);
+            let hardware_workarounds = HardwareWorkarounds::new(&adapter_info);
+
             let render_app = app.sub_app_mut(RenderApp);
 
+            if let Some(render_backend) = render_backend {
+                render_app.insert_resource(render_backend);
+            }
+
             render_app
                 .insert_resource(instance)
+                .insert_resource(render_capabilities)
+                .insert_resource(hardware_workarounds)
                 .insert_resource(PipelineCache::new(
                     device.clone(),
                     render_adapter.clone(),
@@ -399,6 +530,7 @@ impl Plugin for RenderPlugin {
                 .insert_resource(queue)
                 .insert_resource(render_adapter)
                 .insert_resource(adapter_info)
+                .init_resource::<TextureReadbacks>()
                 .add_systems(
                     Render,
                     (|mut bpf: ResMut<RenderAssetBytesPerFrame>| {
@@ -415,6 +547,62 @@ impl Plugin for RenderPlugin {
 #[derive(Resource, Default)]
 struct ScratchMainWorld(World);
 
+/// Extracts the main world into the render world, unless [`RenderEnabled`] is `false` or the
+/// [`RedrawPolicy`] skips this frame.
+fn extract_main_app(main_world: &mut World, render_world: &mut World) {
+    let mut enabled = main_world
+        .get_resource::<RenderEnabled>()
+        .copied()
+        .unwrap_or_default();
+    // Only consume the redraw request while rendering is enabled, so the frame is drawn once
+    // rendering is enabled again.
+    if enabled.0 {
+        enabled.0 = take_redraw_request(main_world, render_world);
+    }
+    match render_world.get_resource_mut::<RenderEnabled>() {
+        Some(mut render_enabled) => {
+            render_enabled.set_if_neq(enabled);
+        }
+        None => render_world.insert_resource(enabled),
+    }
+    if !enabled.0 {
+        return;
+    }
+
+    let start = Instant::now();
+    #[cfg(feature = "trace")]
+    let _render_span =
+        bevy_utils::tracing::info_span!("extract main app to render subapp").entered();
+    {
+        #[cfg(feature = "trace")]
+        let _stage_span = bevy_utils::tracing::info_span!("reserve_and_flush").entered();
+
+        // reserve all existing main world entities for use in render_app
+        // they can only be spawned using `get_or_spawn()`
+        let total_count = main_world.entities().total_count();
+
+        assert_eq!(
+            render_world.entities().len(),
+            0,
+            "An entity was spawned after the entity list was cleared last frame and before the extract schedule began. This is not supported",
+        );
+
+        // SAFETY: This is safe given the clear_entities call in the past frame and the assert above
+        unsafe {
+            render_world
+                .entities_mut()
+                .flush_and_reserve_invalid_assuming_no_entities(total_count);
+        }
+    }
+
+    // run extract schedule
+    extract(main_world, render_world);
+
+    if let Some(mut extract_duration) = render_world.get_resource_mut::<ExtractDuration>() {
+        extract_duration.0 = Some(start.elapsed());
+    }
+}
+
 /// Executes the [`ExtractSchedule`] step of the renderer.
 /// This updates the render world with the extracted ECS data of the current frame.
 fn extract(main_world: &mut World, render_world: &mut World) {
@@ -422,17 +610,37 @@ fn extract(main_world: &mut World, render_world: &mut World) {
     let scratch_world = main_world.remove_resource::<ScratchMainWorld>().unwrap();
     let inserted_world = std::mem::replace(main_world, scratch_world.0);
     render_world.insert_resource(MainWorld(inserted_world));
-    render_world.run_schedule(ExtractSchedule);
 
-    // move the app world back, as if nothing happened.
-    let inserted_world = render_world.remove_resource::<MainWorld>().unwrap();
-    let scratch_world = std::mem::replace(main_world, inserted_world.0);
-    main_world.insert_resource(ScratchMainWorld(scratch_world));
+    // The guard moves the app world back when dropped, even if an extract system panics.
+    let guard = RestoreMainWorld {
+        main_world,
+        render_world,
+    };
+    guard.render_world.run_schedule(ExtractSchedule);
+}
+
+/// Moves the [`MainWorld`] out of the render world and back into place when dropped.
+struct RestoreMainWorld<'a> {
+    main_world: &'a mut World,
+    render_world: &'a mut World,
+}
+
+impl Drop for RestoreMainWorld<'_> {
+    fn drop(&mut self) {
+        // move the app world back, as if nothing happened.
+        let Some(inserted_world) = self.render_world.remove_resource::<MainWorld>() else {
+            return;
+        };
+        let scratch_world = std::mem::replace(self.main_world, inserted_world.0);
+        self.main_world
+            .insert_resource(ScratchMainWorld(scratch_world));
+    }
 }
 
 /// SAFETY: this function must be called from the main thread.
 unsafe fn initialize_render_app(app: &mut App) {
-    app.init_resource::<ScratchMainWorld>();
+    app.init_resource::<ScratchMainWorld>()
+        .init_resource::<RenderEnabled>();
 
     let mut render_app = SubApp::new();
     render_app.update_schedule = Some(Render.intern());
@@ -450,6 +658,10 @@ unsafe fn initialize_render_app(app: &mut App) {
         .add_schedule(extract_schedule)
         .add_schedule(Render::base_schedule())
         .init_resource::<render_graph::RenderGraph>()
+        .init_resource::<RenderEnabled>()
+        .init_resource::<ExtractParallelism>()
+        .init_resource::<renderer::PendingCommandBuffers>()
+        .init_resource::<renderer::ImmediateDraws>()
         .insert_resource(app.world().resource::<AssetServer>().clone())
         .add_systems(ExtractSchedule, PipelineCache::extract_shaders)
         .add_systems(
@@ -458,6 +670,9 @@ unsafe fn initialize_render_app(app: &mut App) {
                 // This set applies the commands from the extract schedule while the render schedule
                 // is running in parallel with the main app.
                 apply_extract_commands.in_set(RenderSet::ExtractCommands),
+                render_phase::clear_phase_item_index
+                    .in_set(RenderSet::Queue)
+                    .run_if(resource_exists::<render_phase::PhaseItemIndex>),
                 (
                     PipelineCache::process_pipeline_queue_system.before(render_system),
                     render_system,
@@ -467,36 +682,7 @@ unsafe fn initialize_render_app(app: &mut App) {
             ),
         );
 
-    render_app.set_extract(|main_world, render_world| {
-        #[cfg(feature = "trace")]
-        let _render_span = bevy_utils::tracing::info_span!("extract main app to render subapp").entered();
-        {
-            #[cfg(feature = "trace")]
-            let _stage_span =
-                bevy_utils::tracing::info_span!("reserve_and_flush")
-                    .entered();
-
-            // reserve all existing main world entities for use in render_app
-            // they can only be spawned using `get_or_spawn()`
-            let total_count = main_world.entities().total_count();
-
-            assert_eq!(
-                render_world.entities().len(),
-                0,
-                "An entity was spawned after the entity list was cleared last frame and before the extract schedule began. This is not supported",
-            );
-
-            // SAFETY: This is safe given the clear_entities call in the past frame and the assert above
-            unsafe {
-                render_world
-                    .entities_mut()
-                    .flush_and_reserve_invalid_assuming_no_entities(total_count);
-            }
-        }
-
-        // run extract schedule
-        extract(main_world, render_world);
-    });
+    render_app.set_extract(extract_main_app);
 
     let (sender, receiver) = bevy_time::create_time_channels();
     render_app.insert_resource(sender);
@@ -515,3 +701,133 @@ fn apply_extract_commands(render_world: &mut World) {
             .apply_deferred(render_world);
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource)]
+    struct Extracted;
+
+    #[test]
+    fn extract_is_skipped_when_render_is_disabled() {
+        let mut main_world = World::new();
+        main_world.init_resource::<ScratchMainWorld>();
+        main_world.insert_resource(RenderEnabled(false));
+
+        let mut render_world = World::new();
+        let mut extract_schedule = Schedule::new(ExtractSchedule);
+        extract_schedule.add_systems(|mut commands: Commands| commands.insert_resource(Extracted));
+        render_world.add_schedule(extract_schedule);
+
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert_eq!(
+            render_world.resource::<RenderEnabled>(),
+            &RenderEnabled(false)
+        );
+        assert!(!render_world.contains_resource::<Extracted>());
+
+        main_world.resource_mut::<RenderEnabled>().0 = true;
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert_eq!(
+            render_world.resource::<RenderEnabled>(),
+            &RenderEnabled(true)
+        );
+        assert!(render_world.contains_resource::<Extracted>());
+    }
+
+    #[test]
+    fn extract_is_skipped_without_redraw_request() {
+        let mut main_world = World::new();
+        main_world.init_resource::<ScratchMainWorld>();
+        main_world.insert_resource(RedrawPolicy::OnlyWhenChanged);
+        main_world.init_resource::<RedrawRequested>();
+
+        let mut render_world = World::new();
+        let mut extract_schedule = Schedule::new(ExtractSchedule);
+        extract_schedule.add_systems(|mut commands: Commands| commands.insert_resource(Extracted));
+        render_world.add_schedule(extract_schedule);
+
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert!(render_world.contains_resource::<Extracted>());
+        assert!(!main_world.resource::<RedrawRequested>().is_requested());
+
+        render_world.remove_resource::<Extracted>();
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert_eq!(
+            render_world.resource::<RenderEnabled>(),
+            &RenderEnabled(false)
+        );
+        assert!(!render_world.contains_resource::<Extracted>());
+
+        main_world.resource_mut::<RedrawRequested>().request();
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert!(render_world.contains_resource::<Extracted>());
+    }
+
+    #[test]
+    fn extract_duration_is_recorded() {
+        let mut main_world = World::new();
+        main_world.init_resource::<ScratchMainWorld>();
+
+        let mut render_world = World::new();
+        render_world.add_schedule(Schedule::new(ExtractSchedule));
+        render_world.init_resource::<ExtractDuration>();
+
+        extract_main_app(&mut main_world, &mut render_world);
+        assert!(render_world.resource::<ExtractDuration>().get().is_some());
+
+        // Frames that aren't extracted aren't measured.
+        render_world.insert_resource(ExtractDuration::default());
+        main_world.insert_resource(RenderEnabled(false));
+        extract_main_app(&mut main_world, &mut render_world);
+        assert!(render_world.resource::<ExtractDuration>().get().is_none());
+    }
+
+    #[test]
+    fn main_world_is_restored_when_extract_panics() {
+        let mut main_world = World::new();
+        main_world.init_resource::<ScratchMainWorld>();
+        main_world.insert_resource(RenderEnabled(true));
+
+        let mut render_world = World::new();
+        let mut extract_schedule = Schedule::new(ExtractSchedule);
+        extract_schedule.add_systems(|| panic!("extract system panicked"));
+        render_world.add_schedule(extract_schedule);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            extract_main_app(&mut main_world, &mut render_world);
+        }));
+        assert!(result.is_err());
+
+        assert!(!render_world.contains_resource::<MainWorld>());
+        assert!(main_world.contains_resource::<ScratchMainWorld>());
+        assert_eq!(main_world.resource::<RenderEnabled>(), &RenderEnabled(true));
+    }
+
+    #[test]
+    fn main_world_ref_reads_the_main_world() {
+        let mut main_world = World::new();
+        main_world.init_resource::<ScratchMainWorld>();
+        main_world.insert_resource(RenderEnabled(true));
+        main_world.insert_resource(Extracted);
+
+        let mut render_world = World::new();
+        let mut extract_schedule = Schedule::new(ExtractSchedule);
+        extract_schedule.add_systems(|mut commands: Commands, main_world: MainWorldRef| {
+            if main_world.contains_resource::<Extracted>() {
+                commands.insert_resource(Extracted);
+            }
+        });
+        render_world.add_schedule(extract_schedule);
+
+        extract_main_app(&mut main_world, &mut render_world);
+        apply_extract_commands(&mut render_world);
+        assert!(render_world.contains_resource::<Extracted>());
+    }
+}
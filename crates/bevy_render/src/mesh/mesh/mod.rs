@@ -1152,6 +1152,25 @@ impl MeshVertexBufferLayout {
         &self.layout
     }
 
+    /// Checks that the layout contains all of the `attribute_ids`, e.g. the ones a material's
+    /// shader reads, so specialization can fail with a clear error instead of a pipeline
+    /// validation error.
+    pub fn ensure_attributes(
+        &self,
+        attribute_ids: &[MeshVertexAttributeId],
+    ) -> Result<(), MissingAttributes> {
+        let missing: Vec<_> = attribute_ids
+            .iter()
+            .filter(|id| !self.attribute_ids.contains(id))
+            .copied()
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(MissingAttributes { missing })
+        }
+    }
+
     pub fn get_layout(
         &self,
         attribute_descriptors: &[VertexAttributeDescriptor],
@@ -1194,6 +1213,14 @@ pub struct MissingVertexAttributeError {
     name: &'static str,
 }
 
+/// The attributes missing from a mesh, returned by [`MeshVertexBufferLayout::ensure_attributes`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Mesh is missing attributes required by the material: {missing:?}")]
+pub struct MissingAttributes {
+    /// The requested attributes the mesh doesn't have, in the requested order.
+    pub missing: Vec<MeshVertexAttributeId>,
+}
+
 pub struct VertexAttributeDescriptor {
     pub shader_location: u32,
     pub id: MeshVertexAttributeId,
@@ -1550,6 +1577,27 @@ impl GpuMesh {
     pub fn primitive_topology(&self) -> PrimitiveTopology {
         self.key_bits.primitive_topology()
     }
+
+    /// Returns `true` if the mesh has the vertex attribute `id`.
+    #[inline]
+    pub fn has_attribute(&self, id: impl Into<MeshVertexAttributeId>) -> bool {
+        self.layout.0.contains(id)
+    }
+
+    /// Returns the vertex attributes of the mesh, in vertex buffer order.
+    #[inline]
+    pub fn layout_attributes(&self) -> &[MeshVertexAttributeId] {
+        self.layout.0.attribute_ids()
+    }
+
+    /// Checks that the mesh has all of the `attribute_ids`.
+    /// See [`MeshVertexBufferLayout::ensure_attributes`].
+    pub fn ensure_attributes(
+        &self,
+        attribute_ids: &[MeshVertexAttributeId],
+    ) -> Result<(), MissingAttributes> {
+        self.layout.0.ensure_attributes(attribute_ids)
+    }
 }
 
 /// The index/vertex buffer info of a [`GpuMesh`].
@@ -1852,4 +1900,34 @@ mod tests {
             panic!("Mesh does not have a uv attribute");
         }
     }
+
+    #[test]
+    fn ensure_attributes_reports_missing_attributes() {
+        use super::{MeshVertexBufferLayouts, MissingAttributes};
+
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0, 0.0, 0.0]])
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0]]);
+        let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+
+        assert_eq!(
+            layout
+                .0
+                .ensure_attributes(&[Mesh::ATTRIBUTE_POSITION.id, Mesh::ATTRIBUTE_UV_0.id]),
+            Ok(())
+        );
+        assert_eq!(
+            layout.0.ensure_attributes(&[
+                Mesh::ATTRIBUTE_NORMAL.id,
+                Mesh::ATTRIBUTE_POSITION.id,
+                Mesh::ATTRIBUTE_TANGENT.id,
+            ]),
+            Err(MissingAttributes {
+                missing: vec![Mesh::ATTRIBUTE_NORMAL.id, Mesh::ATTRIBUTE_TANGENT.id]
+            })
+        );
+    }
 }
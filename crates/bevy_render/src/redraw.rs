@@ -0,0 +1,250 @@
+use bevy_app::{App, Last};
+use bevy_ecs::{
+    event::{Event, Events, ManualEventReader},
+    prelude::*,
+    schedule::common_conditions::resource_exists_and_equals,
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+
+/// Whether the renderer draws every frame, or only the frames in which something it draws changed.
+///
+/// This resource lives in the main world.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub enum RedrawPolicy {
+    /// Extracts and renders every frame.
+    #[default]
+    Always,
+    /// Skips extraction and rendering of frames in which nothing was marked as changed in
+    /// [`RedrawRequested`], which suits low-power apps like editors whose frames are mostly
+    /// identical.
+    ///
+    /// A skipped frame doesn't acquire or present a swap chain texture, so the windows keep
+    /// showing the last presented image. The render world is still updated, like when
+    /// [`RenderEnabled`](crate::RenderEnabled) is `false`.
+    ///
+    /// Changes are only detected for what was registered with [`RedrawPolicyApp`], e.g. cameras,
+    /// transforms, visibility, sprites, text, UI nodes and the assets of every
+    /// [`RenderAssetPlugin`](crate::render_asset::RenderAssetPlugin). Frames also keep being
+    /// rendered while the render world has work left over, like pipelines that are still
+    /// compiling or render assets deferred to a later frame. Anything else that affects
+    /// rendering, like custom extracted components or time driven shaders, has to register its
+    /// types too, or call [`RedrawRequested::request`].
+    OnlyWhenChanged,
+}
+
+/// Whether the next frame has to be rendered under [`RedrawPolicy::OnlyWhenChanged`].
+///
+/// This resource lives in the main world. It's set by the systems added by [`RedrawPolicyApp`],
+/// by [`RequestRedraw`](bevy_window::RequestRedraw) events, and manually with
+/// [`RedrawRequested::request`]. It's reset every time a frame is extracted.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RedrawRequested(bool);
+
+impl Default for RedrawRequested {
+    /// The first frame is always rendered.
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl RedrawRequested {
+    /// Requests the next frame to be rendered.
+    pub fn request(&mut self) {
+        self.0 = true;
+    }
+
+    /// Returns `true` if the next frame has to be rendered.
+    pub fn is_requested(&self) -> bool {
+        self.0
+    }
+
+    /// Returns whether a frame was requested and resets the request.
+    pub(crate) fn take(&mut self) -> bool {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// Registers what requests a redraw under [`RedrawPolicy::OnlyWhenChanged`].
+///
+/// The change detection systems only run while that policy is active.
+pub trait RedrawPolicyApp {
+    /// Requests a redraw when a `C` component is added, changed or removed.
+    fn redraw_on_change<C: Component>(&mut self) -> &mut Self;
+
+    /// Requests a redraw when the `R` resource is inserted or changed.
+    fn redraw_on_resource_change<R: Resource>(&mut self) -> &mut Self;
+
+    /// Requests a redraw when an `E` event is sent, e.g. an
+    /// [`AssetEvent`](bevy_asset::AssetEvent).
+    fn redraw_on_event<E: Event>(&mut self) -> &mut Self;
+
+    /// Requests a redraw for every frame that starts with `pending` returning `true` for the
+    /// render world, e.g. while render assets are waiting to be prepared.
+    fn redraw_while_pending(&mut self, pending: fn(&World) -> bool) -> &mut Self;
+}
+
+/// The checks registered with [`RedrawPolicyApp::redraw_while_pending`].
+#[derive(Resource, Default)]
+struct PendingRenderWork(Vec<fn(&World) -> bool>);
+
+impl RedrawPolicyApp for App {
+    fn redraw_on_change<C: Component>(&mut self) -> &mut Self {
+        self.add_systems(
+            Last,
+            request_redraw_on_change::<C>
+                .run_if(resource_exists_and_equals(RedrawPolicy::OnlyWhenChanged)),
+        )
+    }
+
+    fn redraw_on_resource_change<R: Resource>(&mut self) -> &mut Self {
+        self.add_systems(
+            Last,
+            request_redraw_on_resource_change::<R>
+                .run_if(resource_exists_and_equals(RedrawPolicy::OnlyWhenChanged)),
+        )
+    }
+
+    fn redraw_on_event<E: Event>(&mut self) -> &mut Self {
+        self.add_systems(
+            Last,
+            request_redraw_on_event::<E>
+                .run_if(resource_exists_and_equals(RedrawPolicy::OnlyWhenChanged)),
+        )
+    }
+
+    fn redraw_while_pending(&mut self, pending: fn(&World) -> bool) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(PendingRenderWork::default)
+            .0
+            .push(pending);
+        self
+    }
+}
+
+fn request_redraw_on_change<C: Component>(
+    changed: Query<(), Changed<C>>,
+    mut removed: RemovedComponents<C>,
+    mut redraw_requested: ResMut<RedrawRequested>,
+) {
+    let any_removed = !removed.is_empty();
+    removed.clear();
+    if any_removed || !changed.is_empty() {
+        redraw_requested.request();
+    }
+}
+
+fn request_redraw_on_resource_change<R: Resource>(
+    resource: Option<Res<R>>,
+    mut redraw_requested: ResMut<RedrawRequested>,
+) {
+    if resource.is_some_and(|resource| resource.is_changed()) {
+        redraw_requested.request();
+    }
+}
+
+// Reads the events manually so registering events whose plugin isn't added, e.g. asset events in
+// a headless app, doesn't panic.
+fn request_redraw_on_event<E: Event>(
+    events: Option<Res<Events<E>>>,
+    mut reader: Local<ManualEventReader<E>>,
+    mut redraw_requested: ResMut<RedrawRequested>,
+) {
+    let Some(events) = events else {
+        return;
+    };
+    if !reader.is_empty(&events) {
+        reader.clear(&events);
+        redraw_requested.request();
+    }
+}
+
+/// Returns whether the frame that is about to be extracted has to be rendered, consuming the
+/// [`RedrawRequested`] request.
+pub(crate) fn take_redraw_request(main_world: &mut World, render_world: &World) -> bool {
+    match main_world.get_resource::<RedrawPolicy>() {
+        None | Some(RedrawPolicy::Always) => true,
+        Some(RedrawPolicy::OnlyWhenChanged) => {
+            let requested = main_world
+                .get_resource_mut::<RedrawRequested>()
+                .map_or(true, |mut redraw_requested| {
+                    redraw_requested.bypass_change_detection().take()
+                });
+            requested
+                || main_world
+                    .get_resource::<PendingRenderWork>()
+                    .is_some_and(|checks| checks.0.iter().any(|pending| pending(render_world)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_transform::components::Transform;
+
+    #[test]
+    fn only_changed_frames_are_rendered() {
+        let render_world = World::new();
+        let mut app = App::new();
+        app.insert_resource(RedrawPolicy::OnlyWhenChanged)
+            .init_resource::<RedrawRequested>()
+            .redraw_on_change::<Transform>();
+
+        // The first frame is always rendered.
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+
+        app.update();
+        assert!(!take_redraw_request(app.world_mut(), &render_world));
+
+        let entity = app.world_mut().spawn(Transform::default()).id();
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+
+        app.update();
+        assert!(!take_redraw_request(app.world_mut(), &render_world));
+
+        app.world_mut()
+            .get_mut::<Transform>(entity)
+            .unwrap()
+            .translation
+            .x = 1.0;
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+
+        app.world_mut().despawn(entity);
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+
+        app.update();
+        assert!(!take_redraw_request(app.world_mut(), &render_world));
+
+        app.insert_resource(RedrawPolicy::Always);
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+    }
+
+    #[derive(Resource)]
+    struct PendingWork;
+
+    #[test]
+    fn pending_render_work_is_rendered() {
+        let mut render_world = World::new();
+        let mut app = App::new();
+        app.insert_resource(RedrawPolicy::OnlyWhenChanged)
+            .init_resource::<RedrawRequested>()
+            .redraw_while_pending(|render_world| render_world.contains_resource::<PendingWork>());
+
+        app.update();
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+        assert!(!take_redraw_request(app.world_mut(), &render_world));
+
+        render_world.insert_resource(PendingWork);
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+        assert!(take_redraw_request(app.world_mut(), &render_world));
+
+        render_world.remove_resource::<PendingWork>();
+        assert!(!take_redraw_request(app.world_mut(), &render_world));
+    }
+}
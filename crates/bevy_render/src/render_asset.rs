@@ -1,8 +1,12 @@
-use crate::{ExtractSchedule, MainWorld, Render, RenderApp, RenderSet};
+use crate::{
+    extract_resource::{ExtractResource, ExtractResourcePlugin},
+    redraw::RedrawPolicyApp,
+    ExtractSchedule, MainWorld, Render, RenderApp, RenderSet,
+};
 use bevy_app::{App, Plugin, SubApp};
-use bevy_asset::{Asset, AssetEvent, AssetId, Assets};
+use bevy_asset::{Asset, AssetEvent, AssetId, Assets, UntypedAssetId};
 use bevy_ecs::{
-    prelude::{Commands, EventReader, IntoSystemConfigs, ResMut, Resource},
+    prelude::{Commands, EventReader, IntoSystemConfigs, Res, ResMut, Resource},
     schedule::SystemConfigs,
     system::{StaticSystemParam, SystemParam, SystemParamItem, SystemState},
     world::{FromWorld, Mut},
@@ -133,12 +137,20 @@ impl<A: RenderAsset, AFTER: RenderAssetDependency + 'static> Plugin
     for RenderAssetPlugin<A, AFTER>
 {
     fn build(&self, app: &mut App) {
-        app.init_resource::<CachedExtractRenderAssetSystemState<A>>();
+        app.init_resource::<CachedExtractRenderAssetSystemState<A>>()
+            .add_plugins(ExtractResourcePlugin::<PinnedRenderAssets<A>>::default())
+            .redraw_on_event::<AssetEvent<A::SourceAsset>>()
+            .redraw_while_pending(|render_world| {
+                render_world
+                    .get_resource::<PrepareNextFrameAssets<A>>()
+                    .is_some_and(|prepare_next_frame| !prepare_next_frame.assets.is_empty())
+            });
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<ExtractedAssets<A>>()
                 .init_resource::<RenderAssets<A>>()
                 .init_resource::<PrepareNextFrameAssets<A>>()
+                .init_resource::<RenderAssetBytesResident>()
                 .add_systems(ExtractSchedule, extract_render_asset::<A>);
             AFTER::register_system(
                 render_app,
@@ -239,7 +251,7 @@ impl<A: RenderAsset> FromWorld for CachedExtractRenderAssetSystemState<A> {
 /// This system extracts all created or modified assets of the corresponding [`RenderAsset::SourceAsset`] type
 /// into the "render world".
 fn extract_render_asset<A: RenderAsset>(mut commands: Commands, mut main_world: ResMut<MainWorld>) {
-    main_world.resource_scope(
+    main_world.world_mut().resource_scope(
         |world, mut cached_state: Mut<CachedExtractRenderAssetSystemState<A>>| {
             let (mut events, mut assets) = cached_state.state.get_mut(world);
 
@@ -292,6 +304,54 @@ fn extract_render_asset<A: RenderAsset>(mut commands: Commands, mut main_world:
     );
 }
 
+/// The render assets of type `A` that are prepared as soon as they're extracted, regardless of the
+/// [`RenderAssetBytesPerFrame`] limit, e.g. UI texture atlases and font textures that have to be
+/// available on the frame they're first used.
+///
+/// Insert this resource in the main world; it's extracted to the render world whenever it
+/// changes. Pinned assets are still retried on later frames if [`RenderAsset::prepare_asset`]
+/// asks for it. Their uploads count towards [`RenderAssetBytesPerFrame::used_this_frame`], but
+/// not towards its limit, so they don't delay other assets.
+#[derive(Resource)]
+pub struct PinnedRenderAssets<A: RenderAsset>(HashSet<AssetId<A::SourceAsset>>);
+
+impl<A: RenderAsset> Default for PinnedRenderAssets<A> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<A: RenderAsset> Clone for PinnedRenderAssets<A> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<A: RenderAsset> PinnedRenderAssets<A> {
+    /// Pins the asset, returning `false` if it was already pinned.
+    pub fn pin(&mut self, id: impl Into<AssetId<A::SourceAsset>>) -> bool {
+        self.0.insert(id.into())
+    }
+
+    /// Unpins the asset, returning `false` if it wasn't pinned.
+    pub fn unpin(&mut self, id: impl Into<AssetId<A::SourceAsset>>) -> bool {
+        self.0.remove(&id.into())
+    }
+
+    /// Returns `true` if the asset is pinned.
+    pub fn is_pinned(&self, id: impl Into<AssetId<A::SourceAsset>>) -> bool {
+        self.0.contains(&id.into())
+    }
+}
+
+impl<A: RenderAsset> ExtractResource for PinnedRenderAssets<A> {
+    type Source = Self;
+
+    fn extract_resource(source: &Self) -> Self {
+        source.clone()
+    }
+}
+
 // TODO: consider storing inside system?
 /// All assets that should be prepared next frame.
 #[derive(Resource)]
@@ -315,8 +375,15 @@ pub fn prepare_assets<A: RenderAsset>(
     mut prepare_next_frame: ResMut<PrepareNextFrameAssets<A>>,
     param: StaticSystemParam<<A as RenderAsset>::Param>,
     mut bpf: ResMut<RenderAssetBytesPerFrame>,
+    mut resident: ResMut<RenderAssetBytesResident>,
+    pinned_assets: Option<Res<PinnedRenderAssets<A>>>,
 ) {
     let mut wrote_asset_count = 0;
+    let is_pinned = |id: AssetId<A::SourceAsset>| {
+        pinned_assets
+            .as_ref()
+            .is_some_and(|pinned_assets| pinned_assets.is_pinned(id))
+    };
 
     let mut param = param.into_inner();
     let queued_assets = std::mem::take(&mut prepare_next_frame.assets);
@@ -326,12 +393,14 @@ pub fn prepare_assets<A: RenderAsset>(
             continue;
         }
 
-        let write_bytes = if let Some(size) = A::byte_len(&extracted_asset) {
+        let byte_len = A::byte_len(&extracted_asset);
+        let pinned = is_pinned(id);
+        let write_bytes = if let Some(size) = byte_len {
             // we could check if available bytes > byte_len here, but we want to make some
             // forward progress even if the asset is larger than the max bytes per frame.
             // this way we always write at least one (sized) asset per frame.
             // in future we could also consider partial asset uploads.
-            if bpf.exhausted() {
+            if bpf.exhausted() && !pinned {
                 prepare_next_frame.assets.push((id, extracted_asset));
                 continue;
             }
@@ -343,7 +412,10 @@ pub fn prepare_assets<A: RenderAsset>(
         match A::prepare_asset(extracted_asset, &mut param) {
             Ok(prepared_asset) => {
                 render_assets.insert(id, prepared_asset);
-                bpf.write_bytes(write_bytes);
+                bpf.write_bytes(write_bytes, pinned);
+                if let Some(byte_len) = byte_len {
+                    resident.insert(id, byte_len);
+                }
                 wrote_asset_count += 1;
             }
             Err(PrepareAssetError::RetryNextUpdate(extracted_asset)) => {
@@ -354,6 +426,7 @@ pub fn prepare_assets<A: RenderAsset>(
 
     for removed in extracted_assets.removed.drain() {
         render_assets.remove(removed);
+        resident.remove(removed);
     }
 
     for (id, extracted_asset) in extracted_assets.extracted.drain(..) {
@@ -361,9 +434,12 @@ pub fn prepare_assets<A: RenderAsset>(
         // any users will not see the old asset after a new asset is extracted,
         // even if the new asset is not yet ready or we are out of bytes to write.
         render_assets.remove(id);
+        resident.remove(id);
 
-        let write_bytes = if let Some(size) = A::byte_len(&extracted_asset) {
-            if bpf.exhausted() {
+        let byte_len = A::byte_len(&extracted_asset);
+        let pinned = is_pinned(id);
+        let write_bytes = if let Some(size) = byte_len {
+            if bpf.exhausted() && !pinned {
                 prepare_next_frame.assets.push((id, extracted_asset));
                 continue;
             }
@@ -375,7 +451,10 @@ pub fn prepare_assets<A: RenderAsset>(
         match A::prepare_asset(extracted_asset, &mut param) {
             Ok(prepared_asset) => {
                 render_assets.insert(id, prepared_asset);
-                bpf.write_bytes(write_bytes);
+                bpf.write_bytes(write_bytes, pinned);
+                if let Some(byte_len) = byte_len {
+                    resident.insert(id, byte_len);
+                }
                 wrote_asset_count += 1;
             }
             Err(PrepareAssetError::RetryNextUpdate(extracted_asset)) => {
@@ -397,10 +476,15 @@ pub fn prepare_assets<A: RenderAsset>(
 /// A resource that attempts to limit the amount of data transferred from cpu to gpu
 /// each frame, preventing choppy frames at the cost of waiting longer for gpu assets
 /// to become available
+///
+/// The resource is extracted to the render world whenever it changes, so the limit should
+/// be changed on the main world copy, e.g. with [`Self::set_limit`]. The per-frame counters
+/// are only meaningful on the render world copy.
 #[derive(Resource, Default, Debug, Clone, Copy, ExtractResource)]
 pub struct RenderAssetBytesPerFrame {
     pub max_bytes: Option<usize>,
     pub available: usize,
+    written: usize,
 }
 
 impl RenderAssetBytesPerFrame {
@@ -413,12 +497,35 @@ impl RenderAssetBytesPerFrame {
         Self {
             max_bytes: Some(max_bytes),
             available: 0,
+            written: 0,
         }
     }
 
+    /// The number of bytes that may be written per frame, or `None` if uploads are unlimited.
+    pub fn limit(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Changes the number of bytes that may be written per frame. `None` removes the limit.
+    ///
+    /// Bytes already written this frame count against the new limit. This is meant for adaptive
+    /// streaming, e.g. raising the budget while frames are fast and lowering it under load.
+    pub fn set_limit(&mut self, max_bytes: Option<usize>) {
+        self.max_bytes = max_bytes;
+        self.available = max_bytes.map_or(usize::MAX, |max| max.saturating_sub(self.written));
+    }
+
+    /// The number of bytes written by preparing render assets since the last reset.
+    ///
+    /// This is tracked whether or not there is a limit.
+    pub fn used_this_frame(&self) -> usize {
+        self.written
+    }
+
     /// Reset the available bytes. Called once per frame by the [`crate::RenderPlugin`].
     pub fn reset(&mut self) {
         self.available = self.max_bytes.unwrap_or(usize::MAX);
+        self.written = 0;
     }
 
     /// check how many bytes are available since the last reset
@@ -430,9 +537,11 @@ impl RenderAssetBytesPerFrame {
         required_bytes.min(self.available)
     }
 
-    /// decrease the available bytes for the current frame
-    fn write_bytes(&mut self, bytes: usize) {
-        if self.max_bytes.is_none() {
+    /// decrease the available bytes for the current frame, unless the bytes are written by a
+    /// pinned asset which isn't subject to the limit
+    fn write_bytes(&mut self, bytes: usize, pinned: bool) {
+        self.written = self.written.saturating_add(bytes);
+        if self.max_bytes.is_none() || pinned {
             return;
         }
 
@@ -445,3 +554,179 @@ impl RenderAssetBytesPerFrame {
         self.max_bytes.is_some() && self.available == 0
     }
 }
+
+/// Tracks the number of bytes uploaded to the GPU by prepared [`RenderAsset`]s.
+///
+/// Only assets implementing [`RenderAsset::byte_len`] are tracked. An asset's bytes are counted
+/// from the moment it is prepared until it is removed or replaced by an updated version.
+#[derive(Resource, Default, Debug)]
+pub struct RenderAssetBytesResident {
+    bytes: usize,
+    bytes_written: u64,
+    assets: HashMap<UntypedAssetId, usize>,
+}
+
+impl RenderAssetBytesResident {
+    /// The total number of bytes of all currently prepared render assets.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// The total number of bytes written by preparing render assets since the app started.
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    fn insert(&mut self, id: impl Into<UntypedAssetId>, bytes: usize) {
+        if let Some(previous) = self.assets.insert(id.into(), bytes) {
+            self.bytes -= previous;
+        }
+        self.bytes += bytes;
+        self.bytes_written += bytes as u64;
+    }
+
+    fn remove(&mut self, id: impl Into<UntypedAssetId>) {
+        if let Some(bytes) = self.assets.remove(&id.into()) {
+            self.bytes -= bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::Handle;
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use bevy_reflect::TypePath;
+
+    #[derive(Asset, TypePath, Clone)]
+    struct TestAsset(Vec<u8>);
+
+    struct GpuTestAsset;
+
+    impl RenderAsset for GpuTestAsset {
+        type SourceAsset = TestAsset;
+        type Param = ();
+
+        fn byte_len(source_asset: &Self::SourceAsset) -> Option<usize> {
+            Some(source_asset.0.len())
+        }
+
+        fn prepare_asset(
+            _source_asset: Self::SourceAsset,
+            _param: &mut SystemParamItem<Self::Param>,
+        ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
+            Ok(GpuTestAsset)
+        }
+    }
+
+    fn extract(world: &mut World, extracted: Vec<(AssetId<TestAsset>, TestAsset)>) {
+        let added = extracted.iter().map(|(id, _)| *id).collect();
+        world.insert_resource(ExtractedAssets::<GpuTestAsset> {
+            extracted,
+            removed: HashSet::default(),
+            added,
+        });
+    }
+
+    #[test]
+    fn resident_bytes_track_prepared_assets() {
+        let mut world = World::new();
+        world.init_resource::<RenderAssets<GpuTestAsset>>();
+        world.init_resource::<PrepareNextFrameAssets<GpuTestAsset>>();
+        world.init_resource::<RenderAssetBytesPerFrame>();
+        world.init_resource::<RenderAssetBytesResident>();
+
+        let a = Handle::<TestAsset>::weak_from_u128(1).id();
+        let b = Handle::<TestAsset>::weak_from_u128(2).id();
+
+        extract(
+            &mut world,
+            vec![(a, TestAsset(vec![0; 16])), (b, TestAsset(vec![0; 32]))],
+        );
+        world.run_system_once(prepare_assets::<GpuTestAsset>);
+        assert_eq!(world.resource::<RenderAssetBytesResident>().bytes(), 48);
+
+        // Updating an asset replaces its previous size.
+        extract(&mut world, vec![(a, TestAsset(vec![0; 8]))]);
+        world.run_system_once(prepare_assets::<GpuTestAsset>);
+        assert_eq!(world.resource::<RenderAssetBytesResident>().bytes(), 40);
+
+        world.insert_resource(ExtractedAssets::<GpuTestAsset> {
+            extracted: Vec::new(),
+            removed: [b].into_iter().collect(),
+            added: HashSet::default(),
+        });
+        world.run_system_once(prepare_assets::<GpuTestAsset>);
+
+        let resident = world.resource::<RenderAssetBytesResident>();
+        assert_eq!(resident.bytes(), 8);
+        assert_eq!(resident.bytes_written(), 56);
+    }
+
+    #[test]
+    fn bytes_per_frame_limit_can_change_at_runtime() {
+        let mut bpf = RenderAssetBytesPerFrame::new(100);
+        bpf.reset();
+        assert_eq!(bpf.limit(), Some(100));
+
+        bpf.write_bytes(30, false);
+        assert_eq!(bpf.used_this_frame(), 30);
+
+        // Bytes written this frame count against the new limit.
+        bpf.set_limit(Some(50));
+        assert_eq!(bpf.available_bytes(100), 20);
+        bpf.set_limit(Some(20));
+        assert!(bpf.exhausted());
+
+        bpf.reset();
+        assert_eq!(bpf.used_this_frame(), 0);
+        assert_eq!(bpf.available_bytes(100), 20);
+
+        bpf.set_limit(None);
+        bpf.write_bytes(1000, false);
+        assert!(!bpf.exhausted());
+        assert_eq!(bpf.used_this_frame(), 1000);
+        bpf.reset();
+        assert_eq!(bpf.used_this_frame(), 0);
+    }
+
+    #[test]
+    fn pinned_assets_bypass_bytes_per_frame_limit() {
+        let mut world = World::new();
+        world.init_resource::<RenderAssets<GpuTestAsset>>();
+        world.init_resource::<PrepareNextFrameAssets<GpuTestAsset>>();
+        world.init_resource::<RenderAssetBytesResident>();
+        let mut bpf = RenderAssetBytesPerFrame::new(1);
+        bpf.reset();
+        world.insert_resource(bpf);
+
+        let [small, large, pinned] = [1, 2, 3].map(|i| Handle::<TestAsset>::weak_from_u128(i).id());
+        let mut pinned_assets = PinnedRenderAssets::<GpuTestAsset>::default();
+        pinned_assets.pin(pinned);
+        world.insert_resource(pinned_assets);
+
+        // The first unpinned asset exhausts the budget, deferring the second one but not the
+        // pinned one.
+        extract(
+            &mut world,
+            vec![
+                (small, TestAsset(vec![0; 4])),
+                (large, TestAsset(vec![0; 1024])),
+                (pinned, TestAsset(vec![0; 1024])),
+            ],
+        );
+        world.run_system_once(prepare_assets::<GpuTestAsset>);
+
+        let render_assets = world.resource::<RenderAssets<GpuTestAsset>>();
+        assert!(render_assets.get(small).is_some());
+        assert!(render_assets.get(large).is_none());
+        assert!(render_assets.get(pinned).is_some());
+        assert_eq!(
+            world
+                .resource::<RenderAssetBytesPerFrame>()
+                .used_this_frame(),
+            1028
+        );
+    }
+}
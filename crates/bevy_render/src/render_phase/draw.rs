@@ -48,6 +48,7 @@ pub struct DrawFunctionId(u32);
 pub struct DrawFunctionsInternal<P: PhaseItem> {
     pub draw_functions: Vec<Box<dyn Draw<P>>>,
     pub indices: TypeIdMap<DrawFunctionId>,
+    names: Vec<&'static str>,
 }
 
 impl<P: PhaseItem> DrawFunctionsInternal<P> {
@@ -68,6 +69,7 @@ impl<P: PhaseItem> DrawFunctionsInternal<P> {
         let id = DrawFunctionId(self.draw_functions.len().try_into().unwrap());
         self.draw_functions.push(Box::new(draw_function));
         self.indices.insert(TypeId::of::<T>(), id);
+        self.names.push(std::any::type_name::<T>());
         id
     }
 
@@ -96,6 +98,28 @@ impl<P: PhaseItem> DrawFunctionsInternal<P> {
             )
         })
     }
+
+    /// Retrieves the type name of the [`Draw`] function corresponding to the `id`.
+    ///
+    /// This is the name of the type `T` the function was registered with in [`Self::add_with()`],
+    /// and is intended for debugging and inspection purposes.
+    pub fn name(&self, id: DrawFunctionId) -> Option<&str> {
+        self.names.get(id.0 as usize).copied()
+    }
+
+    /// Retrieves the id of the first [`Draw`] function registered with the type name `name`.
+    pub fn get_by_name(&self, name: &str) -> Option<DrawFunctionId> {
+        self.iter_names()
+            .find_map(|(id, function_name)| (function_name == name).then_some(id))
+    }
+
+    /// Returns an iterator over the ids and type names of all registered [`Draw`] functions.
+    pub fn iter_names(&self) -> impl Iterator<Item = (DrawFunctionId, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (DrawFunctionId(index as u32), *name))
+    }
 }
 
 /// Stores all draw functions for the [`PhaseItem`] type hidden behind a reader-writer lock.
@@ -112,6 +136,7 @@ impl<P: PhaseItem> Default for DrawFunctions<P> {
             internal: RwLock::new(DrawFunctionsInternal {
                 draw_functions: Vec::new(),
                 indices: Default::default(),
+                names: Vec::new(),
             }),
         }
     }
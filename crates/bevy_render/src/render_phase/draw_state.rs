@@ -603,6 +603,31 @@ impl<'a> TrackedRenderPass<'a> {
         detailed_trace!("set blend constant: {:?}", color);
         self.pass.set_blend_constant(wgpu::Color::from(color));
     }
+
+    /// Starts an occlusion query at `query_index` of the pass's occlusion query set.
+    ///
+    /// The pass must have been created with an
+    /// [`occlusion_query_set`](crate::render_resource::RenderPassDescriptor::occlusion_query_set).
+    /// The query counts the samples of the following draws that pass the depth and stencil tests,
+    /// until [`end_occlusion_query`] is called.
+    ///
+    /// Note that [`begin_occlusion_query`] and [`end_occlusion_query`] must always be called in
+    /// pairs, and occlusion queries can't be nested.
+    ///
+    /// [`begin_occlusion_query`]: TrackedRenderPass::begin_occlusion_query
+    /// [`end_occlusion_query`]: TrackedRenderPass::end_occlusion_query
+    pub fn begin_occlusion_query(&mut self, query_index: u32) {
+        detailed_trace!("begin occlusion query: {}", query_index);
+        self.pass.begin_occlusion_query(query_index);
+    }
+
+    /// Ends the occlusion query started by [`begin_occlusion_query`].
+    ///
+    /// [`begin_occlusion_query`]: TrackedRenderPass::begin_occlusion_query
+    pub fn end_occlusion_query(&mut self) {
+        detailed_trace!("end occlusion query");
+        self.pass.end_occlusion_query();
+    }
 }
 
 impl WriteTimestamp for TrackedRenderPass<'_> {
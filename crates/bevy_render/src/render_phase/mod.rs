@@ -26,6 +26,7 @@
 
 mod draw;
 mod draw_state;
+mod phase_item_index;
 mod rangefinder;
 
 use bevy_app::{App, Plugin};
@@ -35,6 +36,7 @@ pub use draw::*;
 pub use draw_state::*;
 use encase::{internal::WriteInto, ShaderSize};
 use nonmax::NonMaxU32;
+pub use phase_item_index::*;
 pub use rangefinder::*;
 
 use crate::{
@@ -323,9 +325,10 @@ where
             // locks.
         }
 
-        self.render_batchable_meshes(render_pass, world, view);
-        self.render_unbatchable_meshes(render_pass, world, view);
-        self.render_non_meshes(render_pass, world, view);
+        let mut remaining_draws = PhaseItemBudget::remaining_draws(world);
+        self.render_batchable_meshes(render_pass, world, view, &mut remaining_draws);
+        self.render_unbatchable_meshes(render_pass, world, view, &mut remaining_draws);
+        self.render_non_meshes(render_pass, world, view, &mut remaining_draws);
     }
 
     /// Renders all batchable meshes queued in this phase.
@@ -334,6 +337,7 @@ where
         render_pass: &mut TrackedRenderPass<'w>,
         world: &'w World,
         view: Entity,
+        remaining_draws: &mut usize,
     ) {
         let draw_functions = world.resource::<DrawFunctions<BPI>>();
         let mut draw_functions = draw_functions.write();
@@ -355,6 +359,10 @@ where
                     continue;
                 };
 
+                if *remaining_draws == 0 {
+                    return;
+                }
+                *remaining_draws -= 1;
                 draw_function.draw(world, render_pass, view, &binned_phase_item);
             }
         }
@@ -366,6 +374,7 @@ where
         render_pass: &mut TrackedRenderPass<'w>,
         world: &'w World,
         view: Entity,
+        remaining_draws: &mut usize,
     ) {
         let draw_functions = world.resource::<DrawFunctions<BPI>>();
         let mut draw_functions = draw_functions.write();
@@ -412,6 +421,10 @@ where
                     continue;
                 };
 
+                if *remaining_draws == 0 {
+                    return;
+                }
+                *remaining_draws -= 1;
                 draw_function.draw(world, render_pass, view, &binned_phase_item);
             }
         }
@@ -425,6 +438,7 @@ where
         render_pass: &mut TrackedRenderPass<'w>,
         world: &'w World,
         view: Entity,
+        remaining_draws: &mut usize,
     ) {
         let draw_functions = world.resource::<DrawFunctions<BPI>>();
         let mut draw_functions = draw_functions.write();
@@ -439,6 +453,10 @@ where
                 continue;
             };
 
+            if *remaining_draws == 0 {
+                return;
+            }
+            *remaining_draws -= 1;
             draw_function.draw(world, render_pass, view, &binned_phase_item);
         }
     }
@@ -449,6 +467,34 @@ where
             && self.non_mesh_items.is_empty()
     }
 
+    /// Returns the number of draws this phase is rendered with, once batched.
+    ///
+    /// Each batch of a batch set is one draw, and every unbatchable entity and non-mesh
+    /// item is drawn on its own.
+    pub fn batch_count(&self) -> usize {
+        self.batch_sets.iter().map(SmallVec::len).sum::<usize>()
+            + self
+                .unbatchable_mesh_values
+                .values()
+                .map(|unbatchables| unbatchables.entities.len())
+                .sum::<usize>()
+            + self.non_mesh_items.len()
+    }
+
+    /// Returns the number of entities binned in this phase.
+    pub fn len(&self) -> usize {
+        self.batchable_mesh_values
+            .values()
+            .map(Vec::len)
+            .sum::<usize>()
+            + self
+                .unbatchable_mesh_values
+                .values()
+                .map(|unbatchables| unbatchables.entities.len())
+                .sum::<usize>()
+            + self.non_mesh_items.len()
+    }
+
     pub fn clear(&mut self) {
         self.batchable_mesh_keys.clear();
         self.batchable_mesh_values.clear();
@@ -790,6 +836,7 @@ where
         let mut draw_functions = draw_functions.write();
         draw_functions.prepare(world);
 
+        let mut remaining_draws = PhaseItemBudget::remaining_draws(world);
         let mut index = 0;
         while index < items.len() {
             let item = &items[index];
@@ -797,6 +844,10 @@ where
             if batch_range.is_empty() {
                 index += 1;
             } else {
+                if remaining_draws == 0 {
+                    return;
+                }
+                remaining_draws -= 1;
                 let draw_function = draw_functions.get_mut(item.draw_function()).unwrap();
                 draw_function.draw(world, render_pass, view, item);
                 index += batch_range.len();
@@ -805,6 +856,28 @@ where
     }
 }
 
+/// A render world resource limiting the number of draws each render phase issues for a view.
+///
+/// This is a profiling aid: comparing the GPU time of a pass when only its first `max_draws`
+/// batches are drawn to when all of them are shows how much of the cost comes from overdraw and
+/// how much from the number of draws. The remaining items are skipped, so the output is
+/// incomplete while this is set. The limit applies separately to every
+/// [`BinnedRenderPhase::render`] and [`SortedRenderPhase::render_range`] call, in the order the
+/// items are drawn.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct PhaseItemBudget {
+    /// The maximum number of draws per phase and view.
+    pub max_draws: usize,
+}
+
+impl PhaseItemBudget {
+    fn remaining_draws(world: &World) -> usize {
+        world
+            .get_resource::<Self>()
+            .map_or(usize::MAX, |budget| budget.max_draws)
+    }
+}
+
 /// An item (entity of the render world) which will be drawn to a texture or the screen,
 /// as part of a render phase.
 ///
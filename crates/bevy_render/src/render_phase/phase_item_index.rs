@@ -0,0 +1,91 @@
+use std::any::type_name;
+
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
+
+use crate::render_resource::CachedRenderPipelineId;
+
+use super::{
+    CachedRenderPipelinePhaseItem, DrawFunctionId, PhaseItem, SortedPhaseItem,
+    ViewSortedRenderPhases,
+};
+
+/// A reverse lookup from entities to the phase items queued for them this frame.
+///
+/// This is a debugging aid that answers questions like "is my sprite queued at all, and with
+/// which pipeline?". It's only populated while the resource exists in the render world, so
+/// indexing costs nothing unless a tool opts in with
+/// `render_app.init_resource::<PhaseItemIndex>()`.
+///
+/// Phases are indexed by [`index_sorted_phase_items`] after they are sorted, and the index is
+/// cleared again during [`RenderSet::Queue`](crate::RenderSet::Queue) of the next frame.
+#[derive(Resource, Default, Debug)]
+pub struct PhaseItemIndex {
+    entries: EntityHashMap<Vec<IndexedPhaseItem>>,
+}
+
+/// A phase item recorded in the [`PhaseItemIndex`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexedPhaseItem {
+    /// The view whose render phase contains the item.
+    pub view: Entity,
+    /// The type name of the phase item, e.g. `bevy_core_pipeline::core_2d::Transparent2d`.
+    pub phase: &'static str,
+    /// The pipeline the item will be drawn with.
+    pub pipeline: CachedRenderPipelineId,
+    /// The draw function the item will be drawn with.
+    pub draw_function: DrawFunctionId,
+}
+
+impl PhaseItemIndex {
+    /// Returns the phase items queued for `entity`, in every indexed phase and view.
+    pub fn get(&self, entity: Entity) -> &[IndexedPhaseItem] {
+        self.entries.get(&entity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterates over all indexed entities and their phase items.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &[IndexedPhaseItem])> {
+        self.entries
+            .iter()
+            .map(|(entity, items)| (*entity, items.as_slice()))
+    }
+
+    /// Records a phase item queued for `entity`.
+    pub fn insert(&mut self, entity: Entity, item: IndexedPhaseItem) {
+        self.entries.entry(entity).or_default().push(item);
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Clears the [`PhaseItemIndex`] before the phases of the current frame are queued.
+pub fn clear_phase_item_index(mut index: ResMut<PhaseItemIndex>) {
+    index.clear();
+}
+
+/// Records every item of the [`ViewSortedRenderPhases<I>`] in the [`PhaseItemIndex`].
+///
+/// This should run in [`RenderSet::PhaseSort`](crate::RenderSet::PhaseSort) with a
+/// `run_if(resource_exists::<PhaseItemIndex>)` condition.
+pub fn index_sorted_phase_items<I>(
+    render_phases: Res<ViewSortedRenderPhases<I>>,
+    mut index: ResMut<PhaseItemIndex>,
+) where
+    I: SortedPhaseItem + CachedRenderPipelinePhaseItem,
+{
+    for (view, phase) in render_phases.iter() {
+        for item in &phase.items {
+            index.insert(
+                item.entity(),
+                IndexedPhaseItem {
+                    view: *view,
+                    phase: type_name::<I>(),
+                    pipeline: item.cached_pipeline(),
+                    draw_function: item.draw_function(),
+                },
+            );
+        }
+    }
+}
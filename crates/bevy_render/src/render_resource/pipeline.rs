@@ -107,6 +107,46 @@ pub struct RenderPipelineDescriptor {
     pub multisample: MultisampleState,
     /// The compiled fragment stage, its entry point, and the color targets.
     pub fragment: Option<FragmentState>,
+    /// Values of the pipeline-overridable constants (WGSL `override` declarations) of the
+    /// vertex and fragment shaders, keyed by name or by numeric `@id`.
+    /// Supply an empty vector to use the default values declared in the shaders.
+    pub constants: Vec<(String, f64)>,
+    /// Overrides the [`CompilationOptions`] of the vertex stage.
+    /// If `None`, the vertex stage is compiled with the shared [`constants`](Self::constants).
+    pub vertex_compilation_options: Option<CompilationOptions>,
+    /// Overrides the [`CompilationOptions`] of the fragment stage.
+    /// If `None`, the fragment stage is compiled with the shared [`constants`](Self::constants).
+    pub fragment_compilation_options: Option<CompilationOptions>,
+}
+
+/// Options for compiling a single shader stage of a pipeline.
+///
+/// This is the owned counterpart of [`wgpu::PipelineCompilationOptions`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompilationOptions {
+    /// Values of the pipeline-overridable constants (WGSL `override` declarations) of the
+    /// stage, keyed by name or by numeric `@id`.
+    pub constants: Vec<(String, f64)>,
+    /// Whether workgroup memory is zero-initialized before the shader runs.
+    /// Disabling this avoids the cost of the initialization for shaders that always write
+    /// workgroup memory before reading it.
+    pub zero_initialize_workgroup_memory: bool,
+}
+
+impl CompilationOptions {
+    /// The options used for stages without their own options: the given shared `constants`, and
+    /// workgroup memory left uninitialized.
+    pub fn shared(constants: &[(String, f64)]) -> Self {
+        Self {
+            constants: constants.to_vec(),
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+
+    /// Returns the constants in the form expected by [`wgpu::PipelineCompilationOptions`].
+    pub fn constants_map(&self) -> std::collections::HashMap<String, f64> {
+        self.constants.iter().cloned().collect()
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -158,6 +198,28 @@ impl VertexBufferLayout {
             attributes,
         }
     }
+
+    /// Returns this layout with the `shader_location` of every attribute increased by `location`.
+    ///
+    /// This is useful to append a buffer to the layouts of another pipeline, e.g. an instance
+    /// buffer after the vertex buffer of a mesh, whose attributes take the first locations:
+    ///
+    /// ```
+    /// # use bevy_render::render_resource::{VertexBufferLayout, VertexFormat, VertexStepMode};
+    /// // Locations 0 to 2 are taken by the position, normal and UV attributes of the mesh.
+    /// let instance_layout = VertexBufferLayout::from_vertex_formats(
+    ///     VertexStepMode::Instance,
+    ///     [VertexFormat::Float32x4, VertexFormat::Float32x4],
+    /// )
+    /// .offset_locations_by(3);
+    /// assert_eq!(instance_layout.attributes[1].shader_location, 4);
+    /// ```
+    pub fn offset_locations_by(mut self, location: u32) -> Self {
+        for attribute in &mut self.attributes {
+            attribute.shader_location += location;
+        }
+        self
+    }
 }
 
 /// Describes the fragment process in a render pipeline.
@@ -185,4 +247,9 @@ pub struct ComputePipelineDescriptor {
     /// The name of the entry point in the compiled shader. There must be a
     /// function with this name in the shader.
     pub entry_point: Cow<'static, str>,
+    /// Values of the pipeline-overridable constants (WGSL `override` declarations) of the
+    /// shader, keyed by name or by numeric `@id`. Overrides can only be used in expressions of
+    /// function bodies, not in attributes like `@workgroup_size`.
+    /// Supply an empty vector to use the default values declared in the shader.
+    pub constants: Vec<(String, f64)>,
 }
@@ -11,7 +11,7 @@ use bevy_utils::hashbrown::hash_map::EntryRef;
 use bevy_utils::{
     default,
     tracing::{debug, error},
-    HashMap, HashSet,
+    Duration, HashMap, HashSet, Instant,
 };
 use naga::valid::Capabilities;
 use std::{
@@ -20,7 +20,10 @@ use std::{
     hash::Hash,
     mem,
     ops::Deref,
-    sync::{Arc, Mutex, PoisonError},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError,
+    },
 };
 use thiserror::Error;
 #[cfg(feature = "shader_format_spirv")]
@@ -44,6 +47,18 @@ pub enum PipelineDescriptor {
     ComputePipelineDescriptor(Box<ComputePipelineDescriptor>),
 }
 
+impl PipelineDescriptor {
+    /// Returns the debug label of the pipeline, if any.
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            PipelineDescriptor::RenderPipelineDescriptor(descriptor) => descriptor.label.as_deref(),
+            PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                descriptor.label.as_deref()
+            }
+        }
+    }
+}
+
 /// A pipeline defining the data layout and shader logic for a specific GPU task.
 ///
 /// Used to store an heterogenous collection of render and compute pipelines together.
@@ -99,6 +114,9 @@ pub enum CachedPipelineState {
     Ok(Pipeline),
     /// An error occurred while trying to create the pipeline GPU object.
     Err(PipelineCacheError),
+    /// The pipeline was removed with [`PipelineCache::remove_pipeline`]. Its GPU object was
+    /// dropped and it will never be created again.
+    Removed,
 }
 
 impl CachedPipelineState {
@@ -122,8 +140,21 @@ impl CachedPipelineState {
                 panic!("Pipeline has not been compiled yet. It is still in the 'Creating' state.")
             }
             CachedPipelineState::Err(err) => panic!("{}", err),
+            CachedPipelineState::Removed => {
+                panic!("Pipeline was removed from the pipeline cache.")
+            }
         }
     }
+
+    /// Returns `true` if the pipeline GPU object was created successfully.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, CachedPipelineState::Ok(_))
+    }
+
+    /// Returns `true` if creating the pipeline GPU object failed.
+    pub fn is_errored(&self) -> bool {
+        matches!(self, CachedPipelineState::Err(_))
+    }
 }
 
 #[derive(Default)]
@@ -140,6 +171,8 @@ struct ShaderCache {
     import_path_shaders: HashMap<ShaderImport, AssetId<Shader>>,
     waiting_on_import: HashMap<ShaderImport, Vec<AssetId<Shader>>>,
     composer: naga_oil::compose::Composer,
+    /// Shader defs added to every shader, after the defs of the pipeline.
+    global_shader_defs: Vec<ShaderDefVal>,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
@@ -191,6 +224,7 @@ impl ShaderCache {
             shaders: Default::default(),
             import_path_shaders: Default::default(),
             waiting_on_import: Default::default(),
+            global_shader_defs: global_shader_defs(render_device),
         }
     }
 
@@ -254,21 +288,7 @@ impl ShaderCache {
             EntryRef::Occupied(entry) => entry.into_mut(),
             EntryRef::Vacant(entry) => {
                 let mut shader_defs = shader_defs.to_vec();
-                #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
-                {
-                    shader_defs.push("NO_ARRAY_TEXTURES_SUPPORT".into());
-                    shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
-                    shader_defs.push("SIXTEEN_BYTE_ALIGNMENT".into());
-                }
-
-                if cfg!(feature = "ios_simulator") {
-                    shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
-                }
-
-                shader_defs.push(ShaderDefVal::UInt(
-                    String::from("AVAILABLE_STORAGE_BUFFER_BINDINGS"),
-                    render_device.limits().max_storage_buffers_per_shader_stage,
-                ));
+                shader_defs.extend(self.global_shader_defs.iter().cloned());
 
                 debug!(
                     "processing shader {:?}, with shader defs {:?}",
@@ -367,6 +387,19 @@ impl ShaderCache {
         pipelines_to_queue
     }
 
+    /// Validates composed shaders if `eager` is `true` or in debug builds, see
+    /// [`EagerShaderValidation`].
+    fn set_eager_validation(&mut self, eager: bool) {
+        self.composer.validate = eager || cfg!(debug_assertions);
+    }
+
+    /// Stops tracking the shaders used by `pipeline`, so changing them doesn't re-queue it.
+    fn remove_pipeline(&mut self, pipeline: CachedPipelineId) {
+        for data in self.data.values_mut() {
+            data.pipelines.remove(&pipeline);
+        }
+    }
+
     fn set_shader(&mut self, id: AssetId<Shader>, shader: Shader) -> Vec<CachedPipelineId> {
         let pipelines_to_queue = self.clear(id);
         let path = shader.import_path();
@@ -400,6 +433,35 @@ impl ShaderCache {
         pipelines_to_queue
     }
 
+    /// Returns `roots` followed by all of the shaders they transitively import, without duplicates.
+    fn dependencies(&self, roots: Vec<AssetId<Shader>>) -> Vec<AssetId<Shader>> {
+        let mut dependencies = Vec::new();
+        let mut visited = HashSet::new();
+        let mut to_visit = roots;
+        to_visit.reverse();
+        while let Some(id) = to_visit.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            dependencies.push(id);
+            if let Some(data) = self.data.get(&id) {
+                to_visit.extend(data.resolved_imports.values().copied());
+            }
+        }
+        dependencies
+    }
+
+    /// Returns the imports of `roots` and of the shaders they transitively import that aren't
+    /// available yet.
+    fn missing_imports(&self, roots: Vec<AssetId<Shader>>) -> Vec<ShaderImport> {
+        let shaders = self.dependencies(roots);
+        self.waiting_on_import
+            .iter()
+            .filter(|(_, waiting)| waiting.iter().any(|shader| shaders.contains(shader)))
+            .map(|(import, _)| import.clone())
+            .collect()
+    }
+
     fn remove(&mut self, id: AssetId<Shader>) -> Vec<CachedPipelineId> {
         let pipelines_to_queue = self.clear(id);
         if let Some(shader) = self.shaders.remove(&id) {
@@ -443,6 +505,172 @@ impl LayoutCache {
     }
 }
 
+/// A function applied to every [`RenderPipelineDescriptor`] right before the [`PipelineCache`]
+/// starts creating the pipeline, see [`PipelineDescriptorTransform`].
+pub type PipelineDescriptorTransformFn = dyn Fn(&mut RenderPipelineDescriptor) + Send + Sync;
+
+/// A render world resource holding a global transform applied to every [`RenderPipelineDescriptor`]
+/// before its shaders and layout are resolved.
+///
+/// This can be used for debug modes that tweak all pipelines at once, for example forcing
+/// [`PolygonMode::Line`](wgpu::PolygonMode::Line) to render everything as wireframe. The transform
+/// may modify the `primitive` state, `depth_stencil` state, color targets or shader defs.
+///
+/// The transform is only applied when a pipeline is created. Inserting, changing or removing this
+/// resource does not affect pipelines that were already created; they have to be re-queued
+/// (e.g. by re-specializing them) for the change to take effect.
+#[derive(Resource, Clone)]
+pub struct PipelineDescriptorTransform(pub Arc<PipelineDescriptorTransformFn>);
+
+impl PipelineDescriptorTransform {
+    /// Creates a new transform from the given function.
+    pub fn new(transform: impl Fn(&mut RenderPipelineDescriptor) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(transform))
+    }
+}
+
+bitflags::bitflags! {
+    /// A render world resource of debugging switches applied to every [`RenderPipelineDescriptor`]
+    /// when the [`PipelineCache`] starts creating the pipeline, after the
+    /// [`PipelineDescriptorTransform`].
+    ///
+    /// These change all pipelines uniformly, so the output is expected to be incorrect while they
+    /// are set. Like [`PipelineDescriptorTransform`], they only affect pipelines created after the
+    /// resource is changed. Pipelines that differ only by the overridden state aren't deduplicated,
+    /// so the overridden pipelines still use as much GPU memory as the originals.
+    #[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct RenderDebugFlags: u8 {
+        /// Sets `depth_write_enabled` to `false` for every pipeline with a depth stencil state.
+        ///
+        /// Depth testing is unaffected, so objects drawn later are no longer occluded by objects
+        /// drawn earlier in the same pass, and passes that read the depth buffer, like the
+        /// prepasses, SSAO or depth of field, see an empty or stale depth buffer.
+        const DISABLE_DEPTH_WRITES = 1 << 0;
+    }
+}
+
+impl RenderDebugFlags {
+    /// Applies the flags to `descriptor`, like the [`PipelineCache`] does before creating it.
+    pub fn apply(self, descriptor: &mut RenderPipelineDescriptor) {
+        if self.contains(RenderDebugFlags::DISABLE_DEPTH_WRITES) {
+            if let Some(depth_stencil) = &mut descriptor.depth_stencil {
+                depth_stencil.depth_write_enabled = false;
+            }
+        }
+    }
+}
+
+/// A render world resource configuring how the [`PipelineCache`] retries pipelines whose shaders
+/// or shader imports aren't loaded yet.
+///
+/// The cache waits a growing number of frames between retries, so a shader that never loads doesn't
+/// get its pipelines reprocessed every frame, and gives up after [`Self::max_retries`]. Adding or
+/// changing a shader resets the retry counters of the pipelines depending on it, so they're retried
+/// on the next frame once a shader they may be waiting on arrives, even if they gave up.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PipelineRetryPolicy {
+    /// The number of frames to wait before the first retry. The wait doubles after every retry.
+    pub initial_backoff_frames: u32,
+    /// The maximum number of frames to wait between two retries.
+    pub max_backoff_frames: u32,
+    /// The number of retries after which creating the pipeline fails with
+    /// [`PipelineCacheError::RetriesExhausted`], or `None` to retry until the shaders are loaded.
+    ///
+    /// Defaults to 16 retries, which takes a few hundred frames with the default backoff.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for PipelineRetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff_frames: 1,
+            max_backoff_frames: 32,
+            max_retries: Some(16),
+        }
+    }
+}
+
+/// A render world resource that validates every composed shader module as soon as it's
+/// processed, even in release builds.
+///
+/// Shaders are only validated by `naga` in debug builds by default. With this set to `true`,
+/// invalid WGSL makes the pipeline fail with a [`PipelineCacheError`] when it's created, with an
+/// error pointing at the shader, rather than surfacing later as a driver or `wgpu` error. It's
+/// meant to be inserted at startup in development builds: shader modules that were already
+/// processed aren't validated again when it changes.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EagerShaderValidation(pub bool);
+
+/// The retries of a pipeline waiting on shaders, see [`PipelineRetryPolicy`].
+#[derive(Default)]
+struct PipelineRetry {
+    retries: u32,
+    frames_to_skip: u32,
+}
+
+impl PipelineRetry {
+    /// Returns `true` if the pipeline has to wait at least one more frame before being retried.
+    fn skip_frame(&mut self) -> bool {
+        if self.frames_to_skip == 0 {
+            return false;
+        }
+        self.frames_to_skip -= 1;
+        true
+    }
+
+    /// Schedules the next retry, returning `false` if the retries are exhausted.
+    fn schedule_retry(&mut self, policy: &PipelineRetryPolicy) -> bool {
+        if policy
+            .max_retries
+            .is_some_and(|max_retries| self.retries >= max_retries)
+        {
+            return false;
+        }
+        let backoff = 1u32.checked_shl(self.retries).unwrap_or(u32::MAX);
+        self.frames_to_skip = policy
+            .initial_backoff_frames
+            .saturating_mul(backoff)
+            .min(policy.max_backoff_frames);
+        self.retries += 1;
+        true
+    }
+}
+
+/// Returns the shaders of the stages of `descriptor`.
+fn descriptor_shaders(descriptor: &PipelineDescriptor) -> Vec<AssetId<Shader>> {
+    match descriptor {
+        PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+            let mut shaders = vec![descriptor.vertex.shader.id()];
+            shaders.extend(
+                descriptor
+                    .fragment
+                    .as_ref()
+                    .map(|fragment| fragment.shader.id()),
+            );
+            shaders
+        }
+        PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+            vec![descriptor.shader.id()]
+        }
+    }
+}
+
+/// Returns the IDs of the `pipelines` with a stage using `shader`, skipping removed pipelines.
+fn pipelines_using_shader(
+    pipelines: &[CachedPipeline],
+    shader: AssetId<Shader>,
+) -> Vec<CachedPipelineId> {
+    pipelines
+        .iter()
+        .enumerate()
+        .filter(|(_, pipeline)| {
+            !matches!(pipeline.state, CachedPipelineState::Removed)
+                && descriptor_shaders(&pipeline.descriptor).contains(&shader)
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
 /// Cache for render and compute pipelines.
 ///
 /// The cache stores existing render and compute pipelines allocated on the GPU, as well as
@@ -463,9 +691,25 @@ pub struct PipelineCache {
     pipelines: Vec<CachedPipeline>,
     waiting_pipelines: HashSet<CachedPipelineId>,
     new_pipelines: Mutex<Vec<CachedPipeline>>,
+    /// Mirrors the length of `new_pipelines` so it can be read without locking.
+    /// Only updated while holding the `new_pipelines` lock.
+    new_pipelines_count: AtomicUsize,
     /// If `true`, disables asynchronous pipeline compilation.
     /// This has no effect on MacOS, wasm, or without the `multi_threaded` feature.
     synchronous_pipeline_compilation: bool,
+    /// Render pipelines that are created synchronously regardless of
+    /// `synchronous_pipeline_compilation`, see [`Self::queue_render_pipeline_sync`].
+    synchronous_pipelines: Mutex<HashSet<CachedPipelineId>>,
+    /// Synced from the [`PipelineDescriptorTransform`] resource in [`Self::process_pipeline_queue_system`].
+    descriptor_transform: Option<Arc<PipelineDescriptorTransformFn>>,
+    /// Synced from the [`RenderDebugFlags`] resource in [`Self::process_pipeline_queue_system`].
+    debug_flags: RenderDebugFlags,
+    /// Synced from the [`PipelineRetryPolicy`] resource in [`Self::process_pipeline_queue_system`].
+    retry_policy: PipelineRetryPolicy,
+    /// Synced from the [`EagerShaderValidation`] resource in [`Self::process_pipeline_queue_system`].
+    eager_shader_validation: bool,
+    /// The retries of the pipelines waiting on shaders.
+    pipeline_retries: HashMap<CachedPipelineId, PipelineRetry>,
 }
 
 impl PipelineCache {
@@ -474,6 +718,67 @@ impl PipelineCache {
         self.pipelines.iter()
     }
 
+    /// Returns the number of pipelines queued since the last [`Self::process_queue`]
+    /// that haven't been moved into the cache yet.
+    ///
+    /// Unlike inspecting the queue itself, this doesn't lock, so it's cheap to call on hot paths.
+    #[inline]
+    pub fn pending_count(&self) -> usize {
+        self.new_pipelines_count.load(Ordering::Acquire)
+    }
+
+    /// Returns the shaders a cached pipeline is built from: the shaders of its descriptor, followed
+    /// by all the shaders they transitively import.
+    ///
+    /// Changing any of these shaders will cause the pipeline to be recompiled. Imports that haven't
+    /// been resolved yet are not included. Returns an empty list for pipelines that are still queued.
+    pub fn pipeline_shader_dependencies(&self, id: CachedPipelineId) -> Vec<AssetId<Shader>> {
+        let Some(pipeline) = self.pipelines.get(id) else {
+            return Vec::new();
+        };
+        self.shader_cache
+            .lock()
+            .unwrap()
+            .dependencies(descriptor_shaders(&pipeline.descriptor))
+    }
+
+    /// Returns the render and compute pipelines whose vertex, fragment or compute stage uses
+    /// `shader`, in the order they were queued.
+    ///
+    /// Only the shaders of the descriptors are considered, not the shaders they import; see
+    /// [`Self::pipeline_shader_dependencies`] for those. Pipelines that are still queued and removed
+    /// pipelines are not included.
+    pub fn pipelines_using_shader(&self, shader: AssetId<Shader>) -> Vec<CachedPipelineId> {
+        pipelines_using_shader(&self.pipelines, shader)
+    }
+
+    /// Returns the debug label of a cached render or compute pipeline, if it has one.
+    ///
+    /// Returns `None` for pipelines that are still queued.
+    pub fn pipeline_label(&self, id: CachedPipelineId) -> Option<&str> {
+        self.pipelines.get(id)?.descriptor.label()
+    }
+
+    /// Returns the shader defs a cached render pipeline is compiled with: the global defs the cache
+    /// adds to every shader, followed by the defs of the vertex and fragment stages of its
+    /// descriptor, without duplicates.
+    ///
+    /// The shader defs declared by the shader assets themselves aren't included. Returns an empty
+    /// list for pipelines that are still queued.
+    pub fn effective_shader_defs(&self, id: CachedRenderPipelineId) -> Vec<ShaderDefVal> {
+        let Some(CachedPipeline {
+            descriptor: PipelineDescriptor::RenderPipelineDescriptor(descriptor),
+            ..
+        }) = self.pipelines.get(id.0)
+        else {
+            return Vec::new();
+        };
+        effective_render_shader_defs(
+            &self.shader_cache.lock().unwrap().global_shader_defs,
+            descriptor,
+        )
+    }
+
     /// Returns a iterator of the IDs of all currently waiting pipelines.
     pub fn waiting_pipelines(&self) -> impl Iterator<Item = CachedPipelineId> + '_ {
         self.waiting_pipelines.iter().copied()
@@ -491,8 +796,15 @@ impl PipelineCache {
             layout_cache: default(),
             waiting_pipelines: default(),
             new_pipelines: default(),
+            new_pipelines_count: default(),
             pipelines: default(),
             synchronous_pipeline_compilation,
+            synchronous_pipelines: default(),
+            descriptor_transform: None,
+            debug_flags: RenderDebugFlags::empty(),
+            retry_policy: default(),
+            eager_shader_validation: false,
+            pipeline_retries: default(),
         }
     }
 
@@ -512,6 +824,35 @@ impl PipelineCache {
         &self.pipelines[id.0].state
     }
 
+    /// Returns `true` if the render pipeline is ready to be used.
+    ///
+    /// Unlike [`PipelineCache::get_render_pipeline_state()`], this doesn't panic for IDs that
+    /// haven't been processed yet, which are reported as not ready.
+    #[inline]
+    pub fn is_ready(&self, id: CachedRenderPipelineId) -> bool {
+        self.pipelines
+            .get(id.0)
+            .is_some_and(|pipeline| pipeline.state.is_ready())
+    }
+
+    /// Returns `true` if creating the render pipeline failed.
+    ///
+    /// IDs that haven't been processed yet are reported as not errored.
+    #[inline]
+    pub fn is_errored(&self, id: CachedRenderPipelineId) -> bool {
+        self.pipelines
+            .get(id.0)
+            .is_some_and(|pipeline| pipeline.state.is_errored())
+    }
+
+    /// Returns `true` if [`PipelineCache::get_render_pipeline_descriptor()`] can be called for
+    /// `id`, i.e. if the pipeline has been moved out of the queue of newly inserted pipelines
+    /// by [`PipelineCache::process_queue()`].
+    #[inline]
+    pub fn descriptor_exists(&self, id: CachedRenderPipelineId) -> bool {
+        id.0 < self.pipelines.len()
+    }
+
     /// Get the render pipeline descriptor a cached render pipeline was inserted from.
     ///
     /// See [`PipelineCache::queue_render_pipeline()`].
@@ -545,7 +886,7 @@ impl PipelineCache {
     /// # Returns
     ///
     /// This method returns a successfully created render pipeline if any, or `None` if the pipeline
-    /// was not created yet or if there was an error during creation. You can check the actual creation
+    /// was not created yet, if it was removed, or if there was an error during creation. You can check the actual creation
     /// state with [`PipelineCache::get_render_pipeline_state()`].
     #[inline]
     pub fn get_render_pipeline(&self, id: CachedRenderPipelineId) -> Option<&RenderPipeline> {
@@ -574,12 +915,47 @@ impl PipelineCache {
         }
     }
 
+    /// Wait at most `budget` for a render pipeline to finish compiling.
+    ///
+    /// Unlike [`PipelineCache::block_on_render_pipeline()`], this gives up once the budget has
+    /// elapsed, so a loading screen can keep animating while pipelines compile. Compilation
+    /// continues in the background, and calling this again on a later frame picks up where it
+    /// left off.
+    ///
+    /// Returns `true` once the pipeline has been created. Returns `false` if the budget elapsed,
+    /// if the pipeline is still queued (e.g. because its shaders aren't loaded yet), or if its
+    /// creation failed; use [`PipelineCache::get_render_pipeline_state()`] to tell these apart.
+    pub fn poll_render_pipeline(&mut self, id: CachedRenderPipelineId, budget: Duration) -> bool {
+        let start = Instant::now();
+        if self.pipelines.len() <= id.0 {
+            self.process_queue();
+        }
+
+        loop {
+            let state = &mut self.pipelines[id.0].state;
+            let CachedPipelineState::Creating(task) = state else {
+                return matches!(state, CachedPipelineState::Ok(_));
+            };
+            if let Some(result) = bevy_utils::futures::check_ready(task) {
+                *state = match result {
+                    Ok(p) => CachedPipelineState::Ok(p),
+                    Err(e) => CachedPipelineState::Err(e),
+                };
+                continue;
+            }
+            if start.elapsed() >= budget {
+                return false;
+            }
+            std::thread::yield_now();
+        }
+    }
+
     /// Try to retrieve a compute pipeline GPU object from a cached ID.
     ///
     /// # Returns
     ///
     /// This method returns a successfully created compute pipeline if any, or `None` if the pipeline
-    /// was not created yet or if there was an error during creation. You can check the actual creation
+    /// was not created yet, if it was removed, or if there was an error during creation. You can check the actual creation
     /// state with [`PipelineCache::get_compute_pipeline_state()`].
     #[inline]
     pub fn get_compute_pipeline(&self, id: CachedComputePipelineId) -> Option<&ComputePipeline> {
@@ -618,6 +994,28 @@ impl PipelineCache {
             descriptor: PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
             state: CachedPipelineState::Queued,
         });
+        self.new_pipelines_count.fetch_add(1, Ordering::Release);
+        id
+    }
+
+    /// Insert a render pipeline into the cache, and queue its synchronous creation.
+    ///
+    /// This is like [`queue_render_pipeline()`], except that the pipeline is created synchronously
+    /// by the next [`process_queue()`] even when the other pipelines are created asynchronously,
+    /// so it's ready as soon as its shaders are loaded, e.g. for a loading screen. Recompiling the
+    /// pipeline after one of its shaders changed is also done synchronously.
+    ///
+    /// [`queue_render_pipeline()`]: PipelineCache::queue_render_pipeline
+    /// [`process_queue()`]: PipelineCache::process_queue
+    pub fn queue_render_pipeline_sync(
+        &self,
+        descriptor: RenderPipelineDescriptor,
+    ) -> CachedRenderPipelineId {
+        let id = self.queue_render_pipeline(descriptor);
+        self.synchronous_pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id.0);
         id
     }
 
@@ -647,16 +1045,71 @@ impl PipelineCache {
             descriptor: PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
             state: CachedPipelineState::Queued,
         });
+        self.new_pipelines_count.fetch_add(1, Ordering::Release);
         id
     }
 
-    fn set_shader(&mut self, id: AssetId<Shader>, shader: &Shader) {
+    /// Removes a render or compute pipeline from the cache, dropping its GPU object.
+    ///
+    /// The ID isn't reused: the pipeline's slot is left in the [`CachedPipelineState::Removed`]
+    /// state, so [`get_render_pipeline()`] and [`get_compute_pipeline()`] return `None` for it
+    /// from now on. The pipeline also stops being recompiled when its shaders change. Removing a
+    /// pipeline that is still being created discards the result of its creation.
+    ///
+    /// [`get_render_pipeline()`]: PipelineCache::get_render_pipeline
+    /// [`get_compute_pipeline()`]: PipelineCache::get_compute_pipeline
+    pub fn remove_pipeline(&mut self, id: CachedPipelineId) {
+        if let Some(pipeline) = self.pipelines.get_mut(id) {
+            pipeline.state = CachedPipelineState::Removed;
+        } else {
+            let mut new_pipelines = self
+                .new_pipelines
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(pipeline) = new_pipelines.get_mut(id - self.pipelines.len()) {
+                pipeline.state = CachedPipelineState::Removed;
+            }
+        }
+        self.waiting_pipelines.remove(&id);
+        self.pipeline_retries.remove(&id);
+        self.synchronous_pipelines
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        self.shader_cache.lock().unwrap().remove_pipeline(id);
+    }
+
+    pub(crate) fn set_shader(&mut self, id: AssetId<Shader>, shader: &Shader) {
         let mut shader_cache = self.shader_cache.lock().unwrap();
         let pipelines_to_queue = shader_cache.set_shader(id, shader.clone());
         for cached_pipeline in pipelines_to_queue {
             self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
             self.waiting_pipelines.insert(cached_pipeline);
         }
+
+        // The new shader may be the one the pipelines depending on it are waiting on, so retry them
+        // right away. The retries of other pipelines are kept, so unrelated shader changes don't
+        // keep them from giving up.
+        for (cached_pipeline, pipeline) in self.pipelines.iter_mut().enumerate() {
+            let exhausted = matches!(
+                pipeline.state,
+                CachedPipelineState::Err(PipelineCacheError::RetriesExhausted { .. })
+            );
+            if !exhausted && !self.pipeline_retries.contains_key(&cached_pipeline) {
+                continue;
+            }
+            if !shader_cache
+                .dependencies(descriptor_shaders(&pipeline.descriptor))
+                .contains(&id)
+            {
+                continue;
+            }
+            self.pipeline_retries.remove(&cached_pipeline);
+            if exhausted {
+                pipeline.state = CachedPipelineState::Queued;
+                self.waiting_pipelines.insert(cached_pipeline);
+            }
+        }
     }
 
     fn remove_shader(&mut self, shader: AssetId<Shader>) {
@@ -671,8 +1124,20 @@ impl PipelineCache {
     fn start_create_render_pipeline(
         &mut self,
         id: CachedPipelineId,
-        descriptor: RenderPipelineDescriptor,
+        mut descriptor: RenderPipelineDescriptor,
     ) -> CachedPipelineState {
+        if let Some(transform) = &self.descriptor_transform {
+            transform(&mut descriptor);
+        }
+        self.debug_flags.apply(&mut descriptor);
+
+        let sync = self.synchronous_pipeline_compilation
+            || self
+                .synchronous_pipelines
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains(&id);
+
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
@@ -738,11 +1203,16 @@ impl PipelineCache {
                     )
                 });
 
-                // TODO: Expose this somehow
-                let compilation_options = PipelineCompilationOptions {
-                    constants: &std::collections::HashMap::new(),
-                    zero_initialize_workgroup_memory: false,
-                };
+                let vertex_options = descriptor
+                    .vertex_compilation_options
+                    .clone()
+                    .unwrap_or_else(|| CompilationOptions::shared(&descriptor.constants));
+                let fragment_options = descriptor
+                    .fragment_compilation_options
+                    .clone()
+                    .unwrap_or_else(|| CompilationOptions::shared(&descriptor.constants));
+                let vertex_constants = vertex_options.constants_map();
+                let fragment_constants = fragment_options.constants_map();
 
                 let descriptor = RawRenderPipelineDescriptor {
                     multiview: None,
@@ -755,8 +1225,11 @@ impl PipelineCache {
                         buffers: &vertex_buffer_layouts,
                         entry_point: descriptor.vertex.entry_point.deref(),
                         module: &vertex_module,
-                        // TODO: Should this be the same as the fragment compilation options?
-                        compilation_options: compilation_options.clone(),
+                        compilation_options: PipelineCompilationOptions {
+                            constants: &vertex_constants,
+                            zero_initialize_workgroup_memory: vertex_options
+                                .zero_initialize_workgroup_memory,
+                        },
                     },
                     fragment: fragment_data
                         .as_ref()
@@ -764,8 +1237,11 @@ impl PipelineCache {
                             entry_point,
                             module,
                             targets,
-                            // TODO: Should this be the same as the vertex compilation options?
-                            compilation_options,
+                            compilation_options: PipelineCompilationOptions {
+                                constants: &fragment_constants,
+                                zero_initialize_workgroup_memory: fragment_options
+                                    .zero_initialize_workgroup_memory,
+                            },
                         }),
                 };
 
@@ -773,7 +1249,7 @@ impl PipelineCache {
                     device.create_render_pipeline(&descriptor),
                 ))
             },
-            self.synchronous_pipeline_compilation,
+            sync,
         )
     }
 
@@ -813,14 +1289,15 @@ impl PipelineCache {
 
                 drop((shader_cache, layout_cache));
 
+                let constants = CompilationOptions::shared(&descriptor.constants).constants_map();
                 let descriptor = RawComputePipelineDescriptor {
                     label: descriptor.label.as_deref(),
                     layout: layout.as_deref(),
                     module: &compute_module,
                     entry_point: &descriptor.entry_point,
-                    // TODO: Expose this somehow
+                    // TODO: Expose the rest of this somehow
                     compilation_options: PipelineCompilationOptions {
-                        constants: &std::collections::HashMap::new(),
+                        constants: &constants,
                         zero_initialize_workgroup_memory: false,
                     },
                 };
@@ -848,14 +1325,25 @@ impl PipelineCache {
                 .new_pipelines
                 .lock()
                 .unwrap_or_else(PoisonError::into_inner);
+            let drained = new_pipelines.len();
             for new_pipeline in new_pipelines.drain(..) {
                 let id = pipelines.len();
                 pipelines.push(new_pipeline);
                 waiting_pipelines.insert(id);
             }
+            self.new_pipelines_count
+                .fetch_sub(drained, Ordering::Release);
         }
 
         for id in waiting_pipelines {
+            if self
+                .pipeline_retries
+                .get_mut(&id)
+                .is_some_and(PipelineRetry::skip_frame)
+            {
+                self.waiting_pipelines.insert(id);
+                continue;
+            }
             self.process_pipeline(&mut pipelines[id], id);
         }
 
@@ -879,6 +1367,7 @@ impl PipelineCache {
                 match bevy_utils::futures::check_ready(task) {
                     Some(Ok(pipeline)) => {
                         cached_pipeline.state = CachedPipelineState::Ok(pipeline);
+                        self.pipeline_retries.remove(&id);
                         return;
                     }
                     Some(Err(err)) => cached_pipeline.state = CachedPipelineState::Err(err),
@@ -890,30 +1379,92 @@ impl PipelineCache {
                 // Retry
                 PipelineCacheError::ShaderNotLoaded(_)
                 | PipelineCacheError::ShaderImportNotYetAvailable => {
-                    cached_pipeline.state = CachedPipelineState::Queued;
+                    let retry = self.pipeline_retries.entry(id).or_default();
+                    if retry.schedule_retry(&self.retry_policy) {
+                        cached_pipeline.state = CachedPipelineState::Queued;
+                    } else {
+                        let retries = retry.retries;
+                        self.pipeline_retries.remove(&id);
+                        let missing = match err {
+                            PipelineCacheError::ShaderNotLoaded(shader) => {
+                                format!("shader {shader:?}")
+                            }
+                            _ => {
+                                let missing_imports =
+                                    self.shader_cache.lock().unwrap().missing_imports(
+                                        descriptor_shaders(&cached_pipeline.descriptor),
+                                    );
+                                let missing_imports = missing_imports
+                                    .iter()
+                                    .map(ShaderImport::module_name)
+                                    .collect::<Vec<_>>();
+                                format!("shader imports {missing_imports:?}")
+                            }
+                        };
+                        let error = PipelineCacheError::RetriesExhausted { retries, missing };
+                        error!(
+                            "failed to create pipeline {:?}: {}",
+                            cached_pipeline.descriptor.label().unwrap_or("<unlabeled>"),
+                            error
+                        );
+                        cached_pipeline.state = CachedPipelineState::Err(error);
+                        return;
+                    }
                 }
 
                 // Shader could not be processed ... retrying won't help
                 PipelineCacheError::ProcessShaderError(err) => {
                     let error_detail =
                         err.emit_to_string(&self.shader_cache.lock().unwrap().composer);
-                    error!("failed to process shader:\n{}", error_detail);
+                    error!(
+                        "failed to process shader for pipeline {:?}:\n{}",
+                        cached_pipeline.descriptor.label().unwrap_or("<unlabeled>"),
+                        error_detail
+                    );
                     return;
                 }
                 PipelineCacheError::CreateShaderModule(description) => {
-                    error!("failed to create shader module: {}", description);
+                    error!(
+                        "failed to create shader module for pipeline {:?}: {}",
+                        cached_pipeline.descriptor.label().unwrap_or("<unlabeled>"),
+                        description
+                    );
                     return;
                 }
+                // Already reported, the pipeline is retried once one of its shaders is added or
+                // changed.
+                PipelineCacheError::RetriesExhausted { .. } => return,
             },
 
-            CachedPipelineState::Ok(_) => return,
+            CachedPipelineState::Ok(_) | CachedPipelineState::Removed => {
+                self.pipeline_retries.remove(&id);
+                return;
+            }
         }
 
         // Retry
         self.waiting_pipelines.insert(id);
     }
 
-    pub(crate) fn process_pipeline_queue_system(mut cache: ResMut<Self>) {
+    pub(crate) fn process_pipeline_queue_system(
+        mut cache: ResMut<Self>,
+        descriptor_transform: Option<Res<PipelineDescriptorTransform>>,
+        debug_flags: Option<Res<RenderDebugFlags>>,
+        retry_policy: Option<Res<PipelineRetryPolicy>>,
+        eager_shader_validation: Option<Res<EagerShaderValidation>>,
+    ) {
+        cache.descriptor_transform = descriptor_transform.map(|transform| transform.0.clone());
+        cache.debug_flags = debug_flags.map_or(RenderDebugFlags::empty(), |flags| *flags);
+        cache.retry_policy = retry_policy.map_or_else(default, |policy| *policy);
+        let eager_shader_validation = eager_shader_validation.is_some_and(|eager| eager.0);
+        if cache.eager_shader_validation != eager_shader_validation {
+            cache.eager_shader_validation = eager_shader_validation;
+            cache
+                .shader_cache
+                .lock()
+                .unwrap()
+                .set_eager_validation(eager_shader_validation);
+        }
         cache.process_queue();
     }
 
@@ -988,6 +1539,53 @@ pub enum PipelineCacheError {
     ShaderImportNotYetAvailable,
     #[error("Could not create shader module: {0}")]
     CreateShaderModule(String),
+    /// The shaders of the pipeline didn't load after the number of retries allowed by the
+    /// [`PipelineRetryPolicy`].
+    #[error("Gave up on the pipeline after {retries} retries, {missing} never became available")]
+    RetriesExhausted { retries: u32, missing: String },
+}
+
+/// Returns the shader defs that depend on the platform and the render device, which are added to
+/// every shader.
+fn global_shader_defs(render_device: &RenderDevice) -> Vec<ShaderDefVal> {
+    let mut shader_defs: Vec<ShaderDefVal> = Vec::new();
+    #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
+    {
+        shader_defs.push("NO_ARRAY_TEXTURES_SUPPORT".into());
+        shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
+        shader_defs.push("SIXTEEN_BYTE_ALIGNMENT".into());
+    }
+
+    if cfg!(feature = "ios_simulator") {
+        shader_defs.push("NO_CUBE_ARRAY_TEXTURES_SUPPORT".into());
+    }
+
+    shader_defs.push(ShaderDefVal::UInt(
+        String::from("AVAILABLE_STORAGE_BUFFER_BINDINGS"),
+        render_device.limits().max_storage_buffers_per_shader_stage,
+    ));
+    shader_defs
+}
+
+/// Merges the global shader defs with the defs of the vertex and fragment stages of `descriptor`,
+/// keeping the first occurrence of duplicated defs.
+fn effective_render_shader_defs(
+    global_shader_defs: &[ShaderDefVal],
+    descriptor: &RenderPipelineDescriptor,
+) -> Vec<ShaderDefVal> {
+    let mut seen = HashSet::default();
+    global_shader_defs
+        .iter()
+        .chain(&descriptor.vertex.shader_defs)
+        .chain(
+            descriptor
+                .fragment
+                .iter()
+                .flat_map(|fragment| &fragment.shader_defs),
+        )
+        .filter(|shader_def| seen.insert(*shader_def))
+        .cloned()
+        .collect()
 }
 
 // TODO: This needs to be kept up to date with the capabilities in the `create_validator` function in wgpu-core
@@ -1075,3 +1673,391 @@ fn get_capabilities(
 
     (capabilities, subgroup_stages)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::Handle;
+
+    fn test_shader_cache() -> ShaderCache {
+        ShaderCache {
+            data: default(),
+            shaders: default(),
+            import_path_shaders: default(),
+            waiting_on_import: default(),
+            composer: naga_oil::compose::Composer::default(),
+            global_shader_defs: vec![],
+        }
+    }
+
+    /// A render pipeline descriptor with only a vertex stage, using `shader`.
+    fn test_render_pipeline_descriptor(shader: &Handle<Shader>) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: None,
+            layout: vec![],
+            push_constant_ranges: vec![],
+            vertex: VertexState {
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: "vertex".into(),
+                buffers: vec![],
+            },
+            primitive: default(),
+            depth_stencil: None,
+            multisample: default(),
+            fragment: None,
+            constants: vec![],
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
+        }
+    }
+
+    fn test_fragment_state(shader: &Handle<Shader>) -> FragmentState {
+        FragmentState {
+            shader: shader.clone(),
+            shader_defs: vec![],
+            entry_point: "fragment".into(),
+            targets: vec![],
+        }
+    }
+
+    fn test_compute_pipeline_descriptor(shader: &Handle<Shader>) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: None,
+            layout: vec![],
+            push_constant_ranges: vec![],
+            shader: shader.clone(),
+            shader_defs: vec![],
+            entry_point: "main".into(),
+            constants: vec![],
+        }
+    }
+
+    #[test]
+    fn shader_dependencies_follow_imports() {
+        let [a, b, c, unrelated] = [1, 2, 3, 4].map(|i| Handle::<Shader>::weak_from_u128(i).id());
+
+        let mut shader_cache = test_shader_cache();
+        for (shader, import) in [(a, b), (b, c), (c, a)] {
+            shader_cache
+                .data
+                .entry(shader)
+                .or_default()
+                .resolved_imports
+                .insert(ShaderImport::Custom(format!("{import:?}")), import);
+        }
+        shader_cache.data.entry(unrelated).or_default();
+
+        assert_eq!(shader_cache.dependencies(vec![a]), vec![a, b, c]);
+        assert_eq!(shader_cache.dependencies(vec![c, b]), vec![c, a, b]);
+        assert_eq!(shader_cache.dependencies(vec![unrelated]), vec![unrelated]);
+    }
+
+    #[test]
+    fn removed_pipelines_stop_tracking_shaders() {
+        let [a, b] = [1, 2].map(|i| Handle::<Shader>::weak_from_u128(i).id());
+
+        let mut shader_cache = test_shader_cache();
+        for shader in [a, b] {
+            let data = shader_cache.data.entry(shader).or_default();
+            data.pipelines.insert(0);
+            data.pipelines.insert(1);
+        }
+
+        shader_cache.remove_pipeline(0);
+        let mut requeued = shader_cache.clear(a);
+        requeued.extend(shader_cache.clear(b));
+        assert_eq!(requeued, vec![1, 1]);
+    }
+
+    #[test]
+    fn cached_pipeline_state_predicates() {
+        // Creating an `Ok` state needs a GPU, see `pipeline_cache_reports_pipeline_states`.
+        let queued = CachedPipelineState::Queued;
+        assert!(!queued.is_ready());
+        assert!(!queued.is_errored());
+
+        let creating = CachedPipelineState::Creating(
+            bevy_tasks::TaskPool::new().spawn(std::future::pending()),
+        );
+        assert!(!creating.is_ready());
+        assert!(!creating.is_errored());
+
+        let errored =
+            CachedPipelineState::Err(PipelineCacheError::CreateShaderModule("invalid".into()));
+        assert!(!errored.is_ready());
+        assert!(errored.is_errored());
+
+        let removed = CachedPipelineState::Removed;
+        assert!(!removed.is_ready());
+        assert!(!removed.is_errored());
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn pipeline_cache_reports_pipeline_states() {
+        let (device, _, adapter) = crate::renderer::test_device();
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let shader = Handle::<Shader>::weak_from_u128(1);
+        cache.set_shader(
+            shader.id(),
+            &Shader::from_wgsl(
+                "@vertex fn vertex() -> @builtin(position) vec4<f32> { return vec4(0.0); }",
+                "shader.wgsl",
+            ),
+        );
+        let ok = cache.queue_render_pipeline(test_render_pipeline_descriptor(&shader));
+        let missing_shader = cache
+            .queue_render_pipeline(test_render_pipeline_descriptor(&Handle::weak_from_u128(2)));
+        for id in [ok, missing_shader] {
+            assert!(!cache.descriptor_exists(id));
+            assert!(!cache.is_ready(id));
+            assert!(!cache.is_errored(id));
+        }
+
+        cache.process_queue();
+
+        assert!(cache.descriptor_exists(ok));
+        assert!(cache.is_ready(ok));
+        assert!(!cache.is_errored(ok));
+
+        assert!(cache.descriptor_exists(missing_shader));
+        assert!(!cache.is_ready(missing_shader));
+        assert!(cache.is_errored(missing_shader));
+
+        assert!(!cache.descriptor_exists(CachedRenderPipelineId::INVALID));
+        assert!(!cache.is_ready(CachedRenderPipelineId::INVALID));
+        assert!(!cache.is_errored(CachedRenderPipelineId::INVALID));
+    }
+
+    #[test]
+    fn pipeline_descriptor_label() {
+        let compute = |label: Option<&'static str>| {
+            PipelineDescriptor::ComputePipelineDescriptor(Box::new(ComputePipelineDescriptor {
+                label: label.map(Into::into),
+                ..test_compute_pipeline_descriptor(&Handle::weak_from_u128(1))
+            }))
+        };
+        assert_eq!(compute(Some("culling")).label(), Some("culling"));
+        assert_eq!(compute(None).label(), None);
+
+        let render =
+            PipelineDescriptor::RenderPipelineDescriptor(Box::new(RenderPipelineDescriptor {
+                label: Some("blit".into()),
+                ..test_render_pipeline_descriptor(&Handle::weak_from_u128(2))
+            }));
+        assert_eq!(render.label(), Some("blit"));
+    }
+
+    #[test]
+    fn effective_shader_defs_include_globals() {
+        let global_shader_defs = vec![
+            ShaderDefVal::from("NO_ARRAY_TEXTURES_SUPPORT"),
+            ShaderDefVal::UInt("AVAILABLE_STORAGE_BUFFER_BINDINGS".into(), 8),
+        ];
+        let shader = Handle::weak_from_u128(1);
+        let mut descriptor = test_render_pipeline_descriptor(&shader);
+        descriptor.vertex.shader_defs =
+            vec!["VERTEX_UVS".into(), "NO_ARRAY_TEXTURES_SUPPORT".into()];
+        descriptor.fragment = Some(FragmentState {
+            shader_defs: vec![
+                "VERTEX_UVS".into(),
+                ShaderDefVal::Int("MAX_LIGHTS".into(), 4),
+            ],
+            ..test_fragment_state(&shader)
+        });
+
+        assert_eq!(
+            effective_render_shader_defs(&global_shader_defs, &descriptor),
+            vec![
+                "NO_ARRAY_TEXTURES_SUPPORT".into(),
+                ShaderDefVal::UInt("AVAILABLE_STORAGE_BUFFER_BINDINGS".into(), 8),
+                "VERTEX_UVS".into(),
+                ShaderDefVal::Int("MAX_LIGHTS".into(), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn debug_flags_disable_depth_writes() {
+        let mut descriptor = RenderPipelineDescriptor {
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: CompareFunction::GreaterEqual,
+                stencil: default(),
+                bias: default(),
+            }),
+            ..test_render_pipeline_descriptor(&Handle::weak_from_u128(1))
+        };
+
+        RenderDebugFlags::empty().apply(&mut descriptor);
+        assert!(
+            descriptor
+                .depth_stencil
+                .as_ref()
+                .unwrap()
+                .depth_write_enabled
+        );
+
+        RenderDebugFlags::DISABLE_DEPTH_WRITES.apply(&mut descriptor);
+        let depth_stencil = descriptor.depth_stencil.as_ref().unwrap();
+        assert!(!depth_stencil.depth_write_enabled);
+        assert_eq!(depth_stencil.depth_compare, CompareFunction::GreaterEqual);
+
+        descriptor.depth_stencil = None;
+        RenderDebugFlags::DISABLE_DEPTH_WRITES.apply(&mut descriptor);
+        assert!(descriptor.depth_stencil.is_none());
+    }
+
+    #[test]
+    fn pipeline_retries_back_off_and_give_up() {
+        let policy = PipelineRetryPolicy {
+            initial_backoff_frames: 1,
+            max_backoff_frames: 4,
+            max_retries: Some(4),
+        };
+        let mut retry = PipelineRetry::default();
+        // Processes the pipeline every frame like `process_queue`, failing every time.
+        let mut frames_between_retries = Vec::new();
+        let mut frames = 0;
+        loop {
+            if retry.skip_frame() {
+                frames += 1;
+                continue;
+            }
+            frames_between_retries.push(frames);
+            frames = 0;
+            if !retry.schedule_retry(&policy) {
+                break;
+            }
+        }
+        assert_eq!(frames_between_retries, vec![0, 1, 2, 4, 4]);
+        assert_eq!(retry.retries, 4);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn pipelines_give_up_on_missing_imports() {
+        let (device, _, adapter) = crate::renderer::test_device();
+        let mut cache = PipelineCache::new(device, adapter, true);
+        let [shader, unrelated] = [1, 2].map(Handle::<Shader>::weak_from_u128);
+        cache.set_shader(
+            shader.id(),
+            &Shader::from_wgsl(
+                "#import missing::module\n\
+                 @vertex fn vertex() -> @builtin(position) vec4<f32> { return vec4(0.0); }",
+                "shader.wgsl",
+            ),
+        );
+        let id = cache.queue_render_pipeline(test_render_pipeline_descriptor(&shader));
+
+        // With the default policy, the retries are exhausted after a few hundred frames. Adding
+        // shaders the pipeline doesn't depend on doesn't reset its retries.
+        for frame in 0..1000 {
+            if frame % 10 == 0 {
+                cache.set_shader(
+                    unrelated.id(),
+                    &Shader::from_wgsl("fn unrelated() {}", "unrelated.wgsl"),
+                );
+            }
+            cache.process_queue();
+        }
+
+        let CachedPipelineState::Err(PipelineCacheError::RetriesExhausted { retries, missing }) =
+            cache.get_render_pipeline_state(id)
+        else {
+            panic!(
+                "expected the retries to be exhausted, got {:?}",
+                cache.get_render_pipeline_state(id)
+            );
+        };
+        assert_eq!(Some(*retries), PipelineRetryPolicy::default().max_retries);
+        assert!(missing.contains("missing::module"), "{missing}");
+    }
+
+    #[test]
+    fn missing_imports_are_reported() {
+        let [shader, unrelated] = [1, 2].map(|i| Handle::<Shader>::weak_from_u128(i).id());
+
+        let mut shader_cache = test_shader_cache();
+        shader_cache.set_shader(
+            shader,
+            Shader::from_wgsl("#import missing::module\n", "shader.wgsl"),
+        );
+        shader_cache.set_shader(
+            unrelated,
+            Shader::from_wgsl("#import other::module\n", "unrelated.wgsl"),
+        );
+
+        assert_eq!(
+            shader_cache.missing_imports(vec![shader]),
+            vec![ShaderImport::Custom("missing::module".into())]
+        );
+    }
+
+    #[test]
+    fn eager_validation_rejects_invalid_shaders() {
+        use naga_oil::compose::{Composer, NagaModuleDescriptor};
+
+        let mut shader_cache = ShaderCache {
+            composer: Composer::non_validating(),
+            ..test_shader_cache()
+        };
+        shader_cache.set_eager_validation(true);
+        assert!(shader_cache.composer.validate);
+
+        // Parses fine, but fragment outputs need a `@location`, which only validation checks.
+        let source = "@fragment fn main() -> vec4<f32> { return vec4(1.0); }";
+        let result = shader_cache
+            .composer
+            .make_naga_module(NagaModuleDescriptor {
+                source,
+                file_path: "invalid.wgsl",
+                ..default()
+            });
+        assert!(result.is_err());
+
+        shader_cache.set_eager_validation(false);
+        assert_eq!(shader_cache.composer.validate, cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn pipelines_using_shader_scans_all_stages() {
+        let [shared, vertex, other] = [1, 2, 3].map(Handle::<Shader>::weak_from_u128);
+        let render = |vertex: &Handle<Shader>, fragment: &Handle<Shader>| CachedPipeline {
+            descriptor: PipelineDescriptor::RenderPipelineDescriptor(Box::new(
+                RenderPipelineDescriptor {
+                    fragment: Some(test_fragment_state(fragment)),
+                    ..test_render_pipeline_descriptor(vertex)
+                },
+            )),
+            state: CachedPipelineState::Queued,
+        };
+        let compute = |shader: &Handle<Shader>| CachedPipeline {
+            descriptor: PipelineDescriptor::ComputePipelineDescriptor(Box::new(
+                test_compute_pipeline_descriptor(shader),
+            )),
+            state: CachedPipelineState::Queued,
+        };
+
+        let mut removed = compute(&shared);
+        removed.state = CachedPipelineState::Removed;
+        let pipelines = [
+            render(&vertex, &shared),
+            render(&vertex, &other),
+            compute(&shared),
+            removed,
+            render(&shared, &other),
+        ];
+
+        assert_eq!(
+            pipelines_using_shader(&pipelines, shared.id()),
+            vec![0, 2, 4]
+        );
+        assert_eq!(pipelines_using_shader(&pipelines, vertex.id()), vec![0, 1]);
+        assert!(
+            pipelines_using_shader(&pipelines, Handle::<Shader>::weak_from_u128(4).id()).is_empty()
+        );
+    }
+}
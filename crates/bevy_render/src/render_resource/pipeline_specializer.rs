@@ -1,7 +1,7 @@
 use crate::mesh::MeshVertexBufferLayoutRef;
 use crate::render_resource::CachedComputePipelineId;
 use crate::{
-    mesh::MissingVertexAttributeError,
+    mesh::{MissingAttributes, MissingVertexAttributeError},
     render_resource::{
         CachedRenderPipelineId, ComputePipelineDescriptor, PipelineCache, RenderPipelineDescriptor,
         VertexBufferLayout,
@@ -138,8 +138,7 @@ impl<S: SpecializedMeshPipeline> SpecializedMeshPipelines<S> {
             let descriptor = specialize_pipeline
                 .specialize(key.clone(), layout)
                 .map_err(|mut err| {
-                    {
-                        let SpecializedMeshPipelineError::MissingVertexAttribute(err) = &mut err;
+                    if let SpecializedMeshPipelineError::MissingVertexAttribute(err) = &mut err {
                         err.pipeline_type = Some(std::any::type_name::<S>());
                     }
                     err
@@ -185,4 +184,6 @@ impl<S: SpecializedMeshPipeline> SpecializedMeshPipelines<S> {
 pub enum SpecializedMeshPipelineError {
     #[error(transparent)]
     MissingVertexAttribute(#[from] MissingVertexAttributeError),
+    #[error(transparent)]
+    MissingAttributes(#[from] MissingAttributes),
 }
@@ -13,7 +13,7 @@ use crate::{
         Edge, InternedRenderLabel, InternedRenderSubGraph, NodeRunError, NodeState, RenderGraph,
         RenderGraphContext, SlotLabel, SlotType, SlotValue,
     },
-    renderer::{RenderContext, RenderDevice},
+    renderer::{RenderContext, RenderDevice, TextureReadbacks},
 };
 
 /// The [`RenderGraphRunner`] is responsible for executing a [`RenderGraph`].
@@ -78,6 +78,9 @@ impl RenderGraphRunner {
 
         let mut render_context =
             RenderContext::new(render_device, adapter.get_info(), diagnostics_recorder);
+        if let Some(texture_readbacks) = world.get_resource::<TextureReadbacks>() {
+            render_context.texture_readbacks = texture_readbacks.clone();
+        }
         Self::run_graph(graph, None, &mut render_context, world, &[], None)?;
         finalizer(render_context.command_encoder());
 
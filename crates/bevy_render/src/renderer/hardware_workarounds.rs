@@ -0,0 +1,151 @@
+use bevy_ecs::system::Resource;
+use wgpu::AdapterInfo;
+
+/// Workarounds for driver and hardware bugs that apply to the adapter in use.
+///
+/// This is derived from the [`RenderAdapterInfo`](super::RenderAdapterInfo) when the renderer
+/// is initialized and inserted into the render world, so that feature code can check a flag
+/// instead of parsing adapter names itself. New workarounds should be added as fields here, with
+/// their detection next to the existing ones.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HardwareWorkarounds {
+    /// GPU preprocessing crashes on Adreno 600 series GPUs.
+    pub disable_gpu_preprocessing: bool,
+    /// Mip levels generated by compute shaders are corrupted on Adreno 600 series GPUs and on Mali
+    /// GPUs with drivers older than r38, so mips have to be generated with render passes instead.
+    pub disable_compute_mipgen: bool,
+    /// Mali GPUs with drivers older than r32 lose precision in
+    /// [`TextureFormat::Depth24Plus`](wgpu::TextureFormat::Depth24Plus) depth textures, so
+    /// [`TextureFormat::Depth32Float`](wgpu::TextureFormat::Depth32Float) has to be used instead.
+    pub force_depth32: bool,
+}
+
+impl HardwareWorkarounds {
+    /// Detects the workarounds needed by the given adapter.
+    ///
+    /// The detected GPUs are only known to misbehave on Android, so no workarounds are enabled
+    /// on other platforms.
+    pub fn new(adapter_info: &AdapterInfo) -> Self {
+        if cfg!(target_os = "android") {
+            Self::from_adapter_info(adapter_info)
+        } else {
+            Self::default()
+        }
+    }
+
+    fn from_adapter_info(adapter_info: &AdapterInfo) -> Self {
+        let adreno_600_series =
+            adreno_model(adapter_info).is_some_and(|model| (600..700).contains(&model));
+        let mali_driver_version = mali_driver_version(adapter_info);
+        Self {
+            disable_gpu_preprocessing: adreno_600_series,
+            disable_compute_mipgen: adreno_600_series
+                || mali_driver_version.is_some_and(|version| version < 38),
+            force_depth32: mali_driver_version.is_some_and(|version| version < 32),
+        }
+    }
+}
+
+/// Returns the model number of an Adreno GPU, e.g. `642` for an "Adreno (TM) 642L".
+pub fn adreno_model(adapter_info: &AdapterInfo) -> Option<u32> {
+    let model = adapter_info.name.strip_prefix("Adreno (TM) ")?;
+    // Ignore suffixes, like the `L` of the Adreno 642L.
+    let digits = model.len() - model.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    model[..digits].parse().ok()
+}
+
+/// Returns the driver version of a Mali GPU, e.g. `38` for the "v1.r38p1" driver.
+pub fn mali_driver_version(adapter_info: &AdapterInfo) -> Option<u32> {
+    if !adapter_info.name.contains("Mali") {
+        return None;
+    }
+    let version = adapter_info.driver_info.split_once("v1.r")?.1;
+    version.split_once('p')?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wgpu::{Backend, DeviceType};
+
+    fn adapter_info(name: &str, driver_info: &str) -> AdapterInfo {
+        AdapterInfo {
+            name: name.to_string(),
+            vendor: 0,
+            device: 0,
+            device_type: DeviceType::IntegratedGpu,
+            driver: String::new(),
+            driver_info: driver_info.to_string(),
+            backend: Backend::Vulkan,
+        }
+    }
+
+    #[test]
+    fn detects_adreno_models() {
+        assert_eq!(
+            adreno_model(&adapter_info("Adreno (TM) 642L", "")),
+            Some(642)
+        );
+        assert_eq!(
+            adreno_model(&adapter_info("Adreno (TM) 740", "")),
+            Some(740)
+        );
+        assert_eq!(adreno_model(&adapter_info("Mali-G78", "")), None);
+    }
+
+    #[test]
+    fn detects_mali_driver_versions() {
+        assert_eq!(
+            mali_driver_version(&adapter_info("Mali-G78", "v1.r38p1-01eac0")),
+            Some(38)
+        );
+        assert_eq!(
+            mali_driver_version(&adapter_info("Adreno (TM) 740", "v1.r38p1")),
+            None
+        );
+        assert_eq!(mali_driver_version(&adapter_info("Mali-G78", "")), None);
+    }
+
+    #[test]
+    fn workarounds_for_representative_adapters() {
+        let workarounds = |name, driver_info| {
+            HardwareWorkarounds::from_adapter_info(&adapter_info(name, driver_info))
+        };
+        assert_eq!(
+            workarounds("Adreno (TM) 642L", ""),
+            HardwareWorkarounds {
+                disable_gpu_preprocessing: true,
+                disable_compute_mipgen: true,
+                force_depth32: false,
+            }
+        );
+        assert_eq!(
+            workarounds("Adreno (TM) 740", ""),
+            HardwareWorkarounds::default()
+        );
+        assert_eq!(
+            workarounds("Mali-G76", "v1.r26p0-01eac0"),
+            HardwareWorkarounds {
+                disable_gpu_preprocessing: false,
+                disable_compute_mipgen: true,
+                force_depth32: true,
+            }
+        );
+        assert_eq!(
+            workarounds("Mali-G78", "v1.r32p1-01eac0"),
+            HardwareWorkarounds {
+                disable_gpu_preprocessing: false,
+                disable_compute_mipgen: true,
+                force_depth32: false,
+            }
+        );
+        assert_eq!(
+            workarounds("Mali-G715", "v1.r38p1-01eac0"),
+            HardwareWorkarounds::default()
+        );
+        assert_eq!(
+            workarounds("NVIDIA GeForce RTX 4090", "560.35.03"),
+            HardwareWorkarounds::default()
+        );
+    }
+}
@@ -1,24 +1,35 @@
 mod graph_runner;
+mod hardware_workarounds;
+mod render_backend;
+mod render_capabilities;
 mod render_device;
+mod texture_readback;
 
 use bevy_derive::{Deref, DerefMut};
 use bevy_tasks::ComputeTaskPool;
 use bevy_utils::tracing::{error, info, info_span, warn};
 pub use graph_runner::*;
+pub use hardware_workarounds::*;
+pub use render_backend::*;
+pub use render_capabilities::*;
 pub use render_device::*;
+pub use texture_readback::*;
 
 use crate::{
     diagnostic::{internal::DiagnosticsRecorder, RecordDiagnostics},
     render_graph::RenderGraph,
     render_phase::TrackedRenderPass,
-    render_resource::RenderPassDescriptor,
+    render_resource::{BufferDescriptor, BufferUsages, RenderPassDescriptor, Texture, TextureView},
     settings::{WgpuSettings, WgpuSettingsPriority},
     view::{ExtractedWindows, ViewTarget},
+    RenderEnabled,
 };
+use bevy_color::{Color, LinearRgba};
 use bevy_ecs::{prelude::*, system::SystemState};
 use bevy_time::TimeSender;
 use bevy_utils::Instant;
 use std::sync::Arc;
+use thiserror::Error;
 use wgpu::{
     Adapter, AdapterInfo, CommandBuffer, CommandEncoder, DeviceType, Instance, Queue,
     RequestAdapterOptions,
@@ -26,10 +37,35 @@ use wgpu::{
 
 /// Updates the [`RenderGraph`] with all of its nodes and then runs it to render the entire frame.
 pub fn render_system(world: &mut World, state: &mut SystemState<Query<Entity, With<ViewTarget>>>) {
+    if !world
+        .get_resource::<RenderEnabled>()
+        .map_or(true, |render_enabled| render_enabled.0)
+    {
+        if let Some(mut pending_command_buffers) = world.get_resource_mut::<PendingCommandBuffers>()
+        {
+            pending_command_buffers.buffers.clear();
+        }
+        if let Some(mut immediate_draws) = world.get_resource_mut::<ImmediateDraws>() {
+            immediate_draws.draws.clear();
+        }
+        send_render_time(world);
+        return;
+    }
+
     world.resource_scope(|world, mut graph: Mut<RenderGraph>| {
         graph.update(world);
     });
 
+    let (pre_graph_command_buffers, post_graph_command_buffers) = world
+        .get_resource_mut::<PendingCommandBuffers>()
+        .map(|mut pending_command_buffers| pending_command_buffers.take_around_graph())
+        .unwrap_or_default();
+    if !pre_graph_command_buffers.is_empty() {
+        world
+            .resource::<RenderQueue>()
+            .submit(pre_graph_command_buffers);
+    }
+
     let diagnostics_recorder = world.remove_resource::<DiagnosticsRecorder>();
 
     let graph = world.resource::<RenderGraph>();
@@ -71,6 +107,23 @@ pub fn render_system(world: &mut World, state: &mut SystemState<Query<Entity, Wi
         }
     }
 
+    if !post_graph_command_buffers.is_empty() {
+        world
+            .resource::<RenderQueue>()
+            .submit(post_graph_command_buffers);
+    }
+
+    if world
+        .get_resource::<ImmediateDraws>()
+        .is_some_and(|immediate_draws| !immediate_draws.is_empty())
+    {
+        run_immediate_draws(world);
+    }
+
+    if let Some(texture_readbacks) = world.get_resource::<TextureReadbacks>() {
+        texture_readbacks.map_recorded();
+    }
+
     {
         let _span = info_span!("present_frames").entered();
 
@@ -103,8 +156,57 @@ pub fn render_system(world: &mut World, state: &mut SystemState<Query<Entity, Wi
     }
 
     crate::view::screenshot::collect_screenshots(world);
+    if let Some(texture_readbacks) = world.get_resource::<TextureReadbacks>() {
+        texture_readbacks.collect_mapped();
+    }
+
+    send_render_time(world);
+}
+
+/// Runs and submits the [`ImmediateDraws`] of this frame, targeting the primary window.
+fn run_immediate_draws(world: &mut World) {
+    let draws = std::mem::take(&mut world.resource_mut::<ImmediateDraws>().draws);
 
-    // update the time and send it to the app world
+    let windows = world.resource::<ExtractedWindows>();
+    let Some(target) = windows
+        .primary
+        .and_then(|primary| windows.get(&primary))
+        .and_then(|window| window.swap_chain_texture_view.as_ref())
+    else {
+        return;
+    };
+
+    let command_buffers = record_immediate_draws(
+        draws,
+        world.resource::<RenderDevice>().clone(),
+        world.resource::<RenderAdapter>().get_info(),
+        world.get_resource::<TextureReadbacks>(),
+        target,
+    );
+    world.resource::<RenderQueue>().submit(command_buffers);
+}
+
+/// Records `draws` in push order, with `target` as their target.
+fn record_immediate_draws(
+    draws: Vec<ImmediateDraw>,
+    render_device: RenderDevice,
+    adapter_info: AdapterInfo,
+    texture_readbacks: Option<&TextureReadbacks>,
+    target: &TextureView,
+) -> Vec<CommandBuffer> {
+    let mut render_context = RenderContext::new(render_device, adapter_info, None);
+    if let Some(texture_readbacks) = texture_readbacks {
+        render_context.texture_readbacks = texture_readbacks.clone();
+    }
+    for draw in draws {
+        draw(&mut render_context, target);
+    }
+    let (command_buffers, _, _) = render_context.finish();
+    command_buffers
+}
+
+/// Updates the time and sends it to the app world.
+fn send_render_time(world: &World) {
     let time_sender = world.resource::<TimeSender>();
     if let Err(error) = time_sender.0.try_send(Instant::now()) {
         match error {
@@ -118,6 +220,91 @@ pub fn render_system(world: &mut World, state: &mut SystemState<Query<Entity, Wi
     }
 }
 
+/// Command buffers recorded outside of the [`RenderGraph`], waiting to be submitted by
+/// [`render_system`].
+///
+/// Each buffer is enqueued with an order: buffers with a negative order are submitted before the
+/// command buffers of the render graph, the others after them. Within each side, buffers are
+/// submitted in ascending order, and buffers with the same order in the order they were enqueued.
+/// This lets plugins that record work in their own systems run it at a deterministic point of the
+/// frame.
+#[derive(Resource, Default)]
+pub struct PendingCommandBuffers {
+    buffers: Vec<(i32, WgpuWrapper<CommandBuffer>)>,
+}
+
+impl PendingCommandBuffers {
+    /// Enqueues `command_buffer` for submission this frame at the given `order`.
+    pub fn submit_ordered(&mut self, order: i32, command_buffer: CommandBuffer) {
+        self.buffers.push((order, WgpuWrapper::new(command_buffer)));
+    }
+
+    /// Returns the number of enqueued command buffers.
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns `true` if no command buffers are enqueued.
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    /// Removes all enqueued command buffers, returning the ones to submit before the render graph
+    /// and the ones to submit after it.
+    pub(crate) fn take_around_graph(&mut self) -> (Vec<CommandBuffer>, Vec<CommandBuffer>) {
+        let (pre_graph, post_graph) = split_around_graph(std::mem::take(&mut self.buffers));
+        (
+            pre_graph.into_iter().map(WgpuWrapper::into_inner).collect(),
+            post_graph
+                .into_iter()
+                .map(WgpuWrapper::into_inner)
+                .collect(),
+        )
+    }
+}
+
+/// Stably sorts `items` by their order and splits them into the negative and non-negative orders.
+fn split_around_graph<T>(mut items: Vec<(i32, T)>) -> (Vec<T>, Vec<T>) {
+    items.sort_by_key(|(order, _)| *order);
+    let split = items.partition_point(|(order, _)| *order < 0);
+    let post_graph = items.split_off(split);
+    (
+        items.into_iter().map(|(_, item)| item).collect(),
+        post_graph.into_iter().map(|(_, item)| item).collect(),
+    )
+}
+
+/// A draw recorded by [`ImmediateDraws`], given the primary window's texture view as target.
+pub type ImmediateDraw = Box<dyn FnOnce(&mut RenderContext, &TextureView) + Send + Sync>;
+
+/// One-off draws issued from regular render world systems, without a render graph node.
+///
+/// This is a debugging convenience, e.g. to quickly fill the screen with a color. The draws pushed
+/// during a frame run once, in push order, after the render graph and its command buffers have
+/// been submitted, with the swap chain texture view of the primary window as target. They are
+/// dropped if there is no primary window this frame, and they don't show up in screenshots.
+///
+/// Nothing is done while the resource is empty.
+#[derive(Resource, Default)]
+pub struct ImmediateDraws {
+    draws: Vec<ImmediateDraw>,
+}
+
+impl ImmediateDraws {
+    /// Adds a draw to run at the end of this frame.
+    pub fn push(
+        &mut self,
+        draw: impl FnOnce(&mut RenderContext, &TextureView) + Send + Sync + 'static,
+    ) {
+        self.draws.push(Box::new(draw));
+    }
+
+    /// Returns `true` if no draws have been pushed this frame.
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+}
+
 /// A wrapper to safely make `wgpu` types Send / Sync on web with atomics enabled.
 /// On web with `atomics` enabled the inner value can only be accessed
 /// or dropped on the `wgpu` thread or else a panic will occur.
@@ -140,6 +327,10 @@ impl<T> WgpuWrapper<T> {
     pub fn new(t: T) -> Self {
         Self(t)
     }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
 }
 
 #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
@@ -147,6 +338,10 @@ impl<T> WgpuWrapper<T> {
     pub fn new(t: T) -> Self {
         Self(send_wrapper::SendWrapper::new(t))
     }
+
+    pub fn into_inner(self) -> T {
+        self.0.take()
+    }
 }
 
 /// This queue is used to enqueue tasks for the GPU to execute asynchronously.
@@ -168,22 +363,38 @@ pub struct RenderInstance(pub Arc<WgpuWrapper<Instance>>);
 pub struct RenderAdapterInfo(pub WgpuWrapper<AdapterInfo>);
 
 const GPU_NOT_FOUND_ERROR_MESSAGE: &str = if cfg!(target_os = "linux") {
-    "Unable to find a GPU! Make sure you have installed required drivers! For extra information, see: https://github.com/bevyengine/bevy/blob/latest/docs/linux_dependencies.md"
+    "Unable to find a GPU! Make sure you have installed required drivers! If no GPU is available (e.g. on headless CI), try WGPU_FORCE_FALLBACK_ADAPTER=1 to use a software adapter. For extra information, see: https://github.com/bevyengine/bevy/blob/latest/docs/linux_dependencies.md"
 } else {
-    "Unable to find a GPU! Make sure you have installed required drivers!"
+    "Unable to find a GPU! Make sure you have installed required drivers! If no GPU is available (e.g. on headless CI), try WGPU_FORCE_FALLBACK_ADAPTER=1 to use a software adapter."
 };
 
+/// An error that occurred while initializing the renderer in [`initialize_renderer`].
+#[derive(Error, Debug)]
+pub enum RenderInitializationError {
+    /// No adapter compatible with the requested options was found.
+    #[error("{}", GPU_NOT_FOUND_ERROR_MESSAGE)]
+    NoCompatibleAdapter,
+    /// An adapter was found, but the render device could not be created from it.
+    #[error("Failed to create the render device: {0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+}
+
 /// Initializes the renderer by retrieving and preparing the GPU instance, device and queue
 /// for the specified backend.
+///
+/// Returns an error instead of panicking if no compatible adapter is found or the device
+/// can't be created, so that the failure can be reported once the [`RenderPlugin`](crate::RenderPlugin)
+/// finishes instead of being lost in an async task.
 pub async fn initialize_renderer(
     instance: &Instance,
     options: &WgpuSettings,
     request_adapter_options: &RequestAdapterOptions<'_, '_>,
-) -> (RenderDevice, RenderQueue, RenderAdapterInfo, RenderAdapter) {
+) -> Result<(RenderDevice, RenderQueue, RenderAdapterInfo, RenderAdapter), RenderInitializationError>
+{
     let adapter = instance
         .request_adapter(request_adapter_options)
         .await
-        .expect(GPU_NOT_FOUND_ERROR_MESSAGE);
+        .ok_or(RenderInitializationError::NoCompatibleAdapter)?;
 
     let adapter_info = adapter.get_info();
     info!("{:?}", adapter_info);
@@ -358,16 +569,15 @@ pub async fn initialize_renderer(
             },
             trace_path,
         )
-        .await
-        .unwrap();
+        .await?;
     let queue = Arc::new(WgpuWrapper::new(queue));
     let adapter = Arc::new(WgpuWrapper::new(adapter));
-    (
+    Ok((
         RenderDevice::from(device),
         RenderQueue(queue),
         RenderAdapterInfo(WgpuWrapper::new(adapter_info)),
         RenderAdapter(adapter),
-    )
+    ))
 }
 
 /// The context with all information required to interact with the GPU.
@@ -380,6 +590,7 @@ pub struct RenderContext<'w> {
     command_buffer_queue: Vec<QueuedCommandBuffer<'w>>,
     force_serial: bool,
     diagnostics_recorder: Option<Arc<DiagnosticsRecorder>>,
+    texture_readbacks: TextureReadbacks,
 }
 
 impl<'w> RenderContext<'w> {
@@ -405,6 +616,7 @@ impl<'w> RenderContext<'w> {
             command_buffer_queue: Vec::new(),
             force_serial,
             diagnostics_recorder: diagnostics_recorder.map(Arc::new),
+            texture_readbacks: TextureReadbacks::default(),
         }
     }
 
@@ -443,6 +655,79 @@ impl<'w> RenderContext<'w> {
         TrackedRenderPass::new(&self.render_device, render_pass)
     }
 
+    /// Clears the texture behind `view` to `color`, using a render pass that does nothing but clear it.
+    ///
+    /// The texture must have been created with [`wgpu::TextureUsages::RENDER_ATTACHMENT`]. To clear a
+    /// texture to zero without that usage, use [`CommandEncoder::clear_texture`] on the
+    /// [`command_encoder`](Self::command_encoder) instead.
+    pub fn clear_texture(&mut self, view: &TextureView, color: Color) {
+        let color: LinearRgba = color.into();
+        self.command_encoder()
+            .begin_render_pass(&RenderPassDescriptor {
+                label: Some("clear_texture"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(color.into()),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+    }
+
+    /// Records a copy of `region` of `texture` into a CPU-readable buffer, returning the id
+    /// under which its texels become available in the [`TextureReadbacks`] resource on a later
+    /// frame.
+    ///
+    /// The texture must have been created with [`wgpu::TextureUsages::COPY_SRC`]. Nothing is
+    /// recorded if `region` doesn't fit in its mip level or the texture has a depth, stencil or
+    /// compressed format. The copy is recorded on the [`command_encoder`](Self::command_encoder),
+    /// so it sees everything recorded before it in this context. Only the contexts of the render
+    /// graph and of [`ImmediateDraws`] report to the [`TextureReadbacks`] resource, readbacks of
+    /// other contexts are never resolved.
+    pub fn copy_texture_to_readback(
+        &mut self,
+        texture: &Texture,
+        region: ReadbackRegion,
+    ) -> Result<ReadbackId, TextureReadbackError> {
+        let pixel_size = validate_readback(texture, &region)?;
+        let (layout, size) = readback_buffer_layout(region.size, pixel_size);
+        let buffer = self.render_device.create_buffer(&BufferDescriptor {
+            label: Some("texture_readback_buffer"),
+            size,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        self.command_encoder().copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: region.mip_level,
+                origin: wgpu::Origin3d {
+                    x: region.origin.x,
+                    y: region.origin.y,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout,
+            },
+            wgpu::Extent3d {
+                width: region.size.x,
+                height: region.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        Ok(self
+            .texture_readbacks
+            .record(buffer, region.size, pixel_size))
+    }
+
     /// Append a [`CommandBuffer`] to the command buffer queue.
     ///
     /// If present, this will flush the currently unflushed [`CommandEncoder`]
@@ -569,3 +854,175 @@ enum QueuedCommandBuffer<'w> {
     #[cfg(all(target_arch = "wasm32", target_feature = "atomics"))]
     Task(Box<dyn FnOnce(RenderDevice) -> CommandBuffer + 'w>),
 }
+
+/// Creates a device for tests that need real GPU resources, along with its queue and adapter.
+///
+/// Tests using it are marked `#[ignore = "requires a GPU adapter"]`, so they're reported as ignored
+/// rather than passing on machines without an adapter. Run them with `cargo test -- --ignored`.
+///
+/// # Panics
+///
+/// Panics if there's no adapter to create the device with.
+#[cfg(test)]
+pub(crate) fn test_device() -> (RenderDevice, RenderQueue, RenderAdapter) {
+    let instance = Instance::default();
+    let adapter = bevy_tasks::block_on(instance.request_adapter(&RequestAdapterOptions::default()))
+        .expect("GPU tests require an adapter");
+    let (device, queue) =
+        bevy_tasks::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+            .expect("failed to create a device for GPU tests");
+    (
+        device.into(),
+        RenderQueue(Arc::new(WgpuWrapper::new(queue))),
+        RenderAdapter(Arc::new(WgpuWrapper::new(adapter))),
+    )
+}
+
+/// Maps `buffer`, which must have been created with [`BufferUsages::MAP_READ`], once the work
+/// submitted so far is done, and returns its contents.
+#[cfg(test)]
+pub(crate) fn test_read_buffer(
+    render_device: &RenderDevice,
+    buffer: &crate::render_resource::Buffer,
+) -> Vec<u8> {
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |result| {
+        result.expect("failed to map the buffer");
+    });
+    render_device.poll(wgpu::Maintain::Wait);
+    let data = slice.get_mapped_range().to_vec();
+    buffer.unmap();
+    data
+}
+
+/// Copies the base mip level of `texture`, which must have been created with
+/// [`wgpu::TextureUsages::COPY_SRC`], once the work submitted so far is done, and returns its tightly
+/// packed texels.
+#[cfg(test)]
+pub(crate) fn test_read_texture(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    texture: &Texture,
+) -> Vec<u8> {
+    let region = ReadbackRegion::whole(texture);
+    let pixel_size = validate_readback(texture, &region).unwrap();
+    let (layout, size) = readback_buffer_layout(region.size, pixel_size);
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: None,
+        size,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut command_encoder =
+        render_device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    command_encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout,
+        },
+        texture.size(),
+    );
+    render_queue.submit([command_encoder.finish()]);
+
+    let mut data = test_read_buffer(render_device, &buffer);
+    crate::view::screenshot::remove_row_padding(
+        &mut data,
+        region.size.x,
+        region.size.y,
+        pixel_size as usize,
+    );
+    data
+}
+
+/// Creates a `width` by `height` [`TextureFormat::Rgba8Unorm`] texture that can be rendered to
+/// and read back with [`test_read_texture`].
+#[cfg(test)]
+pub(crate) fn test_render_target(render_device: &RenderDevice, width: u32, height: u32) -> Texture {
+    render_device.create_texture(&wgpu::TextureDescriptor {
+        label: None,
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_tasks::TaskPool;
+
+    #[test]
+    fn pending_command_buffers_are_split_around_the_graph_in_order() {
+        let pending = vec![
+            (10, "post b"),
+            (-1, "pre c"),
+            (0, "post a"),
+            (-5, "pre a"),
+            (-1, "pre d"),
+            (10, "post c"),
+            (-3, "pre b"),
+        ];
+
+        let (pre_graph, post_graph) = split_around_graph(pending);
+        assert_eq!(pre_graph, vec!["pre a", "pre b", "pre c", "pre d"]);
+        assert_eq!(post_graph, vec!["post a", "post b", "post c"]);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn immediate_draws_run_in_push_order() {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+        let (device, queue, adapter) = test_device();
+        let texture = test_render_target(&device, 4, 4);
+        let target = TextureView::from(texture.create_view(&Default::default()));
+
+        let mut immediate_draws = ImmediateDraws::default();
+        immediate_draws.push(|render_context, target| {
+            render_context.clear_texture(target, Color::srgb(1.0, 0.0, 0.0));
+        });
+        immediate_draws.push(|render_context, target| {
+            render_context.clear_texture(target, Color::srgb(0.0, 0.0, 1.0));
+        });
+        let command_buffers = record_immediate_draws(
+            std::mem::take(&mut immediate_draws.draws),
+            device.clone(),
+            adapter.get_info(),
+            None,
+            &target,
+        );
+        queue.submit(command_buffers);
+
+        assert_eq!(
+            test_read_texture(&device, &queue, &texture),
+            [0, 0, 255, 255].repeat(16)
+        );
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn clear_texture_fills_the_texture() {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+        let (device, queue, adapter) = test_device();
+        let texture = test_render_target(&device, 3, 2);
+        let view = TextureView::from(texture.create_view(&Default::default()));
+
+        let mut render_context = RenderContext::new(device.clone(), adapter.get_info(), None);
+        render_context.clear_texture(&view, Color::linear_rgba(0.0, 1.0, 0.0, 0.2));
+        let (command_buffers, _, _) = render_context.finish();
+        queue.submit(command_buffers);
+
+        assert_eq!(
+            test_read_texture(&device, &queue, &texture),
+            [0, 255, 0, 51].repeat(6)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use bevy_ecs::system::Resource;
+use wgpu::Backend;
+
+/// The graphics backend of the [`RenderAdapter`](super::RenderAdapter) in use.
+///
+/// This is derived from the [`RenderAdapterInfo`](super::RenderAdapterInfo) when the renderer
+/// is initialized and inserted into the render world, so that code which needs to work around
+/// backend-specific quirks can branch at runtime where `cfg!` is too coarse.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderBackend {
+    Vulkan,
+    Metal,
+    Dx12,
+    /// OpenGL, OpenGL ES or WebGL2.
+    Gl,
+    /// WebGPU in the browser.
+    BrowserWebGpu,
+}
+
+impl RenderBackend {
+    /// Maps a wgpu [`Backend`] to a [`RenderBackend`].
+    ///
+    /// Returns `None` for [`Backend::Empty`], which is never used by a real adapter.
+    pub fn from_backend(backend: Backend) -> Option<Self> {
+        match backend {
+            Backend::Empty => None,
+            Backend::Vulkan => Some(Self::Vulkan),
+            Backend::Metal => Some(Self::Metal),
+            Backend::Dx12 => Some(Self::Dx12),
+            Backend::Gl => Some(Self::Gl),
+            Backend::BrowserWebGpu => Some(Self::BrowserWebGpu),
+        }
+    }
+
+    /// Returns `true` if rendering through WebGL2.
+    #[inline]
+    pub fn is_webgl2(&self) -> bool {
+        cfg!(target_arch = "wasm32") && *self == Self::Gl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenderBackend;
+    use wgpu::Backend;
+
+    #[test]
+    fn maps_wgpu_backends() {
+        assert_eq!(RenderBackend::from_backend(Backend::Empty), None);
+        assert_eq!(
+            RenderBackend::from_backend(Backend::Vulkan),
+            Some(RenderBackend::Vulkan)
+        );
+        assert_eq!(
+            RenderBackend::from_backend(Backend::Metal),
+            Some(RenderBackend::Metal)
+        );
+        assert_eq!(
+            RenderBackend::from_backend(Backend::Dx12),
+            Some(RenderBackend::Dx12)
+        );
+        assert_eq!(
+            RenderBackend::from_backend(Backend::Gl),
+            Some(RenderBackend::Gl)
+        );
+        assert_eq!(
+            RenderBackend::from_backend(Backend::BrowserWebGpu),
+            Some(RenderBackend::BrowserWebGpu)
+        );
+    }
+}
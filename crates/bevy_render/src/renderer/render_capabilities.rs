@@ -0,0 +1,130 @@
+use crate::{
+    texture::{BevyDefault, CompressedImageFormats},
+    view::{Msaa, ViewTarget},
+};
+use bevy_ecs::system::Resource;
+use wgpu::{DownlevelFlags, Features, Limits, TextureFormat, TextureFormatFeatureFlags};
+
+use super::{RenderAdapter, RenderDevice};
+
+/// A summary of the capabilities of the [`RenderDevice`] and [`RenderAdapter`] in use.
+///
+/// This is computed once when the renderer is initialized and inserted into the render world,
+/// so that systems can query capabilities in one place instead of calling
+/// [`RenderDevice::limits`] or [`RenderDevice::features`] directly.
+#[derive(Resource, Clone, Debug)]
+pub struct RenderCapabilities {
+    /// The [`Limits`] of the [`RenderDevice`].
+    pub limits: Limits,
+    /// The [`Features`] enabled on the [`RenderDevice`].
+    pub features: Features,
+    /// The [`DownlevelFlags`] supported by the [`RenderAdapter`].
+    pub downlevel_flags: DownlevelFlags,
+    /// The compressed texture formats supported by the [`RenderDevice`].
+    pub compressed_image_formats: CompressedImageFormats,
+    /// The `MULTISAMPLE_*` flags supported by both the LDR and HDR main texture formats of views.
+    ///
+    /// See [`RenderCapabilities::supports_msaa`].
+    pub msaa_sample_flags: TextureFormatFeatureFlags,
+}
+
+impl RenderCapabilities {
+    /// Computes the capabilities of the given device and adapter.
+    pub fn new(render_device: &RenderDevice, render_adapter: &RenderAdapter) -> Self {
+        let features = render_device.features();
+        let sample_flags = |format| render_adapter.get_texture_format_features(format).flags;
+        Self {
+            limits: render_device.limits(),
+            features,
+            downlevel_flags: render_adapter.get_downlevel_capabilities().flags,
+            compressed_image_formats: CompressedImageFormats::from_features(features),
+            msaa_sample_flags: sample_flags(TextureFormat::bevy_default())
+                & sample_flags(ViewTarget::TEXTURE_FORMAT_HDR),
+        }
+    }
+
+    /// The maximum width and height of a 2D texture.
+    #[inline]
+    pub fn max_texture_dimension_2d(&self) -> u32 {
+        self.limits.max_texture_dimension_2d
+    }
+
+    /// The maximum number of storage buffers that can be bound in a single shader stage.
+    #[inline]
+    pub fn max_storage_buffers_per_shader_stage(&self) -> u32 {
+        self.limits.max_storage_buffers_per_shader_stage
+    }
+
+    /// Returns `true` if binding arrays (a.k.a. bindless textures) can be used.
+    pub fn supports_bindless_textures(&self) -> bool {
+        !cfg!(feature = "shader_format_glsl")
+            && self.features.contains(
+                Features::TEXTURE_BINDING_ARRAY
+                    | Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+            )
+    }
+
+    /// Returns `true` if timestamp queries can be written at the start and end of passes.
+    #[inline]
+    pub fn supports_timestamp_queries(&self) -> bool {
+        self.features.contains(Features::TIMESTAMP_QUERY)
+    }
+
+    /// Returns `true` if compute shaders are supported.
+    #[inline]
+    pub fn supports_compute_shaders(&self) -> bool {
+        self.downlevel_flags
+            .contains(DownlevelFlags::COMPUTE_SHADERS)
+    }
+
+    /// Returns `true` if samplers can use anisotropic filtering.
+    #[inline]
+    pub fn supports_anisotropic_filtering(&self) -> bool {
+        self.downlevel_flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING)
+    }
+
+    /// Returns `true` if the compressed texture `format` is supported.
+    ///
+    /// Uncompressed formats always return `true`.
+    #[inline]
+    pub fn supports_compressed_format(&self, format: TextureFormat) -> bool {
+        self.compressed_image_formats.supports(format)
+    }
+    /// Returns `true` if views can be rendered with `msaa`.
+    #[inline]
+    pub fn supports_msaa(&self, msaa: Msaa) -> bool {
+        msaa == Msaa::Off
+            || self
+                .msaa_sample_flags
+                .sample_count_supported(msaa.samples())
+    }
+
+    /// Returns the highest supported [`Msaa`] setting that doesn't exceed `msaa`.
+    pub fn clamp_msaa(&self, msaa: Msaa) -> Msaa {
+        [Msaa::Sample8, Msaa::Sample4, Msaa::Sample2]
+            .into_iter()
+            .find(|&supported| supported <= msaa && self.supports_msaa(supported))
+            .unwrap_or(Msaa::Off)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_msaa_to_supported_sample_counts() {
+        let capabilities = RenderCapabilities {
+            limits: Limits::default(),
+            features: Features::empty(),
+            downlevel_flags: DownlevelFlags::empty(),
+            compressed_image_formats: CompressedImageFormats::NONE,
+            msaa_sample_flags: TextureFormatFeatureFlags::MULTISAMPLE_X4,
+        };
+        assert_eq!(capabilities.clamp_msaa(Msaa::Sample8), Msaa::Sample4);
+        assert_eq!(capabilities.clamp_msaa(Msaa::Sample4), Msaa::Sample4);
+        assert_eq!(capabilities.clamp_msaa(Msaa::Sample2), Msaa::Off);
+        assert_eq!(capabilities.clamp_msaa(Msaa::Off), Msaa::Off);
+    }
+}
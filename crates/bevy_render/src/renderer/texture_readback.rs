@@ -0,0 +1,409 @@
+use crate::{
+    render_resource::{Buffer, Texture},
+    view::screenshot::{align_byte_size, remove_row_padding},
+};
+use bevy_ecs::system::Resource;
+use bevy_math::UVec2;
+use bevy_utils::HashMap;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use wgpu::{BufferAsyncError, ImageDataLayout, MapMode, TextureFormat};
+
+/// The number of frames the data of a readback stays available in [`TextureReadbacks`] once it's
+/// done, before it's dropped.
+const READY_FRAMES: u64 = 2;
+
+/// Identifies a texture readback requested with
+/// [`RenderContext::copy_texture_to_readback`](super::RenderContext::copy_texture_to_readback).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReadbackId(u64);
+
+/// The part of a texture copied by
+/// [`RenderContext::copy_texture_to_readback`](super::RenderContext::copy_texture_to_readback).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadbackRegion {
+    /// The mip level to copy from.
+    pub mip_level: u32,
+    /// The texel coordinates of the top left corner of the region in the mip level.
+    pub origin: UVec2,
+    /// The size of the region in texels.
+    pub size: UVec2,
+}
+
+impl ReadbackRegion {
+    /// The whole base mip level of `texture`.
+    pub fn whole(texture: &Texture) -> Self {
+        Self {
+            mip_level: 0,
+            origin: UVec2::ZERO,
+            size: UVec2::new(texture.width(), texture.height()),
+        }
+    }
+}
+
+/// The reason a texture readback failed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TextureReadbackError {
+    /// Depth, stencil, compressed and planar formats can't be read back.
+    #[error("texture format {0:?} can't be read back")]
+    UnsupportedFormat(TextureFormat),
+    /// The texture doesn't have the requested mip level.
+    #[error("texture has {mip_level_count} mip levels, mip level {mip_level} can't be read back")]
+    MissingMipLevel {
+        mip_level: u32,
+        mip_level_count: u32,
+    },
+    /// The region is empty or doesn't fit in its mip level.
+    #[error("readback region {region:?} doesn't fit in its mip level of size {mip_size}")]
+    InvalidRegion {
+        region: ReadbackRegion,
+        mip_size: UVec2,
+    },
+    /// The buffer the texture was copied into couldn't be mapped.
+    #[error("readback buffer couldn't be mapped: {0}")]
+    MapFailed(#[from] BufferAsyncError),
+}
+
+/// A render world resource collecting the texture readbacks requested with
+/// [`RenderContext::copy_texture_to_readback`](super::RenderContext::copy_texture_to_readback).
+///
+/// The copies are recorded with the other commands of the frame. Once they're submitted, their
+/// buffers are mapped, and the data becomes available through [`Self::take`] at the end of a
+/// later frame, usually the next one. Unlike screenshots, nothing is delivered to the main world:
+/// render world systems are expected to poll for the readbacks they requested. Results that
+/// aren't taken within two frames of becoming available are dropped.
+#[derive(Resource, Clone, Default)]
+pub struct TextureReadbacks(Arc<Mutex<ReadbackState>>);
+
+#[derive(Default)]
+struct ReadbackState {
+    next_id: u64,
+    /// The number of times [`TextureReadbacks::collect_mapped`] ran, to expire old results.
+    frame: u64,
+    /// Copies recorded this frame, whose buffers are mapped once they're submitted.
+    recorded: Vec<PendingReadback>,
+    /// Copies whose buffers are being mapped.
+    mapping: Vec<PendingReadback>,
+    ready: HashMap<ReadbackId, ReadyReadback>,
+}
+
+struct PendingReadback {
+    id: ReadbackId,
+    buffer: Buffer,
+    size: UVec2,
+    pixel_size: u32,
+    /// Set by the `map_async` callback.
+    map_result: Arc<Mutex<Option<Result<(), BufferAsyncError>>>>,
+}
+
+struct ReadyReadback {
+    result: Result<Vec<u8>, TextureReadbackError>,
+    /// The [`ReadbackState::frame`] the readback became available in.
+    frame: u64,
+}
+
+impl TextureReadbacks {
+    /// Removes and returns the tightly packed texels of a readback, if it's done.
+    ///
+    /// Returns `None` while the readback is pending, and once its result was taken or dropped.
+    pub fn take(&self, id: ReadbackId) -> Option<Result<Vec<u8>, TextureReadbackError>> {
+        self.0
+            .lock()
+            .unwrap()
+            .ready
+            .remove(&id)
+            .map(|ready| ready.result)
+    }
+
+    /// Returns `true` if the readback was requested but its result isn't available yet.
+    pub fn is_pending(&self, id: ReadbackId) -> bool {
+        let state = self.0.lock().unwrap();
+        state
+            .recorded
+            .iter()
+            .chain(&state.mapping)
+            .any(|readback| readback.id == id)
+    }
+
+    /// Registers a readback whose copy into `buffer` has been recorded.
+    pub(crate) fn record(&self, buffer: Buffer, size: UVec2, pixel_size: u32) -> ReadbackId {
+        let mut state = self.0.lock().unwrap();
+        let id = ReadbackId(state.next_id);
+        state.next_id += 1;
+        state.recorded.push(PendingReadback {
+            id,
+            buffer,
+            size,
+            pixel_size,
+            map_result: Arc::default(),
+        });
+        id
+    }
+
+    /// Starts mapping the buffers of the readbacks recorded this frame.
+    ///
+    /// This must be called once the commands copying into them have been submitted.
+    pub(crate) fn map_recorded(&self) {
+        let mut state = self.0.lock().unwrap();
+        let recorded = std::mem::take(&mut state.recorded);
+        for readback in recorded {
+            let map_result = readback.map_result.clone();
+            // The polling for this map call is done every frame when the command queue is submitted.
+            readback
+                .buffer
+                .slice(..)
+                .map_async(MapMode::Read, move |result| {
+                    *map_result.lock().unwrap() = Some(result);
+                });
+            state.mapping.push(readback);
+        }
+    }
+
+    /// Makes the results of the readbacks whose buffers were mapped, or failed to map, available
+    /// through [`Self::take`], and drops the results that weren't taken in time.
+    pub(crate) fn collect_mapped(&self) {
+        let mut state = self.0.lock().unwrap();
+        state.frame += 1;
+        let ReadbackState {
+            frame,
+            mapping,
+            ready,
+            ..
+        } = &mut *state;
+        mapping.retain(|readback| {
+            let Some(map_result) = readback.map_result.lock().unwrap().take() else {
+                return true;
+            };
+            let result = map_result
+                .map(|()| {
+                    let mut data = readback.buffer.slice(..).get_mapped_range().to_vec();
+                    readback.buffer.unmap();
+                    remove_row_padding(
+                        &mut data,
+                        readback.size.x,
+                        readback.size.y,
+                        readback.pixel_size as usize,
+                    );
+                    data
+                })
+                .map_err(TextureReadbackError::from);
+            ready.insert(
+                readback.id,
+                ReadyReadback {
+                    result,
+                    frame: *frame,
+                },
+            );
+            false
+        });
+        ready.retain(|_, ready| *frame - ready.frame < READY_FRAMES);
+    }
+}
+
+/// Checks that `region` of `texture` can be read back, and returns the size of its texels in
+/// bytes.
+pub(crate) fn validate_readback(
+    texture: &Texture,
+    region: &ReadbackRegion,
+) -> Result<u32, TextureReadbackError> {
+    let pixel_size = readback_pixel_size(texture.format())?;
+    validate_region(
+        region,
+        UVec2::new(texture.width(), texture.height()),
+        texture.mip_level_count(),
+    )?;
+    Ok(pixel_size)
+}
+
+fn readback_pixel_size(format: TextureFormat) -> Result<u32, TextureReadbackError> {
+    if format.is_depth_stencil_format() || format.block_dimensions() != (1, 1) {
+        return Err(TextureReadbackError::UnsupportedFormat(format));
+    }
+    format
+        .block_copy_size(None)
+        .ok_or(TextureReadbackError::UnsupportedFormat(format))
+}
+
+fn validate_region(
+    region: &ReadbackRegion,
+    texture_size: UVec2,
+    mip_level_count: u32,
+) -> Result<(), TextureReadbackError> {
+    if region.mip_level >= mip_level_count {
+        return Err(TextureReadbackError::MissingMipLevel {
+            mip_level: region.mip_level,
+            mip_level_count,
+        });
+    }
+    let mip_size = (texture_size >> region.mip_level).max(UVec2::ONE);
+    let fits = |origin: u32, size: u32, mip_size: u32| {
+        size > 0 && origin.checked_add(size).is_some_and(|end| end <= mip_size)
+    };
+    if !fits(region.origin.x, region.size.x, mip_size.x)
+        || !fits(region.origin.y, region.size.y, mip_size.y)
+    {
+        return Err(TextureReadbackError::InvalidRegion {
+            region: *region,
+            mip_size,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the layout and size of a buffer receiving a copy of `size` texels of `pixel_size`
+/// bytes, whose rows are padded to [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`].
+pub(crate) fn readback_buffer_layout(size: UVec2, pixel_size: u32) -> (ImageDataLayout, u64) {
+    let bytes_per_row = align_byte_size(size.x * pixel_size);
+    let layout = ImageDataLayout {
+        offset: 0,
+        bytes_per_row: Some(bytes_per_row),
+        rows_per_image: None,
+    };
+    (layout, u64::from(bytes_per_row) * u64::from(size.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readback_rows_are_padded() {
+        let size = UVec2::new(3, 2);
+        let (layout, buffer_size) = readback_buffer_layout(size, 4);
+        let bytes_per_row = layout.bytes_per_row.unwrap();
+        assert_eq!(bytes_per_row % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT, 0);
+        assert_eq!(buffer_size, u64::from(bytes_per_row * size.y));
+
+        let mut data = vec![0; buffer_size as usize];
+        data[bytes_per_row as usize] = 1;
+        remove_row_padding(&mut data, size.x, size.y, 4);
+        assert_eq!(data.len(), 3 * 2 * 4);
+        assert_eq!(data[3 * 4], 1);
+    }
+
+    #[test]
+    fn unsupported_formats_are_rejected() {
+        assert_eq!(readback_pixel_size(TextureFormat::Rgba8Unorm), Ok(4));
+        assert_eq!(readback_pixel_size(TextureFormat::R32Float), Ok(4));
+        for format in [
+            TextureFormat::Depth32Float,
+            TextureFormat::Depth24PlusStencil8,
+            TextureFormat::Bc1RgbaUnorm,
+        ] {
+            assert_eq!(
+                readback_pixel_size(format),
+                Err(TextureReadbackError::UnsupportedFormat(format))
+            );
+        }
+    }
+
+    #[test]
+    fn regions_must_fit_in_their_mip_level() {
+        let region = |mip_level, origin: [u32; 2], size: [u32; 2]| ReadbackRegion {
+            mip_level,
+            origin: origin.into(),
+            size: size.into(),
+        };
+        let texture_size = UVec2::new(64, 32);
+
+        assert_eq!(
+            validate_region(&region(0, [0, 0], [64, 32]), texture_size, 2),
+            Ok(())
+        );
+        assert_eq!(
+            validate_region(&region(1, [16, 8], [16, 8]), texture_size, 2),
+            Ok(())
+        );
+        assert_eq!(
+            validate_region(&region(1, [16, 8], [17, 8]), texture_size, 2),
+            Err(TextureReadbackError::InvalidRegion {
+                region: region(1, [16, 8], [17, 8]),
+                mip_size: UVec2::new(32, 16),
+            })
+        );
+        assert!(validate_region(&region(0, [0, 0], [0, 1]), texture_size, 1).is_err());
+        assert!(validate_region(&region(0, [u32::MAX, 0], [1, 1]), texture_size, 1).is_err());
+        assert_eq!(
+            validate_region(&region(2, [0, 0], [1, 1]), texture_size, 2),
+            Err(TextureReadbackError::MissingMipLevel {
+                mip_level: 2,
+                mip_level_count: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn ready_results_expire() {
+        let readbacks = TextureReadbacks::default();
+        let id = ReadbackId(0);
+        readbacks.0.lock().unwrap().ready.insert(
+            id,
+            ReadyReadback {
+                result: Err(BufferAsyncError.into()),
+                frame: 0,
+            },
+        );
+
+        readbacks.collect_mapped();
+        assert!(readbacks.0.lock().unwrap().ready.contains_key(&id));
+        readbacks.collect_mapped();
+        assert_eq!(readbacks.take(id), None);
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn texture_readbacks_resolve_after_a_frame() {
+        bevy_tasks::ComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default);
+        let (device, queue, adapter) = crate::renderer::test_device();
+        // Rows of 3 texels need padding in the readback buffer.
+        let texels: Vec<u8> = (0..3 * 2 * 4).collect();
+        let texture = device.create_texture_with_data(
+            &queue,
+            &wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 3,
+                    height: 2,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: TextureFormat::Rgba8Unorm,
+                usage: wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &texels,
+        );
+
+        let readbacks = TextureReadbacks::default();
+        let mut render_context =
+            crate::renderer::RenderContext::new(device.clone(), adapter.get_info(), None);
+        render_context.texture_readbacks = readbacks.clone();
+        let whole = render_context
+            .copy_texture_to_readback(&texture, ReadbackRegion::whole(&texture))
+            .unwrap();
+        let region = ReadbackRegion {
+            mip_level: 0,
+            origin: UVec2::new(1, 1),
+            size: UVec2::new(2, 1),
+        };
+        let part = render_context
+            .copy_texture_to_readback(&texture, region)
+            .unwrap();
+        let (command_buffers, _, _) = render_context.finish();
+        queue.submit(command_buffers);
+
+        // What `render_system` does once the frame is submitted, and at the end of the frame.
+        readbacks.map_recorded();
+        assert!(readbacks.is_pending(whole));
+        device.poll(wgpu::Maintain::Wait);
+        readbacks.collect_mapped();
+
+        assert_eq!(readbacks.take(whole), Some(Ok(texels.clone())));
+        assert_eq!(readbacks.take(part), Some(Ok(texels[16..24].to_vec())));
+        assert!(!readbacks.is_pending(whole));
+        assert_eq!(readbacks.take(whole), None);
+    }
+}
@@ -3,12 +3,28 @@ use basis_universal::{
 };
 use wgpu::{AstcBlock, AstcChannel, Extent3d, TextureDimension, TextureFormat};
 
-use super::{CompressedImageFormats, Image, TextureError};
+use super::{CompressedImageFormats, Image, PreferredCompressedFormats, TextureError};
 
 pub fn basis_buffer_to_image(
     buffer: &[u8],
     supported_compressed_formats: CompressedImageFormats,
     is_srgb: bool,
+) -> Result<Image, TextureError> {
+    basis_buffer_to_image_with_preferred_formats(
+        buffer,
+        supported_compressed_formats,
+        &PreferredCompressedFormats::default(),
+        is_srgb,
+    )
+}
+
+/// Like [`basis_buffer_to_image`], but transcodes to the first supported format in
+/// `preferred_compressed_formats`.
+pub fn basis_buffer_to_image_with_preferred_formats(
+    buffer: &[u8],
+    supported_compressed_formats: CompressedImageFormats,
+    preferred_compressed_formats: &PreferredCompressedFormats,
+    is_srgb: bool,
 ) -> Result<Image, TextureError> {
     let mut transcoder = Transcoder::new();
 
@@ -28,8 +44,11 @@ pub fn basis_buffer_to_image(
 
     // First deal with transcoding to the desired format
     // FIXME: Use external metadata to transcode to more appropriate formats for 1- or 2-component sources
-    let (transcode_format, texture_format) =
-        get_transcoded_formats(supported_compressed_formats, is_srgb);
+    let (transcode_format, texture_format) = get_transcoded_formats_with_preferred_formats(
+        supported_compressed_formats,
+        preferred_compressed_formats,
+        is_srgb,
+    );
     let basis_texture_format = transcoder.basis_texture_format(buffer);
     if !basis_texture_format.can_transcode_to_format(transcode_format) {
         return Err(TextureError::UnsupportedTextureFormat(format!(
@@ -124,47 +143,59 @@ pub fn get_transcoded_formats(
     supported_compressed_formats: CompressedImageFormats,
     is_srgb: bool,
 ) -> (TranscoderTextureFormat, TextureFormat) {
-    // NOTE: UASTC can be losslessly transcoded to ASTC4x4 and ASTC uses the same
-    // space as BC7 (128-bits per 4x4 texel block) so prefer ASTC over BC for
-    // transcoding speed and quality.
-    if supported_compressed_formats.contains(CompressedImageFormats::ASTC_LDR) {
-        (
-            TranscoderTextureFormat::ASTC_4x4_RGBA,
-            TextureFormat::Astc {
-                block: AstcBlock::B4x4,
-                channel: if is_srgb {
-                    AstcChannel::UnormSrgb
+    get_transcoded_formats_with_preferred_formats(
+        supported_compressed_formats,
+        &PreferredCompressedFormats::default(),
+        is_srgb,
+    )
+}
+
+/// Like [`get_transcoded_formats`], but picks the first supported format in
+/// `preferred_compressed_formats`.
+pub fn get_transcoded_formats_with_preferred_formats(
+    supported_compressed_formats: CompressedImageFormats,
+    preferred_compressed_formats: &PreferredCompressedFormats,
+    is_srgb: bool,
+) -> (TranscoderTextureFormat, TextureFormat) {
+    for format in preferred_compressed_formats.iter_supported(supported_compressed_formats) {
+        if format.contains(CompressedImageFormats::ASTC_LDR) {
+            return (
+                TranscoderTextureFormat::ASTC_4x4_RGBA,
+                TextureFormat::Astc {
+                    block: AstcBlock::B4x4,
+                    channel: if is_srgb {
+                        AstcChannel::UnormSrgb
+                    } else {
+                        AstcChannel::Unorm
+                    },
+                },
+            );
+        } else if format.contains(CompressedImageFormats::BC) {
+            return (
+                TranscoderTextureFormat::BC7_RGBA,
+                if is_srgb {
+                    TextureFormat::Bc7RgbaUnormSrgb
+                } else {
+                    TextureFormat::Bc7RgbaUnorm
+                },
+            );
+        } else if format.contains(CompressedImageFormats::ETC2) {
+            return (
+                TranscoderTextureFormat::ETC2_RGBA,
+                if is_srgb {
+                    TextureFormat::Etc2Rgba8UnormSrgb
                 } else {
-                    AstcChannel::Unorm
+                    TextureFormat::Etc2Rgba8Unorm
                 },
-            },
-        )
-    } else if supported_compressed_formats.contains(CompressedImageFormats::BC) {
-        (
-            TranscoderTextureFormat::BC7_RGBA,
-            if is_srgb {
-                TextureFormat::Bc7RgbaUnormSrgb
-            } else {
-                TextureFormat::Bc7RgbaUnorm
-            },
-        )
-    } else if supported_compressed_formats.contains(CompressedImageFormats::ETC2) {
-        (
-            TranscoderTextureFormat::ETC2_RGBA,
-            if is_srgb {
-                TextureFormat::Etc2Rgba8UnormSrgb
-            } else {
-                TextureFormat::Etc2Rgba8Unorm
-            },
-        )
-    } else {
-        (
-            TranscoderTextureFormat::RGBA32,
-            if is_srgb {
-                TextureFormat::Rgba8UnormSrgb
-            } else {
-                TextureFormat::Rgba8Unorm
-            },
-        )
+            );
+        }
     }
+    (
+        TranscoderTextureFormat::RGBA32,
+        if is_srgb {
+            TextureFormat::Rgba8UnormSrgb
+        } else {
+            TextureFormat::Rgba8Unorm
+        },
+    )
 }
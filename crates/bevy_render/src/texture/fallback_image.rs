@@ -123,6 +123,9 @@ fn fallback_image_new(
         sampler,
         size: image.size(),
         mip_level_count: image.texture_descriptor.mip_level_count,
+        mip_level_views: Vec::new(),
+        format_views: Vec::new(),
+        format_mip_level_views: Vec::new(),
     }
 }
 
@@ -6,6 +6,7 @@ use super::dds::*;
 use super::ktx2::*;
 
 use crate::{
+    extract_resource::ExtractResource,
     render_asset::{PrepareAssetError, RenderAsset, RenderAssetUsages},
     render_resource::{Sampler, Texture, TextureView},
     renderer::{RenderDevice, RenderQueue},
@@ -16,8 +17,12 @@ use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::system::{lifetimeless::SRes, Resource, SystemParamItem};
 use bevy_math::{AspectRatio, UVec2, Vec2};
 use bevy_reflect::prelude::*;
+use bevy_utils::HashMap;
 use serde::{Deserialize, Serialize};
-use std::hash::Hash;
+use std::{
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
 use thiserror::Error;
 use wgpu::{Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor};
 
@@ -175,7 +180,112 @@ impl ImageSampler {
 /// The [`ImagePlugin`](super::ImagePlugin) can be set during app initialization to change the default
 /// image sampler.
 #[derive(Resource, Debug, Clone, Deref, DerefMut)]
-pub struct DefaultImageSampler(pub(crate) Sampler);
+pub struct DefaultImageSampler(
+    #[deref] pub(crate) Sampler,
+    pub(crate) ImageSamplerDescriptor,
+    pub(crate) AnisotropicSamplers,
+);
+
+impl DefaultImageSampler {
+    /// Returns the sampler of an image with `mip_level_count` mip levels that uses the default
+    /// sampler, with `anisotropy` applied if it applies to the image.
+    fn sampler(
+        &self,
+        render_device: &RenderDevice,
+        anisotropy: Option<&DefaultSamplerAnisotropy>,
+        mip_level_count: u32,
+    ) -> Sampler {
+        let Some(descriptor) = anisotropy.and_then(|anisotropy| {
+            anisotropy.apply(&self.1, mip_level_count, self.2.max_anisotropy_clamp)
+        }) else {
+            return self.0.clone();
+        };
+        self.2
+            .samplers
+            .lock()
+            .unwrap()
+            .entry(descriptor.anisotropy_clamp)
+            .or_insert_with(|| render_device.create_sampler(&descriptor.as_wgpu()))
+            .clone()
+    }
+}
+
+/// The anisotropic variants of the [`DefaultImageSampler`], keyed by anisotropy clamp.
+///
+/// They're created the first time an image needs them and shared by all images, as backends
+/// limit the number of samplers that can exist at once.
+#[derive(Debug, Clone)]
+pub(crate) struct AnisotropicSamplers {
+    /// The largest anisotropy clamp supported by the adapter.
+    max_anisotropy_clamp: u16,
+    samplers: Arc<Mutex<HashMap<u16, Sampler>>>,
+}
+
+impl AnisotropicSamplers {
+    pub(crate) fn new(supports_anisotropic_filtering: bool) -> Self {
+        Self {
+            max_anisotropy_clamp: if supports_anisotropic_filtering {
+                DefaultSamplerAnisotropy::MAX
+            } else {
+                1
+            },
+            samplers: Arc::default(),
+        }
+    }
+}
+
+/// The anisotropy clamp applied to images using the default sampler ([`ImageSampler::Default`]).
+///
+/// Anisotropic filtering sharpens textures viewed at oblique angles. It is only applied to images
+/// with more than one mip level and when the default sampler uses linear filtering for
+/// magnification, minification and mipmaps, as required by `wgpu`. The value is clamped to `1..=16`,
+/// the range accepted by `wgpu`, and ignored on adapters without
+/// [`DownlevelFlags::ANISOTROPIC_FILTERING`](wgpu::DownlevelFlags::ANISOTROPIC_FILTERING).
+/// Images with an explicit [`ImageSampler::Descriptor`] are not affected.
+///
+/// This resource is extracted from the main world. Changing it only affects images prepared
+/// afterwards.
+#[derive(Resource, ExtractResource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultSamplerAnisotropy(pub u16);
+
+impl Default for DefaultSamplerAnisotropy {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl DefaultSamplerAnisotropy {
+    /// The maximum anisotropy clamp supported by `wgpu`.
+    pub const MAX: u16 = 16;
+
+    /// Returns `descriptor` with this anisotropy applied, or `None` if it doesn't apply to
+    /// an image with `mip_level_count` mip levels sampled with `descriptor`.
+    ///
+    /// The anisotropy is clamped to `max_anisotropy_clamp`, the largest clamp supported by the
+    /// adapter: [`Self::MAX`], or `1` if it doesn't support anisotropic filtering.
+    pub fn apply(
+        &self,
+        descriptor: &ImageSamplerDescriptor,
+        mip_level_count: u32,
+        max_anisotropy_clamp: u16,
+    ) -> Option<ImageSamplerDescriptor> {
+        let anisotropy_clamp = self.0.clamp(1, max_anisotropy_clamp.clamp(1, Self::MAX));
+        let all_linear = [
+            descriptor.mag_filter,
+            descriptor.min_filter,
+            descriptor.mipmap_filter,
+        ]
+        .iter()
+        .all(|filter| matches!(filter, ImageFilterMode::Linear));
+        if anisotropy_clamp == 1 || mip_level_count <= 1 || !all_linear {
+            return None;
+        }
+        Some(ImageSamplerDescriptor {
+            anisotropy_clamp,
+            ..descriptor.clone()
+        })
+    }
+}
 
 /// How edges should be handled in texture addressing.
 ///
@@ -707,10 +817,36 @@ impl Image {
     /// Load a bytes buffer in a [`Image`], according to type `image_type`, using the `image`
     /// crate
     pub fn from_buffer(
+        #[cfg(all(debug_assertions, feature = "dds"))] name: String,
+        buffer: &[u8],
+        image_type: ImageType,
+        supported_compressed_formats: CompressedImageFormats,
+        is_srgb: bool,
+        image_sampler: ImageSampler,
+        asset_usage: RenderAssetUsages,
+    ) -> Result<Image, TextureError> {
+        Self::from_buffer_with_preferred_formats(
+            #[cfg(all(debug_assertions, feature = "dds"))]
+            name,
+            buffer,
+            image_type,
+            supported_compressed_formats,
+            &PreferredCompressedFormats::default(),
+            is_srgb,
+            image_sampler,
+            asset_usage,
+        )
+    }
+
+    /// Like [`Image::from_buffer`], but transcodes universal textures to the first supported format
+    /// in `preferred_compressed_formats`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_buffer_with_preferred_formats(
         #[cfg(all(debug_assertions, feature = "dds"))] name: String,
         buffer: &[u8],
         image_type: ImageType,
         #[allow(unused_variables)] supported_compressed_formats: CompressedImageFormats,
+        #[allow(unused_variables)] preferred_compressed_formats: &PreferredCompressedFormats,
         is_srgb: bool,
         image_sampler: ImageSampler,
         asset_usage: RenderAssetUsages,
@@ -725,9 +861,12 @@ impl Image {
 
         let mut image = match format {
             #[cfg(feature = "basis-universal")]
-            ImageFormat::Basis => {
-                basis_buffer_to_image(buffer, supported_compressed_formats, is_srgb)?
-            }
+            ImageFormat::Basis => basis_buffer_to_image_with_preferred_formats(
+                buffer,
+                supported_compressed_formats,
+                preferred_compressed_formats,
+                is_srgb,
+            )?,
             #[cfg(feature = "dds")]
             ImageFormat::Dds => dds_buffer_to_image(
                 #[cfg(debug_assertions)]
@@ -737,9 +876,12 @@ impl Image {
                 is_srgb,
             )?,
             #[cfg(feature = "ktx2")]
-            ImageFormat::Ktx2 => {
-                ktx2_buffer_to_image(buffer, supported_compressed_formats, is_srgb)?
-            }
+            ImageFormat::Ktx2 => ktx2_buffer_to_image_with_preferred_formats(
+                buffer,
+                supported_compressed_formats,
+                preferred_compressed_formats,
+                is_srgb,
+            )?,
             _ => {
                 let image_crate_format = format
                     .as_image_crate_format()
@@ -878,6 +1020,59 @@ pub struct GpuImage {
     pub sampler: Sampler,
     pub size: UVec2,
     pub mip_level_count: u32,
+    /// Views of each individual mip level, used when rendering to a single mip level.
+    ///
+    /// Only populated for images with [`wgpu::TextureUsages::RENDER_ATTACHMENT`] and more than one mip level.
+    pub mip_level_views: Vec<TextureView>,
+    /// Views of the image in each of its extra view formats, used when rendering to the image
+    /// through a [`RenderTargetViewFormats`](crate::camera::RenderTargetViewFormats).
+    pub format_views: Vec<(TextureFormat, TextureView)>,
+    /// Views of each individual mip level in each of the image's extra view formats.
+    ///
+    /// Populated like [`Self::mip_level_views`], for each format in [`Self::format_views`].
+    pub format_mip_level_views: Vec<(TextureFormat, Vec<TextureView>)>,
+}
+
+impl GpuImage {
+    /// Returns a view of a single mip level of this image, suitable for use as a render attachment.
+    pub fn mip_level_view(&self, mip_level: u32) -> Option<&TextureView> {
+        if mip_level == 0 && self.mip_level_views.is_empty() {
+            return Some(&self.texture_view);
+        }
+        self.mip_level_views.get(mip_level as usize)
+    }
+
+    /// Returns a view of this image in the given format, if the image was created with it as one of
+    /// its view formats.
+    pub fn format_view(&self, format: TextureFormat) -> Option<&TextureView> {
+        if format == self.texture_format {
+            return Some(&self.texture_view);
+        }
+        self.format_views
+            .iter()
+            .find(|(view_format, _)| *view_format == format)
+            .map(|(_, view)| view)
+    }
+
+    /// Returns a view of a single mip level of this image in the given format, suitable for use as
+    /// a render attachment.
+    pub fn format_mip_level_view(
+        &self,
+        format: TextureFormat,
+        mip_level: u32,
+    ) -> Option<&TextureView> {
+        if format == self.texture_format {
+            return self.mip_level_view(mip_level);
+        }
+        let Some((_, views)) = self
+            .format_mip_level_views
+            .iter()
+            .find(|(view_format, _)| *view_format == format)
+        else {
+            return (mip_level == 0).then(|| self.format_view(format)).flatten();
+        };
+        views.get(mip_level as usize)
+    }
 }
 
 impl RenderAsset for GpuImage {
@@ -886,6 +1081,7 @@ impl RenderAsset for GpuImage {
         SRes<RenderDevice>,
         SRes<RenderQueue>,
         SRes<DefaultImageSampler>,
+        Option<SRes<DefaultSamplerAnisotropy>>,
     );
 
     #[inline]
@@ -901,7 +1097,9 @@ impl RenderAsset for GpuImage {
     /// Converts the extracted image into a [`GpuImage`].
     fn prepare_asset(
         image: Self::SourceAsset,
-        (render_device, render_queue, default_sampler): &mut SystemParamItem<Self::Param>,
+        (render_device, render_queue, default_sampler, default_anisotropy): &mut SystemParamItem<
+            Self::Param,
+        >,
     ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
         let texture = render_device.create_texture_with_data(
             render_queue,
@@ -912,6 +1110,10 @@ impl RenderAsset for GpuImage {
         );
 
         let size = image.size();
+        let view_format = image
+            .texture_view_descriptor
+            .as_ref()
+            .and_then(|descriptor| descriptor.format);
         let texture_view = texture.create_view(
             image
                 .texture_view_descriptor
@@ -920,23 +1122,87 @@ impl RenderAsset for GpuImage {
                 .unwrap(),
         );
         let sampler = match image.sampler {
-            ImageSampler::Default => (***default_sampler).clone(),
+            ImageSampler::Default => default_sampler.sampler(
+                render_device,
+                default_anisotropy.as_deref(),
+                image.texture_descriptor.mip_level_count,
+            ),
             ImageSampler::Descriptor(descriptor) => {
                 render_device.create_sampler(&descriptor.as_wgpu())
             }
         };
 
+        let mip_level_count = image.texture_descriptor.mip_level_count;
+        let has_mip_level_views = mip_level_count > 1
+            && image
+                .texture_descriptor
+                .usage
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+        let create_mip_level_views = |format: Option<TextureFormat>| -> Vec<TextureView> {
+            if !has_mip_level_views {
+                return Vec::new();
+            }
+            (0..mip_level_count)
+                .map(|mip_level| texture.create_view(&mip_level_view_descriptor(mip_level, format)))
+                .collect()
+        };
+        // Render into the mip levels through the same format as the image's own view.
+        let mip_level_views = create_mip_level_views(view_format);
+
+        let extra_view_formats: Vec<TextureFormat> = image
+            .texture_descriptor
+            .view_formats
+            .iter()
+            .copied()
+            .filter(|&format| format != image.texture_descriptor.format)
+            .collect();
+        let format_views = extra_view_formats
+            .iter()
+            .map(|&format| {
+                let view = texture.create_view(&TextureViewDescriptor {
+                    format: Some(format),
+                    ..Default::default()
+                });
+                (format, view)
+            })
+            .collect();
+        let format_mip_level_views = if has_mip_level_views {
+            extra_view_formats
+                .iter()
+                .map(|&format| (format, create_mip_level_views(Some(format))))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
         Ok(GpuImage {
             texture,
             texture_view,
             texture_format: image.texture_descriptor.format,
             sampler,
             size,
-            mip_level_count: image.texture_descriptor.mip_level_count,
+            mip_level_count,
+            mip_level_views,
+            format_views,
+            format_mip_level_views,
         })
     }
 }
 
+/// The descriptor of a view that only covers the given mip level in `format`, for rendering into
+/// it.
+fn mip_level_view_descriptor(
+    mip_level: u32,
+    format: Option<TextureFormat>,
+) -> TextureViewDescriptor<'static> {
+    TextureViewDescriptor {
+        format,
+        base_mip_level: mip_level,
+        mip_level_count: Some(1),
+        ..Default::default()
+    }
+}
+
 bitflags::bitflags! {
     #[derive(Default, Clone, Copy, Eq, PartialEq, Debug)]
     #[repr(transparent)]
@@ -995,6 +1261,44 @@ impl CompressedImageFormats {
     }
 }
 
+/// The order in which [`CompressedImageFormats`] are preferred when transcoding universal
+/// (Basis Universal and UASTC KTX2) textures at load time.
+///
+/// Transcoding falls back through the list to the first format the device supports, and to an
+/// uncompressed format if none are. This can be used to e.g. prefer the smaller ASTC format over BC
+/// on devices supporting both.
+///
+/// The [`ImageLoader`](super::ImageLoader) reads this resource when it is created, so it must be
+/// inserted before [`ImagePlugin`](super::ImagePlugin) finishes.
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct PreferredCompressedFormats(pub Vec<CompressedImageFormats>);
+
+impl Default for PreferredCompressedFormats {
+    fn default() -> Self {
+        // NOTE: UASTC can be losslessly transcoded to ASTC4x4 and ASTC uses the same
+        // space as BC7 (128-bits per 4x4 texel block) so prefer ASTC over BC for
+        // transcoding speed and quality.
+        Self(vec![
+            CompressedImageFormats::ASTC_LDR,
+            CompressedImageFormats::BC,
+            CompressedImageFormats::ETC2,
+        ])
+    }
+}
+
+impl PreferredCompressedFormats {
+    /// Iterates over the preferred formats contained in `supported_compressed_formats`, in order
+    /// of preference.
+    pub fn iter_supported(
+        &self,
+        supported_compressed_formats: CompressedImageFormats,
+    ) -> impl Iterator<Item = CompressedImageFormats> + '_ {
+        self.0.iter().copied().filter(move |format| {
+            !format.is_empty() && supported_compressed_formats.contains(*format)
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1019,10 +1323,129 @@ mod test {
         );
     }
 
+    #[test]
+    fn mip_level_view_targets_a_single_mip() {
+        let descriptor = mip_level_view_descriptor(1, None);
+        assert_eq!(descriptor.base_mip_level, 1);
+        assert_eq!(descriptor.mip_level_count, Some(1));
+        assert_eq!(descriptor.base_array_layer, 0);
+        assert_eq!(descriptor.format, None);
+
+        let descriptor = mip_level_view_descriptor(2, Some(TextureFormat::Rgba8UnormSrgb));
+        assert_eq!(descriptor.base_mip_level, 2);
+        assert_eq!(descriptor.format, Some(TextureFormat::Rgba8UnormSrgb));
+    }
+
     #[test]
     fn image_default_size() {
         let image = Image::default();
         assert_eq!(UVec2::ONE, image.size());
         assert_eq!(Vec2::ONE, image.size_f32());
     }
+
+    #[test]
+    fn preferred_compressed_formats_order() {
+        let supported = CompressedImageFormats::BC | CompressedImageFormats::ASTC_LDR;
+
+        let default = PreferredCompressedFormats::default();
+        assert_eq!(
+            default.iter_supported(supported).collect::<Vec<_>>(),
+            vec![CompressedImageFormats::ASTC_LDR, CompressedImageFormats::BC]
+        );
+
+        let prefer_bc = PreferredCompressedFormats(vec![
+            CompressedImageFormats::ETC2,
+            CompressedImageFormats::BC,
+            CompressedImageFormats::ASTC_LDR,
+        ]);
+        assert_eq!(
+            prefer_bc.iter_supported(supported).next(),
+            Some(CompressedImageFormats::BC)
+        );
+
+        let etc2_only = PreferredCompressedFormats(vec![CompressedImageFormats::ETC2]);
+        assert_eq!(etc2_only.iter_supported(supported).next(), None);
+    }
+
+    #[test]
+    fn default_sampler_anisotropy() {
+        let linear = ImageSamplerDescriptor {
+            mipmap_filter: ImageFilterMode::Linear,
+            ..ImageSamplerDescriptor::linear()
+        };
+
+        let max = DefaultSamplerAnisotropy::MAX;
+
+        let descriptor = DefaultSamplerAnisotropy(8).apply(&linear, 4, max).unwrap();
+        assert_eq!(descriptor.anisotropy_clamp, 8);
+
+        // Clamped to the maximum supported by wgpu.
+        let descriptor = DefaultSamplerAnisotropy(64).apply(&linear, 4, max).unwrap();
+        assert_eq!(descriptor.anisotropy_clamp, DefaultSamplerAnisotropy::MAX);
+
+        // Clamped to the maximum supported by the adapter.
+        let descriptor = DefaultSamplerAnisotropy(16).apply(&linear, 4, 4).unwrap();
+        assert_eq!(descriptor.anisotropy_clamp, 4);
+        assert!(DefaultSamplerAnisotropy(16).apply(&linear, 4, 1).is_none());
+
+        // Images without mips and non-linear samplers are left untouched.
+        assert!(DefaultSamplerAnisotropy(8).apply(&linear, 1, max).is_none());
+        assert!(DefaultSamplerAnisotropy(8)
+            .apply(&ImageSamplerDescriptor::nearest(), 4, max)
+            .is_none());
+    }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn prepared_images_use_the_clamped_anisotropic_default_sampler() {
+        use bevy_ecs::{system::SystemState, world::World};
+
+        let (render_device, render_queue, _) = crate::renderer::test_device();
+        let descriptor = ImageSamplerDescriptor::linear();
+        let default_sampler = DefaultImageSampler(
+            render_device.create_sampler(&descriptor.as_wgpu()),
+            descriptor,
+            AnisotropicSamplers::new(true),
+        );
+        let mut world = World::new();
+        world.insert_resource(render_device);
+        world.insert_resource(render_queue);
+        world.insert_resource(default_sampler.clone());
+        // Clamped to `DefaultSamplerAnisotropy::MAX`.
+        world.insert_resource(DefaultSamplerAnisotropy(64));
+        let mut param = SystemState::<<GpuImage as RenderAsset>::Param>::new(&mut world);
+
+        // A 4x4 image with 3 mip levels.
+        let mipmapped_image = |sampler| {
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[255; 4],
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::RENDER_WORLD,
+            );
+            image.texture_descriptor.mip_level_count = 3;
+            image.data = vec![255; (16 + 4 + 1) * 4];
+            image.sampler = sampler;
+            image
+        };
+        let mut prepare =
+            |image| GpuImage::prepare_asset(image, &mut param.get_mut(&mut world)).unwrap();
+
+        let gpu_image = prepare(mipmapped_image(ImageSampler::Default));
+        let anisotropic_sampler_id =
+            default_sampler.2.samplers.lock().unwrap()[&DefaultSamplerAnisotropy::MAX].id();
+        assert_eq!(gpu_image.sampler.id(), anisotropic_sampler_id);
+        assert_ne!(gpu_image.sampler.id(), default_sampler.0.id());
+
+        // Images with their own sampler don't get the default anisotropy.
+        let gpu_image = prepare(mipmapped_image(ImageSampler::linear()));
+        assert_ne!(gpu_image.sampler.id(), anisotropic_sampler_id);
+        assert_ne!(gpu_image.sampler.id(), default_sampler.0.id());
+        assert_eq!(default_sampler.2.samplers.lock().unwrap().len(), 1);
+    }
 }
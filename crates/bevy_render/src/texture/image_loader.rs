@@ -8,13 +8,14 @@ use crate::{
     texture::{Image, ImageFormat, ImageType, TextureError},
 };
 
-use super::{CompressedImageFormats, ImageSampler};
+use super::{CompressedImageFormats, ImageSampler, PreferredCompressedFormats};
 use serde::{Deserialize, Serialize};
 
 /// Loader for images that can be read by the `image` crate.
 #[derive(Clone)]
 pub struct ImageLoader {
     supported_compressed_formats: CompressedImageFormats,
+    preferred_compressed_formats: PreferredCompressedFormats,
 }
 
 pub(crate) const IMG_FILE_EXTENSIONS: &[&str] = &[
@@ -114,12 +115,13 @@ impl AssetLoader for ImageLoader {
                 )?)
             }
         };
-        Ok(Image::from_buffer(
+        Ok(Image::from_buffer_with_preferred_formats(
             #[cfg(all(debug_assertions, feature = "dds"))]
             load_context.path().display().to_string(),
             &bytes,
             image_type,
             self.supported_compressed_formats,
+            &self.preferred_compressed_formats,
             settings.is_srgb,
             settings.sampler.clone(),
             settings.asset_usage,
@@ -142,8 +144,13 @@ impl FromWorld for ImageLoader {
 
             None => CompressedImageFormats::NONE,
         };
+        let preferred_compressed_formats = world
+            .get_resource::<PreferredCompressedFormats>()
+            .cloned()
+            .unwrap_or_default();
         Self {
             supported_compressed_formats,
+            preferred_compressed_formats,
         }
     }
 }
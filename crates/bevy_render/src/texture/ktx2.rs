@@ -18,12 +18,31 @@ use wgpu::{
     TextureViewDimension,
 };
 
-use super::{CompressedImageFormats, DataFormat, Image, TextureError, TranscodeFormat};
+use super::{
+    CompressedImageFormats, DataFormat, Image, PreferredCompressedFormats, TextureError,
+    TranscodeFormat,
+};
 
 pub fn ktx2_buffer_to_image(
     buffer: &[u8],
     supported_compressed_formats: CompressedImageFormats,
     is_srgb: bool,
+) -> Result<Image, TextureError> {
+    ktx2_buffer_to_image_with_preferred_formats(
+        buffer,
+        supported_compressed_formats,
+        &PreferredCompressedFormats::default(),
+        is_srgb,
+    )
+}
+
+/// Like [`ktx2_buffer_to_image`], but transcodes UASTC textures to the first supported format in
+/// `preferred_compressed_formats`.
+pub fn ktx2_buffer_to_image_with_preferred_formats(
+    buffer: &[u8],
+    supported_compressed_formats: CompressedImageFormats,
+    #[allow(unused_variables)] preferred_compressed_formats: &PreferredCompressedFormats,
+    is_srgb: bool,
 ) -> Result<Image, TextureError> {
     let ktx2 = ktx2::Reader::new(buffer)
         .map_err(|err| TextureError::InvalidData(format!("Failed to parse ktx2 file: {err:?}")))?;
@@ -149,7 +168,12 @@ pub fn ktx2_buffer_to_image(
                 #[cfg(feature = "basis-universal")]
                 TranscodeFormat::Uastc(data_format) => {
                     let (transcode_block_format, texture_format) =
-                        get_transcoded_formats(supported_compressed_formats, data_format, is_srgb);
+                        get_transcoded_formats_with_preferred_formats(
+                            supported_compressed_formats,
+                            preferred_compressed_formats,
+                            data_format,
+                            is_srgb,
+                        );
                     let texture_format_info = texture_format;
                     let (block_width_pixels, block_height_pixels) = (
                         texture_format_info.block_dimensions().0,
@@ -312,79 +336,106 @@ pub fn get_transcoded_formats(
     data_format: DataFormat,
     is_srgb: bool,
 ) -> (TranscoderBlockFormat, TextureFormat) {
-    match data_format {
-        DataFormat::Rrr => {
-            if supported_compressed_formats.contains(CompressedImageFormats::BC) {
-                (TranscoderBlockFormat::BC4, TextureFormat::Bc4RUnorm)
-            } else if supported_compressed_formats.contains(CompressedImageFormats::ETC2) {
-                (
-                    TranscoderBlockFormat::ETC2_EAC_R11,
-                    TextureFormat::EacR11Unorm,
-                )
-            } else {
-                (TranscoderBlockFormat::RGBA32, TextureFormat::R8Unorm)
+    get_transcoded_formats_with_preferred_formats(
+        supported_compressed_formats,
+        &PreferredCompressedFormats::default(),
+        data_format,
+        is_srgb,
+    )
+}
+
+/// Like [`get_transcoded_formats`], but picks the first supported format in
+/// `preferred_compressed_formats`.
+#[cfg(feature = "basis-universal")]
+pub fn get_transcoded_formats_with_preferred_formats(
+    supported_compressed_formats: CompressedImageFormats,
+    preferred_compressed_formats: &PreferredCompressedFormats,
+    data_format: DataFormat,
+    is_srgb: bool,
+) -> (TranscoderBlockFormat, TextureFormat) {
+    for format in preferred_compressed_formats.iter_supported(supported_compressed_formats) {
+        let transcoded = match data_format {
+            DataFormat::Rrr => {
+                if format.contains(CompressedImageFormats::BC) {
+                    Some((TranscoderBlockFormat::BC4, TextureFormat::Bc4RUnorm))
+                } else if format.contains(CompressedImageFormats::ETC2) {
+                    Some((
+                        TranscoderBlockFormat::ETC2_EAC_R11,
+                        TextureFormat::EacR11Unorm,
+                    ))
+                } else {
+                    None
+                }
             }
-        }
-        DataFormat::Rrrg | DataFormat::Rg => {
-            if supported_compressed_formats.contains(CompressedImageFormats::BC) {
-                (TranscoderBlockFormat::BC5, TextureFormat::Bc5RgUnorm)
-            } else if supported_compressed_formats.contains(CompressedImageFormats::ETC2) {
-                (
-                    TranscoderBlockFormat::ETC2_EAC_RG11,
-                    TextureFormat::EacRg11Unorm,
-                )
-            } else {
-                (TranscoderBlockFormat::RGBA32, TextureFormat::Rg8Unorm)
+            DataFormat::Rrrg | DataFormat::Rg => {
+                if format.contains(CompressedImageFormats::BC) {
+                    Some((TranscoderBlockFormat::BC5, TextureFormat::Bc5RgUnorm))
+                } else if format.contains(CompressedImageFormats::ETC2) {
+                    Some((
+                        TranscoderBlockFormat::ETC2_EAC_RG11,
+                        TextureFormat::EacRg11Unorm,
+                    ))
+                } else {
+                    None
+                }
             }
-        }
-        // NOTE: Rgba16Float should be transcoded to BC6H/ASTC_HDR. Neither are supported by
-        // basis-universal, nor is ASTC_HDR supported by wgpu
-        DataFormat::Rgb | DataFormat::Rgba => {
-            // NOTE: UASTC can be losslessly transcoded to ASTC4x4 and ASTC uses the same
-            // space as BC7 (128-bits per 4x4 texel block) so prefer ASTC over BC for
-            // transcoding speed and quality.
-            if supported_compressed_formats.contains(CompressedImageFormats::ASTC_LDR) {
-                (
-                    TranscoderBlockFormat::ASTC_4x4,
-                    TextureFormat::Astc {
-                        block: AstcBlock::B4x4,
-                        channel: if is_srgb {
-                            AstcChannel::UnormSrgb
+            // NOTE: Rgba16Float should be transcoded to BC6H/ASTC_HDR. Neither are supported by
+            // basis-universal, nor is ASTC_HDR supported by wgpu
+            DataFormat::Rgb | DataFormat::Rgba => {
+                if format.contains(CompressedImageFormats::ASTC_LDR) {
+                    Some((
+                        TranscoderBlockFormat::ASTC_4x4,
+                        TextureFormat::Astc {
+                            block: AstcBlock::B4x4,
+                            channel: if is_srgb {
+                                AstcChannel::UnormSrgb
+                            } else {
+                                AstcChannel::Unorm
+                            },
+                        },
+                    ))
+                } else if format.contains(CompressedImageFormats::BC) {
+                    Some((
+                        TranscoderBlockFormat::BC7,
+                        if is_srgb {
+                            TextureFormat::Bc7RgbaUnormSrgb
                         } else {
-                            AstcChannel::Unorm
+                            TextureFormat::Bc7RgbaUnorm
                         },
-                    },
-                )
-            } else if supported_compressed_formats.contains(CompressedImageFormats::BC) {
-                (
-                    TranscoderBlockFormat::BC7,
-                    if is_srgb {
-                        TextureFormat::Bc7RgbaUnormSrgb
-                    } else {
-                        TextureFormat::Bc7RgbaUnorm
-                    },
-                )
-            } else if supported_compressed_formats.contains(CompressedImageFormats::ETC2) {
-                (
-                    TranscoderBlockFormat::ETC2_RGBA,
-                    if is_srgb {
-                        TextureFormat::Etc2Rgba8UnormSrgb
-                    } else {
-                        TextureFormat::Etc2Rgba8Unorm
-                    },
-                )
-            } else {
-                (
-                    TranscoderBlockFormat::RGBA32,
-                    if is_srgb {
-                        TextureFormat::Rgba8UnormSrgb
-                    } else {
-                        TextureFormat::Rgba8Unorm
-                    },
-                )
+                    ))
+                } else if format.contains(CompressedImageFormats::ETC2) {
+                    Some((
+                        TranscoderBlockFormat::ETC2_RGBA,
+                        if is_srgb {
+                            TextureFormat::Etc2Rgba8UnormSrgb
+                        } else {
+                            TextureFormat::Etc2Rgba8Unorm
+                        },
+                    ))
+                } else {
+                    None
+                }
             }
+        };
+        if let Some(transcoded) = transcoded {
+            return transcoded;
         }
     }
+
+    match data_format {
+        DataFormat::Rrr => (TranscoderBlockFormat::RGBA32, TextureFormat::R8Unorm),
+        DataFormat::Rrrg | DataFormat::Rg => {
+            (TranscoderBlockFormat::RGBA32, TextureFormat::Rg8Unorm)
+        }
+        DataFormat::Rgb | DataFormat::Rgba => (
+            TranscoderBlockFormat::RGBA32,
+            if is_srgb {
+                TextureFormat::Rgba8UnormSrgb
+            } else {
+                TextureFormat::Rgba8Unorm
+            },
+        ),
+    }
 }
 
 pub fn ktx2_get_texture_format<Data: AsRef<[u8]>>(
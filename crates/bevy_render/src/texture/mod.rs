@@ -37,7 +37,10 @@ pub use texture_attachment::*;
 pub use texture_cache::*;
 
 use crate::{
-    render_asset::RenderAssetPlugin, renderer::RenderDevice, Render, RenderApp, RenderSet,
+    extract_resource::ExtractResourcePlugin,
+    render_asset::RenderAssetPlugin,
+    renderer::{RenderCapabilities, RenderDevice},
+    Render, RenderApp, RenderSet,
 };
 use bevy_app::{App, Plugin};
 use bevy_asset::{AssetApp, Assets, Handle};
@@ -92,10 +95,15 @@ impl Plugin for ImagePlugin {
             app.init_asset_loader::<HdrTextureLoader>();
         }
 
-        app.add_plugins(RenderAssetPlugin::<GpuImage>::default())
-            .register_type::<Image>()
-            .init_asset::<Image>()
-            .register_asset_reflect::<Image>();
+        app.add_plugins((
+            RenderAssetPlugin::<GpuImage>::default(),
+            ExtractResourcePlugin::<DefaultSamplerAnisotropy>::default(),
+        ))
+        .init_resource::<DefaultSamplerAnisotropy>()
+        .init_resource::<PreferredCompressedFormats>()
+        .register_type::<Image>()
+        .init_asset::<Image>()
+        .register_asset_reflect::<Image>();
 
         let mut image_assets = app.world_mut().resource_mut::<Assets<Image>>();
 
@@ -156,8 +164,16 @@ impl Plugin for ImagePlugin {
                 let device = render_app.world().resource::<RenderDevice>();
                 device.create_sampler(&self.default_sampler.as_wgpu())
             };
+            let supports_anisotropic_filtering = render_app
+                .world()
+                .get_resource::<RenderCapabilities>()
+                .map_or(true, RenderCapabilities::supports_anisotropic_filtering);
             render_app
-                .insert_resource(DefaultImageSampler(default_sampler))
+                .insert_resource(DefaultImageSampler(
+                    default_sampler,
+                    self.default_sampler.clone(),
+                    AnisotropicSamplers::new(supports_anisotropic_filtering),
+                ))
                 .init_resource::<FallbackImage>()
                 .init_resource::<FallbackImageZero>()
                 .init_resource::<FallbackImageCubemap>()
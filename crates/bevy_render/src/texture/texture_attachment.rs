@@ -44,10 +44,7 @@ impl ColorAttachment {
                 view: &resolve_target.default_view,
                 resolve_target: Some(&self.texture.default_view),
                 ops: Operations {
-                    load: match (self.clear_color, first_call) {
-                        (Some(clear_color), true) => LoadOp::Clear(clear_color.into()),
-                        (None, _) | (Some(_), false) => LoadOp::Load,
-                    },
+                    load: color_load_op(self.clear_color, first_call),
                     store: StoreOp::Store,
                 },
             }
@@ -67,10 +64,7 @@ impl ColorAttachment {
             view: &self.texture.default_view,
             resolve_target: None,
             ops: Operations {
-                load: match (self.clear_color, first_call) {
-                    (Some(clear_color), true) => LoadOp::Clear(clear_color.into()),
-                    (None, _) | (Some(_), false) => LoadOp::Load,
-                },
+                load: color_load_op(self.clear_color, first_call),
                 store: StoreOp::Store,
             },
         }
@@ -81,6 +75,15 @@ impl ColorAttachment {
     }
 }
 
+/// The load op of a color attachment: only the first pass writing to it clears it, and only if
+/// it has a clear color.
+fn color_load_op(clear_color: Option<LinearRgba>, first_call: bool) -> LoadOp<wgpu::Color> {
+    match (clear_color, first_call) {
+        (Some(clear_color), true) => LoadOp::Clear(clear_color.into()),
+        (None, _) | (Some(_), false) => LoadOp::Load,
+    }
+}
+
 /// A wrapper for a [`TextureView`] that is used as a depth-only [`RenderPassDepthStencilAttachment`].
 pub struct DepthAttachment {
     pub view: TextureView,
@@ -158,3 +161,35 @@ impl OutputColorAttachment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::{
+        ClearColor, ClearColorConfig, ManualTextureViewHandle, NormalizedRenderTarget,
+        RenderTargetClearColor,
+    };
+    use bevy_color::Color;
+
+    #[test]
+    fn custom_clear_color_reaches_first_load_op() {
+        let target = NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0));
+        let custom = Color::srgb(0.2, 0.4, 0.8);
+        let clear_color = RenderTargetClearColor::default()
+            .resolve(
+                &target,
+                0,
+                ClearColorConfig::Custom(custom),
+                &ClearColor(Color::BLACK),
+            )
+            .map(LinearRgba::from);
+
+        assert_eq!(
+            color_load_op(clear_color, true),
+            LoadOp::Clear(LinearRgba::from(custom).into())
+        );
+        // Later passes on the same view, e.g. after an MSAA writeback, load the existing contents.
+        assert_eq!(color_load_op(clear_color, false), LoadOp::Load);
+        assert_eq!(color_load_op(None, true), LoadOp::Load);
+    }
+}
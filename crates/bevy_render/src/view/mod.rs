@@ -7,8 +7,9 @@ pub use window::*;
 
 use crate::{
     camera::{
-        CameraMainTextureUsages, ClearColor, ClearColorConfig, Exposure, ExtractedCamera,
-        ManualTextureViews, MipBias, TemporalJitter,
+        CameraMainTextureUsages, ClearColor, Exposure, ExtractedCamera, ManualTextureViews,
+        MipBias, NormalizedRenderTarget, RenderTargetClearColor, RenderTargetViewFormats,
+        SortedCameras, TemporalJitter,
     },
     extract_resource::{ExtractResource, ExtractResourcePlugin},
     prelude::Shader,
@@ -16,7 +17,7 @@ use crate::{
     render_asset::RenderAssets,
     render_phase::ViewRangefinder3d,
     render_resource::{DynamicUniformBuffer, ShaderType, Texture, TextureView},
-    renderer::{RenderDevice, RenderQueue},
+    renderer::{RenderCapabilities, RenderDevice, RenderQueue},
     texture::{
         BevyDefault, CachedTexture, ColorAttachment, DepthAttachment, GpuImage,
         OutputColorAttachment, TextureCache,
@@ -25,11 +26,12 @@ use crate::{
 };
 use bevy_app::{App, Plugin};
 use bevy_color::LinearRgba;
-use bevy_ecs::prelude::*;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{entity::EntityHashMap, prelude::*};
 use bevy_math::{mat3, vec2, vec3, Mat3, Mat4, UVec4, Vec2, Vec3, Vec4, Vec4Swizzles};
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use bevy_transform::components::GlobalTransform;
-use bevy_utils::HashMap;
+use bevy_utils::{warn_once, HashMap};
 use std::{
     ops::Range,
     sync::{
@@ -104,6 +106,7 @@ impl Plugin for ViewPlugin {
             .register_type::<Msaa>()
             .register_type::<NoFrustumCulling>()
             .register_type::<RenderLayers>()
+            .register_type::<RenderTargetLayerMask>()
             .register_type::<Visibility>()
             .register_type::<VisibleEntities>()
             .register_type::<ColorGrading>()
@@ -116,17 +119,26 @@ impl Plugin for ViewPlugin {
             ));
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
-            render_app.add_systems(
-                Render,
-                (
-                    prepare_view_targets
-                        .in_set(RenderSet::ManageViews)
-                        .after(prepare_windows)
-                        .after(crate::render_asset::prepare_assets::<GpuImage>)
-                        .ambiguous_with(crate::camera::sort_cameras), // doesn't use `sorted_camera_index_for_target`
-                    prepare_view_uniforms.in_set(RenderSet::PrepareResources),
-                ),
-            );
+            render_app
+                .init_resource::<DepthTextures>()
+                .init_resource::<RenderViews>()
+                .add_systems(
+                    Render,
+                    (
+                        clamp_msaa
+                            .in_set(RenderSet::ManageViews)
+                            .before(prepare_windows),
+                        collect_render_views
+                            .in_set(RenderSet::ManageViews)
+                            .after(crate::camera::sort_cameras),
+                        prepare_view_targets
+                            .in_set(RenderSet::ManageViews)
+                            .after(prepare_windows)
+                            .after(crate::render_asset::prepare_assets::<GpuImage>)
+                            .after(crate::camera::sort_cameras),
+                        prepare_view_uniforms.in_set(RenderSet::PrepareResources),
+                    ),
+                );
         }
     }
 
@@ -143,7 +155,9 @@ impl Plugin for ViewPlugin {
 /// smoother edges.
 /// Defaults to 4 samples.
 ///
-/// Note that web currently only supports 1 or 4 samples.
+/// Note that web currently only supports 1 or 4 samples. Sample counts the device doesn't
+/// support for the main textures of views are lowered to the highest supported one in the render
+/// world, see [`RenderCapabilities::clamp_msaa`].
 ///
 /// # Example
 /// ```
@@ -186,6 +200,71 @@ pub struct ExtractedView {
     pub color_grading: ColorGrading,
 }
 
+/// A camera view rendered this frame, see [`RenderViews`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderView {
+    /// The render world entity of the view, holding its [`ExtractedView`] and [`ExtractedCamera`].
+    pub entity: Entity,
+    /// The render target of the camera.
+    pub target: Option<NormalizedRenderTarget>,
+    /// Whether the view is rendered in HDR.
+    pub hdr: bool,
+    /// The viewport of the view in physical pixels, as `(origin.x, origin.y, width, height)`.
+    pub viewport: UVec4,
+}
+
+/// A render world resource listing the views of all active cameras rendered this frame, in the
+/// order the cameras are rendered in.
+///
+/// This is updated in [`RenderSet::ManageViews`], after [`sort_cameras`](crate::camera::sort_cameras),
+/// and is meant for effects spanning multiple views that would otherwise each query
+/// [`ExtractedView`]s with their own filters. Views that aren't cameras, like shadow views, aren't
+/// included.
+#[derive(Resource, Default, Debug)]
+pub struct RenderViews(Vec<RenderView>);
+
+impl RenderViews {
+    /// Returns an iterator over the views, in the order the cameras are rendered in.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &RenderView> {
+        self.0.iter()
+    }
+
+    /// Returns the view of the given render world entity, if it's a camera rendered this frame.
+    pub fn get(&self, entity: Entity) -> Option<&RenderView> {
+        self.0.iter().find(|view| view.entity == entity)
+    }
+
+    /// Returns the number of views.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no view is rendered this frame.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Fills [`RenderViews`] with the views of the sorted cameras that have an [`ExtractedView`].
+pub fn collect_render_views(
+    mut render_views: ResMut<RenderViews>,
+    sorted_cameras: Res<SortedCameras>,
+    views: Query<&ExtractedView>,
+) {
+    render_views.0.clear();
+    for camera in &sorted_cameras.0 {
+        let Ok(view) = views.get(camera.entity) else {
+            continue;
+        };
+        render_views.0.push(RenderView {
+            entity: camera.entity,
+            target: camera.target.clone(),
+            hdr: camera.hdr,
+            viewport: view.viewport,
+        });
+    }
+}
+
 impl ExtractedView {
     /// Creates a 3D rangefinder for a view
     pub fn rangefinder3d(&self) -> ViewRangefinder3d {
@@ -549,6 +628,15 @@ pub struct NoCpuCulling;
 impl ViewTarget {
     pub const TEXTURE_FORMAT_HDR: TextureFormat = TextureFormat::Rgba16Float;
 
+    /// The format of the main texture of a view, depending on whether it's rendered in HDR.
+    pub fn main_texture_format_for(hdr: bool) -> TextureFormat {
+        if hdr {
+            Self::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        }
+    }
+
     /// Retrieve this target's main texture's color attachment.
     pub fn get_color_attachment(&self) -> RenderPassColorAttachment {
         if self.main_texture.load(Ordering::SeqCst) == 0 {
@@ -668,25 +756,43 @@ impl ViewTarget {
     /// [`ViewTarget`]'s main texture to the `destination` texture, so the caller
     /// _must_ ensure `source` is copied to `destination`, with or without modifications.
     /// Failing to do so will cause the current main texture information to be lost.
+    ///
+    /// Each call swaps the roles of the two main textures, so several passes can be chained by
+    /// calling this once per pass. This works the same for 2D and 3D cameras, e.g. custom
+    /// fullscreen passes can be inserted between the end of the main pass and tonemapping.
+    /// Both textures use [`Self::main_texture_format`], so they are HDR when the view is.
     pub fn post_process_write(&self) -> PostProcessWrite {
-        let old_is_a_main_texture = self.main_texture.fetch_xor(1, Ordering::SeqCst);
-        // if the old main texture is a, then the post processing must write from a to b
-        if old_is_a_main_texture == 0 {
-            self.main_textures.b.mark_as_cleared();
-            PostProcessWrite {
-                source: &self.main_textures.a.texture.default_view,
-                destination: &self.main_textures.b.texture.default_view,
-            }
-        } else {
-            self.main_textures.a.mark_as_cleared();
-            PostProcessWrite {
-                source: &self.main_textures.b.texture.default_view,
-                destination: &self.main_textures.a.texture.default_view,
-            }
+        let (source, destination) = ping_pong(
+            &self.main_texture,
+            &self.main_textures.a,
+            &self.main_textures.b,
+        );
+        destination.mark_as_cleared();
+        PostProcessWrite {
+            source: &source.texture.default_view,
+            destination: &destination.texture.default_view,
         }
     }
 }
 
+/// Flips `main_texture` between `a` (0) and `b` (1), returning `(source, destination)`:
+/// the previous main texture and the new one.
+fn ping_pong<'a, T>(main_texture: &AtomicUsize, a: &'a T, b: &'a T) -> (&'a T, &'a T) {
+    // if the old main texture is a, then the post processing must write from a to b
+    if main_texture.fetch_xor(1, Ordering::SeqCst) == 0 {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// The depth textures of all views prepared this frame, keyed by view entity.
+///
+/// This is filled alongside each view's [`ViewDepthTexture`], so that custom render nodes that need
+/// a view's depth texture can share it instead of allocating their own.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DepthTextures(pub EntityHashMap<CachedTexture>);
+
 #[derive(Component)]
 pub struct ViewDepthTexture {
     pub texture: Texture,
@@ -710,6 +816,23 @@ impl ViewDepthTexture {
     }
 }
 
+/// Lowers the extracted [`Msaa`] to a sample count supported by the device, as creating the main
+/// textures and pipelines of views would fail otherwise.
+pub fn clamp_msaa(mut msaa: ResMut<Msaa>, render_capabilities: Option<Res<RenderCapabilities>>) {
+    let Some(render_capabilities) = render_capabilities else {
+        return;
+    };
+    let clamped = render_capabilities.clamp_msaa(*msaa);
+    if clamped != *msaa {
+        warn_once!(
+            "MSAA {}x is not supported by this device, using {}x instead.",
+            msaa.samples(),
+            clamped.samples()
+        );
+        *msaa = clamped;
+    }
+}
+
 pub fn prepare_view_uniforms(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
@@ -799,6 +922,7 @@ pub fn prepare_view_targets(
     images: Res<RenderAssets<GpuImage>>,
     msaa: Res<Msaa>,
     clear_color_global: Res<ClearColor>,
+    render_target_clear_color: Res<RenderTargetClearColor>,
     render_device: Res<RenderDevice>,
     mut texture_cache: ResMut<TextureCache>,
     cameras: Query<(
@@ -806,25 +930,68 @@ pub fn prepare_view_targets(
         &ExtractedCamera,
         &ExtractedView,
         &CameraMainTextureUsages,
+        Option<&RenderTargetViewFormats>,
     )>,
     manual_texture_views: Res<ManualTextureViews>,
 ) {
     let mut textures = HashMap::default();
     let mut output_textures = HashMap::default();
-    for (entity, camera, view, texture_usage) in cameras.iter() {
+    for (entity, camera, view, texture_usage, view_formats) in cameras.iter() {
         let (Some(target_size), Some(target)) = (camera.physical_target_size, &camera.target)
         else {
             continue;
         };
 
-        let Some(out_texture) = output_textures.entry(target.clone()).or_insert_with(|| {
-            target
-                .get_texture_view(&windows, &images, &manual_texture_views)
-                .zip(target.get_texture_format(&windows, &images, &manual_texture_views))
-                .map(|(view, format)| {
-                    OutputColorAttachment::new(view.clone(), format.add_srgb_suffix())
-                })
-        }) else {
+        let view_format = view_formats.and_then(|view_formats| {
+            if let NormalizedRenderTarget::Image(image_handle)
+            | NormalizedRenderTarget::ImageMipLevel(image_handle, _) = target
+            {
+                if let Some(Err(error)) = images
+                    .get(image_handle)
+                    .map(|image| view_formats.validate_gpu_image(image))
+                {
+                    warn_once!("Invalid `RenderTargetViewFormats` on a camera: {error}");
+                }
+            }
+            let view_format = target.get_texture_view_format(&images, view_formats);
+            if view_format.is_none() {
+                warn_once!(
+                    "None of the camera's `RenderTargetViewFormats` {:?} are supported by its render target; falling back to the target's format.",
+                    view_formats.0
+                );
+            }
+            view_format
+        });
+
+        let Some(out_texture) = output_textures
+            .entry((target.clone(), view_format))
+            .or_insert_with(|| match (target, view_format) {
+                (NormalizedRenderTarget::Image(image_handle), Some(format)) => images
+                    .get(image_handle)
+                    .and_then(|image| image.format_view(format))
+                    .map(|view| OutputColorAttachment::new(view.clone(), format)),
+                (NormalizedRenderTarget::ImageMipLevel(image_handle, mip_level), Some(format)) => {
+                    images
+                        .get(image_handle)
+                        .and_then(|image| image.format_mip_level_view(format, *mip_level))
+                        .map(|view| OutputColorAttachment::new(view.clone(), format))
+                }
+                (NormalizedRenderTarget::TextureView(id), _) => {
+                    manual_texture_views.get(id).map(|manual_texture_view| {
+                        OutputColorAttachment::new(
+                            manual_texture_view.texture_view.clone(),
+                            manual_texture_view.output_format(),
+                        )
+                    })
+                }
+                _ => target
+                    .get_texture_view(&windows, &images, &manual_texture_views)
+                    .zip(target.get_texture_format(&windows, &images, &manual_texture_views))
+                    .map(|(view, format)| {
+                        OutputColorAttachment::new(view.clone(), format.add_srgb_suffix())
+                    }),
+            })
+        else {
             continue;
         };
 
@@ -834,17 +1001,14 @@ pub fn prepare_view_targets(
             depth_or_array_layers: 1,
         };
 
-        let main_texture_format = if view.hdr {
-            ViewTarget::TEXTURE_FORMAT_HDR
-        } else {
-            TextureFormat::bevy_default()
-        };
+        let main_texture_format = ViewTarget::main_texture_format_for(view.hdr);
 
-        let clear_color = match camera.clear_color {
-            ClearColorConfig::Custom(color) => Some(color),
-            ClearColorConfig::None => None,
-            _ => Some(clear_color_global.0),
-        };
+        let clear_color = render_target_clear_color.resolve(
+            target,
+            camera.sorted_camera_index_for_target,
+            camera.clear_color,
+            &clear_color_global,
+        );
 
         let (a, b, sampled, main_texture) = textures
             .entry((camera.target.clone(), view.hdr))
@@ -915,3 +1079,71 @@ pub fn prepare_view_targets(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_render_views, ping_pong, ColorGrading, ExtractedView, RenderViews};
+    use crate::camera::{NormalizedRenderTarget, SortedCamera, SortedCameras};
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use bevy_math::{Mat4, UVec4};
+    use bevy_transform::components::GlobalTransform;
+    use bevy_window::WindowRef;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn post_process_write_alternates_source_and_destination() {
+        let main_texture = AtomicUsize::new(0);
+        let (a, b) = ("a", "b");
+
+        assert_eq!(ping_pong(&main_texture, &a, &b), (&a, &b));
+        assert_eq!(ping_pong(&main_texture, &a, &b), (&b, &a));
+        assert_eq!(main_texture.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn render_views_list_each_camera_once() {
+        let mut world = World::new();
+        let viewports = [UVec4::new(0, 0, 640, 360), UVec4::new(640, 0, 640, 360)];
+        let views = viewports.map(|viewport| {
+            world
+                .spawn(ExtractedView {
+                    clip_from_view: Mat4::IDENTITY,
+                    world_from_view: GlobalTransform::IDENTITY,
+                    clip_from_world: None,
+                    hdr: false,
+                    viewport,
+                    color_grading: ColorGrading::default(),
+                })
+                .id()
+        });
+        let target = WindowRef::Entity(world.spawn_empty().id())
+            .normalize(None)
+            .map(NormalizedRenderTarget::Window);
+        world.insert_resource(SortedCameras(
+            views
+                .iter()
+                .map(|&entity| SortedCamera {
+                    entity,
+                    order: 0,
+                    target: target.clone(),
+                    hdr: false,
+                    allow_order_ambiguity: false,
+                })
+                .collect(),
+        ));
+        world.init_resource::<RenderViews>();
+
+        // Collecting again next frame replaces the previous views instead of appending to them.
+        world.run_system_once(collect_render_views);
+        world.run_system_once(collect_render_views);
+
+        let render_views = world.resource::<RenderViews>();
+        assert_eq!(render_views.len(), 2);
+        for (view, (entity, viewport)) in render_views.iter().zip(views.into_iter().zip(viewports))
+        {
+            assert_eq!(view.entity, entity);
+            assert_eq!(view.viewport, viewport);
+            assert_eq!(view.target, target);
+        }
+    }
+}
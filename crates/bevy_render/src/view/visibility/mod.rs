@@ -1,7 +1,7 @@
 mod range;
 mod render_layers;
 
-use std::any::TypeId;
+use std::{any::TypeId, borrow::Cow};
 
 pub use range::*;
 pub use render_layers::*;
@@ -228,6 +228,25 @@ impl VisibleEntities {
     {
         self.get_mut::<QF>().push(entity);
     }
+
+    /// Returns the number of visible entities across all query filter types.
+    ///
+    /// An entity matching several query filters is counted once per filter.
+    pub fn total_len(&self) -> usize {
+        self.entities.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no entities are visible for any query filter type.
+    pub fn is_empty_all(&self) -> bool {
+        self.entities.values().all(Vec::is_empty)
+    }
+
+    /// Iterates over the number of visible entities for each query filter type.
+    pub fn counts(&self) -> impl Iterator<Item = (TypeId, usize)> + '_ {
+        self.entities
+            .iter()
+            .map(|(type_id, entities)| (*type_id, entities.len()))
+    }
 }
 
 /// A convenient alias for `With<Handle<Mesh>>`, for use with
@@ -400,6 +419,7 @@ pub fn check_visibility<QF>(
         &mut VisibleEntities,
         &Frustum,
         Option<&RenderLayers>,
+        Option<&RenderTargetLayerMask>,
         &Camera,
         Has<NoCpuCulling>,
     )>,
@@ -422,14 +442,27 @@ pub fn check_visibility<QF>(
 {
     let visible_entity_ranges = visible_entity_ranges.as_deref();
 
-    for (view, mut visible_entities, frustum, maybe_view_mask, camera, no_cpu_culling) in
-        &mut view_query
+    for (
+        view,
+        mut visible_entities,
+        frustum,
+        maybe_view_mask,
+        maybe_target_mask,
+        camera,
+        no_cpu_culling,
+    ) in &mut view_query
     {
         if !camera.is_active {
             continue;
         }
 
         let view_mask = maybe_view_mask.unwrap_or_default();
+        // An entity shares a layer with both the view and the target mask exactly when it shares
+        // a layer with their intersection, so the target mask can be applied once per view.
+        let view_mask = match maybe_target_mask {
+            Some(target_mask) => Cow::Owned(view_mask.intersection(&target_mask.0)),
+            None => Cow::Borrowed(view_mask),
+        };
 
         visible_aabb_query.par_iter_mut().for_each_init(
             || thread_queues.borrow_local_mut(),
@@ -761,6 +794,100 @@ mod test {
         assert!(child_visible);
     }
 
+    #[test]
+    fn render_target_layer_mask() {
+        // `check_visibility` iterates entities in parallel.
+        bevy_tasks::ComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default);
+
+        let mut world = World::new();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(check_visibility::<()>);
+
+        let camera = world
+            .spawn((
+                Camera::default(),
+                Frustum::default(),
+                VisibleEntities::default(),
+                RenderLayers::from_layers(&[0, 1, 2]),
+                // Layer 2 is a debug layer that shouldn't reach this target.
+                RenderTargetLayerMask(RenderLayers::from_layers(&[0, 1, 3])),
+            ))
+            .id();
+
+        let mut spawn = |layers: Option<RenderLayers>| {
+            let mut entity = world.spawn((
+                InheritedVisibility::VISIBLE,
+                ViewVisibility::default(),
+                GlobalTransform::default(),
+            ));
+            if let Some(layers) = layers {
+                entity.insert(layers);
+            }
+            entity.id()
+        };
+        let default_layer = spawn(None);
+        let layer_1 = spawn(Some(RenderLayers::layer(1)));
+        let debug_layer = spawn(Some(RenderLayers::layer(2)));
+        let debug_and_layer_1 = spawn(Some(RenderLayers::from_layers(&[1, 2])));
+        // In the mask, but not seen by the camera.
+        let layer_3 = spawn(Some(RenderLayers::layer(3)));
+
+        schedule.run(&mut world);
+
+        let mut visible = world
+            .get::<VisibleEntities>(camera)
+            .unwrap()
+            .get::<()>()
+            .to_vec();
+        visible.sort();
+        let mut expected = vec![default_layer, layer_1, debug_and_layer_1];
+        expected.sort();
+        assert_eq!(visible, expected);
+        for entity in [debug_layer, layer_3] {
+            assert!(!world.get::<ViewVisibility>(entity).unwrap().get());
+        }
+
+        // Without the mask, the debug layer is visible again.
+        world.entity_mut(camera).remove::<RenderTargetLayerMask>();
+        schedule.run(&mut world);
+        assert!(world
+            .get::<VisibleEntities>(camera)
+            .unwrap()
+            .get::<()>()
+            .contains(&debug_layer));
+    }
+
+    #[test]
+    fn visible_entities_counts() {
+        struct A;
+        struct B;
+
+        let mut visible_entities = VisibleEntities::default();
+        assert!(visible_entities.is_empty_all());
+        assert_eq!(visible_entities.total_len(), 0);
+
+        visible_entities.push::<A>(Entity::from_raw(0));
+        visible_entities.push::<A>(Entity::from_raw(1));
+        visible_entities.push::<B>(Entity::from_raw(1));
+        // Touching a type without pushing leaves an empty entry behind.
+        visible_entities.clear::<WithMesh>();
+
+        assert!(!visible_entities.is_empty_all());
+        assert_eq!(visible_entities.total_len(), 3);
+        assert_eq!(visible_entities.len::<A>(), 2);
+        assert_eq!(visible_entities.len::<B>(), 1);
+        assert!(visible_entities.is_empty::<WithMesh>());
+
+        let counts: TypeIdMap<usize> = visible_entities.counts().collect();
+        assert_eq!(counts.get(&TypeId::of::<A>()), Some(&2));
+        assert_eq!(counts.get(&TypeId::of::<B>()), Some(&1));
+        assert_eq!(counts.get(&TypeId::of::<WithMesh>()), Some(&0));
+
+        visible_entities.clear::<A>();
+        visible_entities.clear::<B>();
+        assert!(visible_entities.is_empty_all());
+    }
+
     #[test]
     fn ensure_visibility_enum_size() {
         use std::mem;
@@ -122,6 +122,21 @@ impl RenderLayers {
         false
     }
 
+    /// Returns the layers contained in both `self` and `other`.
+    #[must_use]
+    pub fn intersection(&self, other: &RenderLayers) -> Self {
+        let mut layers: SmallVec<[u64; 1]> = self
+            .0
+            .iter()
+            .zip(other.0.iter())
+            .map(|(self_layer, other_layer)| *self_layer & *other_layer)
+            .collect();
+        if layers.is_empty() {
+            layers.push(0);
+        }
+        RenderLayers(layers)
+    }
+
     /// get the bitmask representation of the contained layers
     pub fn bits(&self) -> &[u64] {
         self.0.as_slice()
@@ -156,6 +171,16 @@ impl RenderLayers {
     }
 }
 
+/// An additional mask of [`RenderLayers`] for a camera, restricting which layers can reach its
+/// render target regardless of the camera's own [`RenderLayers`].
+///
+/// An entity is only visible from the camera if the layers it shares with the camera are also in
+/// this mask. This is useful to hide debug-only layers from some targets, e.g. to render clean
+/// frames for screenshots, without changing the layers of the camera or of the entities.
+#[derive(Component, Clone, Debug, Default, Reflect, PartialEq, Eq)]
+#[reflect(Component, Default, PartialEq)]
+pub struct RenderTargetLayerMask(pub RenderLayers);
+
 #[cfg(test)]
 mod rendering_mask_tests {
     use super::{Layer, RenderLayers};
@@ -237,6 +262,21 @@ mod rendering_mask_tests {
             "from_layers and from_iter are equivalent"
         );
 
+        assert_eq!(
+            RenderLayers::from_layers(&[0, 1, 70])
+                .intersection(&RenderLayers::from_layers(&[1, 2, 70, 200]))
+                .iter()
+                .collect::<Vec<_>>(),
+            vec![1, 70],
+            "intersection keeps shared layers"
+        );
+        assert!(
+            !RenderLayers::layer(0)
+                .intersection(&RenderLayers::layer(1))
+                .intersects(&RenderLayers::layer(0)),
+            "intersection of disjoint masks is empty"
+        );
+
         let tricky_layers = vec![0, 5, 17, 55, 999, 1025, 1026];
         let layers = RenderLayers::from_layers(&tricky_layers);
         let out = layers.iter().collect::<Vec<_>>();
@@ -1,4 +1,5 @@
 use crate::{
+    render_enabled,
     render_resource::{
         BindGroupEntries, PipelineCache, SpecializedRenderPipelines, SurfaceTexture, TextureView,
     },
@@ -8,9 +9,8 @@ use crate::{
 };
 use bevy_app::{App, Plugin};
 use bevy_ecs::{entity::EntityHashMap, prelude::*};
-#[cfg(target_os = "linux")]
-use bevy_utils::warn_once;
-use bevy_utils::{default, tracing::debug, HashSet};
+use bevy_reflect::Reflect;
+use bevy_utils::{default, tracing::debug, warn_once, HashSet};
 use bevy_window::{
     CompositeAlphaMode, PresentMode, PrimaryWindow, RawHandleWrapper, Window, WindowClosing,
 };
@@ -36,7 +36,8 @@ pub struct WindowRenderPlugin;
 
 impl Plugin for WindowRenderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(ScreenshotPlugin);
+        app.register_type::<PresentModeOverride>()
+            .add_plugins(ScreenshotPlugin);
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
@@ -49,7 +50,12 @@ impl Plugin for WindowRenderPlugin {
                         .run_if(need_surface_configuration)
                         .before(prepare_windows),
                 )
-                .add_systems(Render, prepare_windows.in_set(RenderSet::ManageViews));
+                .add_systems(
+                    Render,
+                    prepare_windows
+                        .run_if(render_enabled)
+                        .in_set(RenderSet::ManageViews),
+                );
         }
     }
 
@@ -60,6 +66,15 @@ impl Plugin for WindowRenderPlugin {
     }
 }
 
+/// Overrides the [`PresentMode`] of the [`Window`] on the same entity when configuring its surface.
+///
+/// Like [`Window::present_mode`], the requested mode is validated against the present modes
+/// supported by the surface, and unsupported modes fall back to [`PresentMode::Fifo`] with a
+/// warning. Changing or removing this component reconfigures the surface on the next frame.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct PresentModeOverride(pub PresentMode);
+
 pub struct ExtractedWindow {
     /// An entity that contains the components in [`Window`].
     pub entity: Entity,
@@ -118,15 +133,25 @@ fn extract_windows(
     mut extracted_windows: ResMut<ExtractedWindows>,
     screenshot_manager: Extract<Res<ScreenshotManager>>,
     mut closing: Extract<EventReader<WindowClosing>>,
-    windows: Extract<Query<(Entity, &Window, &RawHandleWrapper, Option<&PrimaryWindow>)>>,
+    windows: Extract<
+        Query<(
+            Entity,
+            &Window,
+            &RawHandleWrapper,
+            Option<&PrimaryWindow>,
+            Option<&PresentModeOverride>,
+        )>,
+    >,
     mut removed: Extract<RemovedComponents<RawHandleWrapper>>,
     mut window_surfaces: ResMut<WindowSurfaces>,
 ) {
-    for (entity, window, handle, primary) in windows.iter() {
+    for (entity, window, handle, primary, present_mode_override) in windows.iter() {
         if primary.is_some() {
             extracted_windows.primary = Some(entity);
         }
 
+        let present_mode = present_mode_override.map_or(window.present_mode, |o| o.0);
+
         let (new_width, new_height) = (
             window.resolution.physical_width().max(1),
             window.resolution.physical_height().max(1),
@@ -137,7 +162,7 @@ fn extract_windows(
             handle: handle.clone(),
             physical_width: new_width,
             physical_height: new_height,
-            present_mode: window.present_mode,
+            present_mode,
             desired_maximum_frame_latency: window.desired_maximum_frame_latency,
             swap_chain_texture: None,
             swap_chain_texture_view: None,
@@ -153,8 +178,7 @@ fn extract_windows(
         extracted_window.swap_chain_texture_view = None;
         extracted_window.size_changed = new_width != extracted_window.physical_width
             || new_height != extracted_window.physical_height;
-        extracted_window.present_mode_changed =
-            window.present_mode != extracted_window.present_mode;
+        extracted_window.present_mode_changed = present_mode != extracted_window.present_mode;
 
         if extracted_window.size_changed {
             debug!(
@@ -171,9 +195,9 @@ fn extract_windows(
         if extracted_window.present_mode_changed {
             debug!(
                 "Window Present Mode changed from {:?} to {:?}",
-                extracted_window.present_mode, window.present_mode
+                extracted_window.present_mode, present_mode
             );
-            extracted_window.present_mode = window.present_mode;
+            extracted_window.present_mode = present_mode;
         }
     }
 
@@ -205,6 +229,8 @@ struct SurfaceData {
     // TODO: what lifetime should this be?
     surface: WgpuWrapper<wgpu::Surface<'static>>,
     configuration: SurfaceConfiguration,
+    /// The present modes supported by the surface on the current adapter.
+    present_modes: Vec<wgpu::PresentMode>,
 }
 
 #[derive(Resource, Default)]
@@ -489,14 +515,7 @@ pub fn create_surfaces(
                     width: window.physical_width,
                     height: window.physical_height,
                     usage: TextureUsages::RENDER_ATTACHMENT,
-                    present_mode: match window.present_mode {
-                        PresentMode::Fifo => wgpu::PresentMode::Fifo,
-                        PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
-                        PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
-                        PresentMode::Immediate => wgpu::PresentMode::Immediate,
-                        PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
-                        PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
-                    },
+                    present_mode: select_present_mode(window.present_mode, &caps.present_modes),
                     desired_maximum_frame_latency: window
                         .desired_maximum_frame_latency
                         .map(NonZeroU32::get)
@@ -524,21 +543,70 @@ pub fn create_surfaces(
                 SurfaceData {
                     surface: WgpuWrapper::new(surface),
                     configuration,
+                    present_modes: caps.present_modes,
                 }
             });
 
         if window.size_changed || window.present_mode_changed {
             data.configuration.width = window.physical_width;
             data.configuration.height = window.physical_height;
-            data.configuration.present_mode = match window.present_mode {
-                PresentMode::Fifo => wgpu::PresentMode::Fifo,
-                PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
-                PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
-                PresentMode::Immediate => wgpu::PresentMode::Immediate,
-                PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
-                PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
-            };
+            data.configuration.present_mode =
+                select_present_mode(window.present_mode, &data.present_modes);
             render_device.configure_surface(&data.surface, &data.configuration);
         }
     }
 }
+
+/// Converts the requested [`PresentMode`] to a [`wgpu::PresentMode`], falling back to
+/// [`wgpu::PresentMode::Fifo`] if the mode isn't in `supported_modes`.
+///
+/// The `Auto*` modes are always accepted, since `wgpu` resolves them to a supported mode itself.
+fn select_present_mode(
+    present_mode: PresentMode,
+    supported_modes: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    let present_mode = match present_mode {
+        PresentMode::Fifo => wgpu::PresentMode::Fifo,
+        PresentMode::FifoRelaxed => wgpu::PresentMode::FifoRelaxed,
+        PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        PresentMode::Immediate => wgpu::PresentMode::Immediate,
+        PresentMode::AutoVsync => return wgpu::PresentMode::AutoVsync,
+        PresentMode::AutoNoVsync => return wgpu::PresentMode::AutoNoVsync,
+    };
+    if present_mode == wgpu::PresentMode::Fifo || supported_modes.contains(&present_mode) {
+        present_mode
+    } else {
+        warn_once!(
+            "Present mode {:?} is not supported by the window surface (supported: {:?}). Falling back to Fifo.",
+            present_mode, supported_modes
+        );
+        wgpu::PresentMode::Fifo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn present_mode_override_is_validated() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox];
+
+        let window_override = PresentModeOverride(PresentMode::Mailbox);
+        assert_eq!(
+            select_present_mode(window_override.0, &supported),
+            wgpu::PresentMode::Mailbox
+        );
+
+        let window_override = PresentModeOverride(PresentMode::Immediate);
+        assert_eq!(
+            select_present_mode(window_override.0, &supported),
+            wgpu::PresentMode::Fifo
+        );
+
+        assert_eq!(
+            select_present_mode(PresentMode::AutoNoVsync, &supported),
+            wgpu::PresentMode::AutoNoVsync
+        );
+    }
+}
@@ -1,27 +1,33 @@
 use std::{borrow::Cow, path::Path, sync::PoisonError};
 
 use bevy_app::Plugin;
-use bevy_asset::{load_internal_asset, Handle};
+use bevy_asset::{load_internal_asset, AssetId, Handle};
 use bevy_ecs::{entity::EntityHashMap, prelude::*};
+use bevy_math::UVec2;
 use bevy_tasks::AsyncComputeTaskPool;
-use bevy_utils::tracing::{error, info, info_span};
+use bevy_utils::{
+    tracing::{error, info, info_span, warn},
+    HashMap,
+};
 use std::sync::Mutex;
 use thiserror::Error;
 use wgpu::{
-    CommandEncoder, Extent3d, ImageDataLayout, TextureFormat, COPY_BYTES_PER_ROW_ALIGNMENT,
+    BufferDescriptor, BufferUsages, CommandEncoder, Extent3d, ImageDataLayout, TextureFormat,
+    TextureUsages, COPY_BYTES_PER_ROW_ALIGNMENT,
 };
 
 use crate::{
+    camera::NormalizedRenderTarget,
     prelude::{Image, Shader},
-    render_asset::RenderAssetUsages,
+    render_asset::{RenderAssetUsages, RenderAssets},
     render_resource::{
         binding_types::texture_2d, BindGroup, BindGroupLayout, BindGroupLayoutEntries, Buffer,
         CachedRenderPipelineId, FragmentState, PipelineCache, RenderPipelineDescriptor,
         SpecializedRenderPipeline, SpecializedRenderPipelines, Texture, VertexState,
     },
     renderer::RenderDevice,
-    texture::TextureFormatPixelInfo,
-    RenderApp,
+    texture::{GpuImage, TextureFormatPixelInfo},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
 };
 
 use super::ExtractedWindows;
@@ -33,12 +39,24 @@ pub type ScreenshotFn = Box<dyn FnOnce(Image) + Send + Sync>;
 pub struct ScreenshotManager {
     // this is in a mutex to enable extraction with only an immutable reference
     pub(crate) callbacks: Mutex<EntityHashMap<ScreenshotFn>>,
+    pub(crate) image_callbacks: Mutex<HashMap<AssetId<Image>, ScreenshotFn>>,
 }
 
 #[derive(Error, Debug)]
-#[error("A screenshot for this window has already been requested.")]
+#[error("A screenshot for this render target has already been requested.")]
 pub struct ScreenshotAlreadyRequestedError;
 
+/// An error returned by [`ScreenshotManager::take_render_target_screenshot`].
+#[derive(Error, Debug)]
+pub enum RenderTargetScreenshotError {
+    #[error(transparent)]
+    AlreadyRequested(#[from] ScreenshotAlreadyRequestedError),
+    #[error(
+        "Screenshots of {0:?} are not supported, only windows and whole images can be captured."
+    )]
+    UnsupportedTarget(NormalizedRenderTarget),
+}
+
 impl ScreenshotManager {
     /// Signals the renderer to take a screenshot of this frame.
     ///
@@ -56,6 +74,60 @@ impl ScreenshotManager {
             .map_err(|_| ScreenshotAlreadyRequestedError)
     }
 
+    /// Signals the renderer to read back the contents of an [`Image`] render target at the end of this frame.
+    ///
+    /// This is useful for deterministic, window-independent captures, e.g. in regression tests:
+    /// point a camera at an image created with [`screenshot_target_image`] and request a readback of it.
+    /// Since the final color of a view is always written to its [`Image`] target after any MSAA
+    /// resolve, the readback does not depend on the [`Msaa`](crate::view::Msaa) setting.
+    ///
+    /// The image must have been created with [`TextureUsages::COPY_SRC`], otherwise the request is
+    /// dropped with a warning. The given callback will eventually be called on one of the
+    /// [`AsyncComputeTaskPool`]s threads with the tightly packed pixel data.
+    pub fn take_image_screenshot(
+        &mut self,
+        image: impl Into<AssetId<Image>>,
+        callback: impl FnOnce(Image) + Send + Sync + 'static,
+    ) -> Result<(), ScreenshotAlreadyRequestedError> {
+        self.image_callbacks
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .try_insert(image.into(), Box::new(callback))
+            .map(|_| ())
+            .map_err(|_| ScreenshotAlreadyRequestedError)
+    }
+
+    /// Signals the renderer to take a screenshot of a camera's render target at the end of this frame.
+    ///
+    /// Use [`RenderTarget::normalize`](crate::camera::RenderTarget::normalize) to get the target of a camera.
+    /// Window targets are captured with [`take_screenshot`](Self::take_screenshot) and image targets with
+    /// [`take_image_screenshot`](Self::take_image_screenshot).
+    ///
+    /// Unlike those methods, captured [`TextureFormat::Bgra8UnormSrgb`] images, as commonly used by swap
+    /// chains, are converted to [`TextureFormat::Rgba8UnormSrgb`]. Other formats are passed through
+    /// unchanged.
+    pub fn take_render_target_screenshot(
+        &mut self,
+        target: &NormalizedRenderTarget,
+        callback: impl FnOnce(Image) + Send + Sync + 'static,
+    ) -> Result<(), RenderTargetScreenshotError> {
+        let callback = move |image: Image| callback(convert_to_rgba8(image));
+        match target {
+            NormalizedRenderTarget::Window(window) => {
+                self.take_screenshot(window.entity(), callback)?;
+            }
+            NormalizedRenderTarget::Image(image) => {
+                self.take_image_screenshot(image, callback)?;
+            }
+            target => {
+                return Err(RenderTargetScreenshotError::UnsupportedTarget(
+                    target.clone(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
     /// Signals the renderer to take a screenshot of this frame.
     ///
     /// The screenshot will eventually be saved to the given path, and the format will be derived from the extension.
@@ -122,6 +194,41 @@ impl ScreenshotManager {
     }
 }
 
+/// Converts a captured [`TextureFormat::Bgra8UnormSrgb`] `image` to
+/// [`TextureFormat::Rgba8UnormSrgb`].
+///
+/// Only the channel order changes, so other formats are returned unchanged: converting linear
+/// formats would relabel their data as sRGB without converting it.
+fn convert_to_rgba8(image: Image) -> Image {
+    if image.texture_descriptor.format != TextureFormat::Bgra8UnormSrgb {
+        return image;
+    }
+    image
+        .convert(TextureFormat::Rgba8UnormSrgb)
+        .unwrap_or(image)
+}
+
+/// Creates an [`Image`] suitable as a camera [`RenderTarget`](crate::camera::RenderTarget)
+/// that can be read back with [`ScreenshotManager::take_image_screenshot`].
+pub fn screenshot_target_image(width: u32, height: u32, format: TextureFormat) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        wgpu::TextureDimension::D2,
+        &vec![0; format.pixel_size()],
+        format,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_DST
+        | TextureUsages::COPY_SRC
+        | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
 pub struct ScreenshotPlugin;
 
 const SCREENSHOT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11918575842344596158);
@@ -140,7 +247,14 @@ impl Plugin for ScreenshotPlugin {
 
     fn finish(&self, app: &mut bevy_app::App) {
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
-            render_app.init_resource::<SpecializedRenderPipelines<ScreenshotToScreenPipeline>>();
+            render_app
+                .init_resource::<SpecializedRenderPipelines<ScreenshotToScreenPipeline>>()
+                .init_resource::<ImageScreenshots>()
+                .add_systems(ExtractSchedule, extract_image_screenshots)
+                .add_systems(
+                    Render,
+                    prepare_image_screenshots.in_set(RenderSet::PrepareResources),
+                );
         }
     }
 }
@@ -217,6 +331,9 @@ impl SpecializedRenderPipeline for ScreenshotToScreenPipeline {
                 })],
             }),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -228,6 +345,107 @@ pub struct ScreenshotPreparedState {
     pub pipeline_id: CachedRenderPipelineId,
 }
 
+/// A pending readback of an [`Image`] render target.
+pub struct ImageScreenshot {
+    pub image: AssetId<Image>,
+    pub func: ScreenshotFn,
+    /// The resources of the copy, once the image has been prepared.
+    pub memory: Option<ImageScreenshotMemory>,
+}
+
+/// The buffer an [`ImageScreenshot`] is copied into.
+pub struct ImageScreenshotMemory {
+    pub buffer: Buffer,
+    /// The texture of the image, kept alive until it's copied even if the image is removed.
+    pub texture: Texture,
+    pub size: UVec2,
+    pub format: TextureFormat,
+}
+
+/// The [`Image`] readbacks requested through [`ScreenshotManager::take_image_screenshot`] for this frame.
+#[derive(Resource, Default)]
+pub struct ImageScreenshots(pub Vec<ImageScreenshot>);
+
+fn extract_image_screenshots(
+    mut image_screenshots: ResMut<ImageScreenshots>,
+    screenshot_manager: Extract<Res<ScreenshotManager>>,
+) {
+    // See `extract_windows` for why locking here never blocks.
+    image_screenshots.0.extend(
+        screenshot_manager
+            .image_callbacks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain()
+            .map(|(image, func)| ImageScreenshot {
+                image,
+                func,
+                memory: None,
+            }),
+    );
+}
+
+fn prepare_image_screenshots(
+    mut image_screenshots: ResMut<ImageScreenshots>,
+    images: Res<RenderAssets<GpuImage>>,
+    render_device: Res<RenderDevice>,
+) {
+    image_screenshots.0.retain_mut(|screenshot| {
+        if screenshot.memory.is_some() {
+            return true;
+        }
+        // The image may not have been prepared yet, try again next frame.
+        let Some(gpu_image) = images.get(screenshot.image) else {
+            return true;
+        };
+        if !gpu_image.texture.usage().contains(TextureUsages::COPY_SRC) {
+            warn!(
+                "Cannot take a screenshot of image {:?}, it was not created with TextureUsages::COPY_SRC",
+                screenshot.image
+            );
+            return false;
+        }
+
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("image-screenshot-transfer-buffer"),
+            size: get_aligned_size(
+                gpu_image.size.x,
+                gpu_image.size.y,
+                gpu_image.texture_format.pixel_size() as u32,
+            ) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        screenshot.memory = Some(ImageScreenshotMemory {
+            buffer,
+            texture: gpu_image.texture.clone(),
+            size: gpu_image.size,
+            format: gpu_image.texture_format,
+        });
+        true
+    });
+}
+
+/// Removes the padding added to each row of a buffer copied from a texture, since rows
+/// have to be aligned to [`COPY_BYTES_PER_ROW_ALIGNMENT`].
+pub(crate) fn remove_row_padding(data: &mut Vec<u8>, width: u32, height: u32, pixel_size: usize) {
+    if data.len() == (width * height) as usize * pixel_size {
+        return;
+    }
+
+    let initial_row_bytes = width as usize * pixel_size;
+    let buffered_row_bytes = align_byte_size(width * pixel_size as u32) as usize;
+
+    let mut take_offset = buffered_row_bytes;
+    let mut place_offset = initial_row_bytes;
+    for _ in 1..height {
+        data.copy_within(take_offset..take_offset + initial_row_bytes, place_offset);
+        take_offset += buffered_row_bytes;
+        place_offset += initial_row_bytes;
+    }
+    data.truncate(initial_row_bytes * height as usize);
+}
+
 pub(crate) fn submit_screenshot_commands(world: &World, encoder: &mut CommandEncoder) {
     let windows = world.resource::<ExtractedWindows>();
     let pipelines = world.resource::<PipelineCache>();
@@ -277,6 +495,32 @@ pub(crate) fn submit_screenshot_commands(world: &World, encoder: &mut CommandEnc
             }
         }
     }
+
+    // Copy from the textures kept when the screenshots were prepared, so every buffer mapped in
+    // `collect_screenshots` has been copied into, even if its image was removed since.
+    for screenshot in &world.resource::<ImageScreenshots>().0 {
+        let Some(ImageScreenshotMemory {
+            buffer,
+            texture,
+            size,
+            format,
+        }) = &screenshot.memory
+        else {
+            continue;
+        };
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer,
+                layout: layout_data(size.x, size.y, *format),
+            },
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                ..Default::default()
+            },
+        );
+    }
 }
 
 pub(crate) fn collect_screenshots(world: &mut World) {
@@ -288,60 +532,225 @@ pub(crate) fn collect_screenshots(world: &mut World) {
             let width = window.physical_width;
             let height = window.physical_height;
             let texture_format = window.swap_chain_texture_format.unwrap();
-            let pixel_size = texture_format.pixel_size();
             let ScreenshotPreparedState { buffer, .. } = window.screenshot_memory.take().unwrap();
 
-            let finish = async move {
-                let (tx, rx) = async_channel::bounded(1);
-                let buffer_slice = buffer.slice(..);
-                // The polling for this map call is done every frame when the command queue is submitted.
-                buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
-                    let err = result.err();
-                    if err.is_some() {
-                        panic!("{}", err.unwrap().to_string());
-                    }
-                    tx.try_send(()).unwrap();
-                });
-                rx.recv().await.unwrap();
-                let data = buffer_slice.get_mapped_range();
-                // we immediately move the data to CPU memory to avoid holding the mapped view for long
-                let mut result = Vec::from(&*data);
-                drop(data);
-                drop(buffer);
-
-                if result.len() != ((width * height) as usize * pixel_size) {
-                    // Our buffer has been padded because we needed to align to a multiple of 256.
-                    // We remove this padding here
-                    let initial_row_bytes = width as usize * pixel_size;
-                    let buffered_row_bytes = align_byte_size(width * pixel_size as u32) as usize;
-
-                    let mut take_offset = buffered_row_bytes;
-                    let mut place_offset = initial_row_bytes;
-                    for _ in 1..height {
-                        result.copy_within(
-                            take_offset..take_offset + buffered_row_bytes,
-                            place_offset,
-                        );
-                        take_offset += buffered_row_bytes;
-                        place_offset += initial_row_bytes;
-                    }
-                    result.truncate(initial_row_bytes * height as usize);
-                }
+            read_back_screenshot(
+                buffer,
+                UVec2::new(width, height),
+                texture_format,
+                screenshot_func,
+            );
+        }
+    }
+
+    let mut image_screenshots = world.resource_mut::<ImageScreenshots>();
+    // Screenshots of images that weren't prepared yet stay queued for the next frame.
+    let (ready, pending) = std::mem::take(&mut image_screenshots.0)
+        .into_iter()
+        .partition::<Vec<_>, _>(|screenshot| screenshot.memory.is_some());
+    image_screenshots.0 = pending;
+    for ImageScreenshot { func, memory, .. } in ready {
+        let ImageScreenshotMemory {
+            buffer,
+            size,
+            format,
+            ..
+        } = memory.unwrap();
+        read_back_screenshot(buffer, size, format, func);
+    }
+}
+
+fn read_back_screenshot(
+    buffer: Buffer,
+    size: UVec2,
+    texture_format: TextureFormat,
+    screenshot_func: ScreenshotFn,
+) {
+    let UVec2 {
+        x: width,
+        y: height,
+    } = size;
+    let pixel_size = texture_format.pixel_size();
+
+    let finish = async move {
+        let (tx, rx) = async_channel::bounded(1);
+        let buffer_slice = buffer.slice(..);
+        // The polling for this map call is done every frame when the command queue is submitted.
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            let err = result.err();
+            if err.is_some() {
+                panic!("{}", err.unwrap().to_string());
+            }
+            tx.try_send(()).unwrap();
+        });
+        rx.recv().await.unwrap();
+        let data = buffer_slice.get_mapped_range();
+        // we immediately move the data to CPU memory to avoid holding the mapped view for long
+        let mut result = Vec::from(&*data);
+        drop(data);
+        drop(buffer);
+
+        // Our buffer has been padded because we needed to align to a multiple of 256.
+        // We remove this padding here
+        remove_row_padding(&mut result, width, height, pixel_size);
+
+        screenshot_func(Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            wgpu::TextureDimension::D2,
+            result,
+            texture_format,
+            RenderAssetUsages::RENDER_WORLD,
+        ));
+    };
+
+    AsyncComputeTaskPool::get().spawn(finish).detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_tasks::TaskPool;
+    use std::time::Duration;
+    use wgpu::TextureDimension;
+
+    #[test]
+    fn row_padding_is_removed() {
+        let (width, height, pixel_size) = (3u32, 4u32, 4usize);
+        let row_bytes = width as usize * pixel_size;
+        let padded_row_bytes = align_byte_size(width * pixel_size as u32) as usize;
+        assert!(padded_row_bytes > row_bytes);
+
+        let mut data = vec![0xff; padded_row_bytes * height as usize];
+        for row in 0..height as usize {
+            for byte in 0..row_bytes {
+                data[row * padded_row_bytes + byte] = (row * row_bytes + byte) as u8;
+            }
+        }
 
-                screenshot_func(Image::new(
-                    Extent3d {
-                        width,
-                        height,
-                        depth_or_array_layers: 1,
-                    },
-                    wgpu::TextureDimension::D2,
-                    result,
-                    texture_format,
-                    RenderAssetUsages::RENDER_WORLD,
-                ));
-            };
-
-            AsyncComputeTaskPool::get().spawn(finish).detach();
+        remove_row_padding(&mut data, width, height, pixel_size);
+        let expected: Vec<u8> = (0..(row_bytes * height as usize) as u8).collect();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn captured_bgra_is_converted_to_rgba() {
+        let size = Extent3d {
+            width: 2,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let bgra = Image::new(
+            size,
+            TextureDimension::D2,
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            TextureFormat::Bgra8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        let rgba = convert_to_rgba8(bgra);
+        assert_eq!(
+            rgba.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        assert_eq!(rgba.data, vec![3, 2, 1, 4, 7, 6, 5, 8]);
+
+        // Other formats, including linear ones that would be relabeled as sRGB, are passed through.
+        for format in [
+            TextureFormat::Rgba16Float,
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::R8Unorm,
+        ] {
+            let image = Image::new_fill(
+                size,
+                TextureDimension::D2,
+                &vec![0; format.block_copy_size(None).unwrap() as usize],
+                format,
+                RenderAssetUsages::RENDER_WORLD,
+            );
+            assert_eq!(convert_to_rgba8(image).texture_descriptor.format, format);
         }
     }
+
+    #[test]
+    #[ignore = "requires a GPU adapter"]
+    fn image_screenshots_are_read_back_without_row_padding() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let (device, queue, adapter) = crate::renderer::test_device();
+
+        // Rows of 3 texels are padded in the buffer the image is copied into.
+        let mut image = screenshot_target_image(3, 2, TextureFormat::Rgba8UnormSrgb);
+        assert!(image
+            .texture_descriptor
+            .usage
+            .contains(TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC));
+        image.data = (0..3 * 2 * 4).collect();
+        let texture = device.create_texture_with_data(
+            &queue,
+            &image.texture_descriptor,
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &image.data,
+        );
+        let gpu_image = GpuImage {
+            texture_view: texture.create_view(&Default::default()),
+            texture,
+            texture_format: image.texture_descriptor.format,
+            sampler: device.create_sampler(&Default::default()),
+            size: image.size(),
+            mip_level_count: 1,
+            mip_level_views: Vec::new(),
+            format_views: Vec::new(),
+            format_mip_level_views: Vec::new(),
+        };
+        let handle = Handle::<Image>::weak_from_u128(0x5c7ee45);
+        let mut images = RenderAssets::<GpuImage>::default();
+        images.insert(&handle, gpu_image);
+
+        let (sender, receiver) = async_channel::bounded(1);
+        let mut world = World::new();
+        world.insert_resource(images);
+        world.insert_resource(device.clone());
+        world.insert_resource(PipelineCache::new(device.clone(), adapter, true));
+        world.init_resource::<ExtractedWindows>();
+        world.insert_resource(ImageScreenshots(vec![ImageScreenshot {
+            image: handle.id(),
+            func: Box::new(move |image| sender.try_send(image).unwrap()),
+            memory: None,
+        }]));
+
+        // What the renderer does during a frame, see `render_system`.
+        world.run_system_once(prepare_image_screenshots);
+        let mut command_encoder = device.create_command_encoder(&Default::default());
+        submit_screenshot_commands(&world, &mut command_encoder);
+        queue.submit([command_encoder.finish()]);
+        collect_screenshots(&mut world);
+
+        // The buffer is mapped on the task pool, polling the device completes the mapping. Without
+        // the `multi_threaded` feature the task only makes progress when the pool is ticked.
+        let screenshot = (0..1000)
+            .find_map(|_| {
+                device.poll(wgpu::Maintain::Poll);
+                AsyncComputeTaskPool::get()
+                    .with_local_executor(|executor| while executor.try_tick() {});
+                std::thread::sleep(Duration::from_millis(5));
+                receiver.try_recv().ok()
+            })
+            .expect("the screenshot wasn't read back");
+        assert_eq!(
+            screenshot.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        assert_eq!(screenshot.size(), UVec2::new(3, 2));
+        assert_eq!(screenshot.data, image.data);
+    }
+
+    #[test]
+    fn tightly_packed_data_is_untouched() {
+        let mut data: Vec<u8> = (0..64).collect();
+        remove_row_padding(&mut data, 64, 1, 1);
+        assert_eq!(data, (0..64).collect::<Vec<u8>>());
+    }
 }
@@ -43,13 +43,15 @@ pub use texture_atlas_builder::*;
 pub use texture_slice::*;
 
 use bevy_app::prelude::*;
-use bevy_asset::{load_internal_asset, AssetApp, Assets, Handle};
+use bevy_asset::{load_internal_asset, AssetApp, AssetEvent, Assets, Handle};
 use bevy_core_pipeline::core_2d::Transparent2d;
 use bevy_ecs::{prelude::*, query::QueryItem};
 use bevy_render::{
     extract_component::{ExtractComponent, ExtractComponentPlugin},
+    extract_resource::ExtractResourcePlugin,
     mesh::Mesh,
     primitives::Aabb,
+    redraw::RedrawPolicyApp,
     render_phase::AddRenderCommand,
     render_resource::{Shader, SpecializedRenderPipelines},
     texture::Image,
@@ -70,6 +72,14 @@ pub const SPRITE_VIEW_BINDINGS_SHADER_HANDLE: Handle<Shader> =
 pub enum SpriteSystem {
     ExtractSprites,
     ComputeSlices,
+    /// Queues sprites into the [`Transparent2d`] phase, in [`RenderSet::Queue`] of the render app.
+    ///
+    /// [`Transparent2d`] items are sorted with a stable sort, so items with equal sort keys are
+    /// drawn in the order they were queued. Systems queuing other items into the same phase, such
+    /// as custom 2D material queue systems, should be ordered `.after(SpriteSystem::Queue)` (or
+    /// `.before`) so that sprites and those items at the same depth draw in a predictable order.
+    /// Queuing after sprites draws the other items on top of them.
+    Queue,
 }
 
 /// A component that marks entities that aren't themselves sprites but become
@@ -111,10 +121,16 @@ impl Plugin for SpritePlugin {
             .register_type::<TextureAtlas>()
             .register_type::<Mesh2dHandle>()
             .register_type::<SpriteSource>()
+            .init_resource::<SpriteDebug>()
+            .redraw_on_change::<Sprite>()
+            .redraw_on_change::<TextureAtlas>()
+            .redraw_on_change::<Mesh2dHandle>()
+            .redraw_on_event::<AssetEvent<TextureAtlasLayout>>()
             .add_plugins((
                 Mesh2dRenderPlugin,
                 ColorMaterialPlugin,
                 ExtractComponentPlugin::<SpriteSource>::default(),
+                ExtractResourcePlugin::<SpriteDebug>::default(),
             ))
             .add_systems(
                 PostUpdate,
@@ -153,6 +169,7 @@ impl Plugin for SpritePlugin {
                     (
                         queue_sprites
                             .in_set(RenderSet::Queue)
+                            .in_set(SpriteSystem::Queue)
                             .ambiguous_with(queue_material2d_meshes::<ColorMaterial>),
                         prepare_sprite_image_bind_groups.in_set(RenderSet::PrepareBindGroups),
                         prepare_sprite_view_bind_groups.in_set(RenderSet::PrepareBindGroups),
@@ -390,4 +407,144 @@ mod test {
         // Verify that the AABB has the expected size
         assert_eq!(aabb.half_extents, Vec3A::new(0.25, 0.5, 0.));
     }
+
+    #[test]
+    fn extract_sprite_events_coalesces_repeated_events() {
+        use bevy_asset::AssetEvent;
+        use bevy_ecs::{event::Events, system::RunSystemOnce};
+        use bevy_render::{texture::TRANSPARENT_IMAGE_HANDLE, MainWorld};
+
+        let mut main_world = MainWorld::default();
+        main_world
+            .world_mut()
+            .init_resource::<Events<AssetEvent<Image>>>();
+        let atlas = Handle::<Image>::default().id();
+        let other = TRANSPARENT_IMAGE_HANDLE.id();
+        let mut events = main_world
+            .world_mut()
+            .resource_mut::<Events<AssetEvent<Image>>>();
+        for _ in 0..100 {
+            events.send(AssetEvent::Modified { id: atlas });
+        }
+        events.send(AssetEvent::Modified { id: other });
+        events.send(AssetEvent::Removed { id: atlas });
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<SpriteAssetEvents>();
+        render_world.run_system_once(extract_sprite_events);
+
+        assert_eq!(
+            render_world.resource::<SpriteAssetEvents>().images,
+            vec![
+                AssetEvent::Modified { id: atlas },
+                AssetEvent::Modified { id: other },
+                AssetEvent::Removed { id: atlas },
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_sprites_keeps_pipeline_override() {
+        use bevy_ecs::system::RunSystemOnce;
+        use bevy_render::{
+            render_resource::CachedRenderPipelineId, view::ViewVisibility, MainWorld,
+        };
+        use bevy_transform::components::GlobalTransform;
+
+        let mut main_world = MainWorld::default();
+        main_world
+            .world_mut()
+            .init_resource::<Assets<TextureAtlasLayout>>();
+        let mut visible = ViewVisibility::HIDDEN;
+        visible.set();
+        let sprite = (
+            visible,
+            Sprite::default(),
+            GlobalTransform::default(),
+            Handle::<Image>::default(),
+        );
+        let default_sprite = main_world.world_mut().spawn(sprite.clone()).id();
+        let overridden_sprite = main_world
+            .world_mut()
+            .spawn((
+                sprite,
+                SpritePipelineOverride(CachedRenderPipelineId::INVALID),
+            ))
+            .id();
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<ExtractedSprites>();
+        render_world.run_system_once(extract_sprites);
+
+        let sprites = &render_world.resource::<ExtractedSprites>().sprites;
+        assert_eq!(sprites[&default_sprite].pipeline_override, None);
+        assert_eq!(
+            sprites[&overridden_sprite].pipeline_override,
+            Some(CachedRenderPipelineId::INVALID)
+        );
+    }
+
+    #[test]
+    fn extract_sprites_is_independent_of_parallelism() {
+        use bevy_color::Color;
+        use bevy_ecs::system::RunSystemOnce;
+        use bevy_render::{view::ViewVisibility, ExtractParallelism, MainWorld};
+        use bevy_tasks::{ComputeTaskPool, TaskPool};
+        use bevy_transform::components::GlobalTransform;
+
+        ComputeTaskPool::get_or_init(TaskPool::default);
+
+        let mut main_world = MainWorld::default();
+        main_world
+            .world_mut()
+            .init_resource::<Assets<TextureAtlasLayout>>();
+        for i in 0..1000 {
+            let mut view_visibility = ViewVisibility::HIDDEN;
+            if i % 3 != 0 {
+                view_visibility.set();
+            }
+            main_world.world_mut().spawn((
+                view_visibility,
+                Sprite {
+                    color: Color::srgb(i as f32 / 1000.0, 0.0, 0.0),
+                    ..default()
+                },
+                GlobalTransform::from_xyz(i as f32, 0.0, 0.0),
+                Handle::<Image>::default(),
+            ));
+        }
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<ExtractedSprites>();
+
+        let mut extract = |parallelism| {
+            render_world.insert_resource(parallelism);
+            render_world.run_system_once(extract_sprites);
+            let mut sprites: Vec<_> = render_world
+                .resource::<ExtractedSprites>()
+                .sprites
+                .iter()
+                .map(|(entity, sprite)| (*entity, sprite.transform.translation(), sprite.color))
+                .collect();
+            sprites.sort_by_key(|(entity, ..)| *entity);
+            sprites
+        };
+
+        let single_threaded = extract(ExtractParallelism::SINGLE_THREADED);
+        assert_eq!(single_threaded.len(), 666);
+        assert_eq!(single_threaded, extract(ExtractParallelism::parallel(16)));
+        assert_eq!(single_threaded, extract(ExtractParallelism::parallel(1)));
+    }
+
+    #[test]
+    fn sprite_batch_debug_colors_differ_between_neighbouring_batches() {
+        for batch_index in 0..64 {
+            let color = sprite_batch_debug_color(batch_index);
+            assert_eq!(color, sprite_batch_debug_color(batch_index));
+            assert_ne!(color, sprite_batch_debug_color(batch_index + 1));
+            assert_eq!(color.alpha, 1.0);
+        }
+    }
 }
@@ -13,6 +13,7 @@ use bevy_ecs::{
 use bevy_math::FloatOrd;
 use bevy_render::{
     mesh::{GpuMesh, MeshVertexBufferLayoutRef},
+    redraw::RedrawPolicyApp,
     render_asset::{
         prepare_assets, PrepareAssetError, RenderAsset, RenderAssetPlugin, RenderAssets,
     },
@@ -149,6 +150,7 @@ where
 {
     fn build(&self, app: &mut App) {
         app.init_asset::<M>()
+            .redraw_on_change::<Handle<M>>()
             .add_plugins(RenderAssetPlugin::<PreparedMaterial2d<M>>::default());
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
@@ -454,6 +456,7 @@ pub fn queue_material2d_meshes<M: Material2d>(
                 // -z in front of the camera, the largest distance is -far with values increasing toward the
                 // camera. As such we can just use mesh_z as the distance
                 sort_key: FloatOrd(mesh_z + material_2d.depth_bias),
+                extracted_index: visible_entity.index(),
                 // Batching is done in batch_and_prepare_render_phase
                 batch_range: 0..1,
                 extra_index: PhaseItemExtraIndex::NONE,
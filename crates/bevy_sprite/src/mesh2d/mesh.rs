@@ -325,6 +325,9 @@ impl FromWorld for Mesh2dPipeline {
                 sampler,
                 size: image.size(),
                 mip_level_count: image.texture_descriptor.mip_level_count,
+                mip_level_views: Vec::new(),
+                format_views: Vec::new(),
+                format_mip_level_views: Vec::new(),
             }
         };
         Mesh2dPipeline {
@@ -573,6 +576,9 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
                 alpha_to_coverage_enabled: false,
             },
             label: Some("transparent_mesh2d_pipeline".into()),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         })
     }
 }
@@ -1,11 +1,14 @@
-use std::ops::Range;
+use std::{
+    mem::{discriminant, Discriminant},
+    ops::Range,
+};
 
 use crate::{
     texture_atlas::{TextureAtlas, TextureAtlasLayout},
     ComputedTextureSlices, Sprite, WithSprite, SPRITE_SHADER_HANDLE,
 };
 use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
-use bevy_color::{ColorToComponents, LinearRgba};
+use bevy_color::{ColorToComponents, Hsla, LinearRgba};
 use bevy_core_pipeline::{
     core_2d::Transparent2d,
     tonemapping::{
@@ -20,6 +23,7 @@ use bevy_ecs::{
 };
 use bevy_math::{Affine3A, FloatOrd, Quat, Rect, Vec2, Vec4};
 use bevy_render::{
+    extract_resource::ExtractResource,
     render_asset::RenderAssets,
     render_phase::{
         DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand, RenderCommandResult,
@@ -38,10 +42,10 @@ use bevy_render::{
         ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms,
         ViewVisibility, VisibleEntities,
     },
-    Extract,
+    Extract, ExtractParallelism,
 };
 use bevy_transform::components::GlobalTransform;
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, HashSet, Parallel};
 use bytemuck::{Pod, Zeroable};
 use fixedbitset::FixedBitSet;
 
@@ -119,6 +123,9 @@ impl FromWorld for SpritePipeline {
                 sampler,
                 size: image.size(),
                 mip_level_count: image.texture_descriptor.mip_level_count,
+                mip_level_views: Vec::new(),
+                format_views: Vec::new(),
+                format_mip_level_views: Vec::new(),
             }
         };
 
@@ -140,6 +147,7 @@ bitflags::bitflags! {
         const HDR                               = 1 << 0;
         const TONEMAP_IN_SHADER                 = 1 << 1;
         const DEBAND_DITHER                     = 1 << 2;
+        const DEBUG_BATCH_COLORS                = 1 << 3;
         const MSAA_RESERVED_BITS                = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
         const TONEMAP_METHOD_RESERVED_BITS      = Self::TONEMAP_METHOD_MASK_BITS << Self::TONEMAP_METHOD_SHIFT_BITS;
         const TONEMAP_METHOD_NONE               = 0 << Self::TONEMAP_METHOD_SHIFT_BITS;
@@ -225,6 +233,10 @@ impl SpecializedRenderPipeline for SpritePipeline {
             }
         }
 
+        if key.contains(SpritePipelineKey::DEBUG_BATCH_COLORS) {
+            shader_defs.push("DEBUG_BATCH_COLORS".into());
+        }
+
         let format = match key.contains(SpritePipelineKey::HDR) {
             true => ViewTarget::TEXTURE_FORMAT_HDR,
             false => TextureFormat::bevy_default(),
@@ -302,6 +314,9 @@ impl SpecializedRenderPipeline for SpritePipeline {
             },
             label: Some("sprite_pipeline".into()),
             push_constant_ranges: Vec::new(),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -322,8 +337,67 @@ pub struct ExtractedSprite {
     /// For cases where additional [`ExtractedSprites`] are created during extraction, this stores the
     /// entity that caused that creation for use in determining visibility.
     pub original_entity: Option<Entity>,
+    /// Tiebreak for sprites sharing the same `z`, see [`Sprite::sort_bias`].
+    pub sort_bias: f32,
+    /// The pipeline to draw this sprite with instead of the [`SpritePipeline`],
+    /// see [`SpritePipelineOverride`].
+    pub pipeline_override: Option<CachedRenderPipelineId>,
+}
+
+impl ExtractedSprite {
+    /// The key used to sort this sprite in the [`Transparent2d`] phase.
+    ///
+    /// This is the sprite's `z` translation, shifted by [`Self::sort_bias`] times the precision
+    /// of `z`, `f32::EPSILON * z.abs().max(1.0)`, i.e. by one or two representable values of `z`
+    /// per unit of bias. See [`Sprite::sort_bias`] for the resulting limits.
+    #[inline]
+    pub fn sort_key(&self) -> FloatOrd {
+        FloatOrd(biased_z(self.transform.translation().z, self.sort_bias))
+    }
 }
 
+#[inline]
+fn biased_z(z: f32, sort_bias: f32) -> f32 {
+    z + sort_bias * f32::EPSILON * z.abs().max(1.0)
+}
+
+/// Debugging options for sprite rendering.
+#[derive(Resource, Clone, Copy, Debug, Default, ExtractResource)]
+pub struct SpriteDebug {
+    /// Draws every sprite batch in a flat color that differs from its neighbouring batches,
+    /// to show where batches break, e.g. on texture or pipeline changes.
+    ///
+    /// The sprite colors are replaced by [`sprite_batch_debug_color`] of the batch index, and
+    /// the sprite pipeline is specialized with the `DEBUG_BATCH_COLORS` shader def, which
+    /// ignores the texture color and only keeps its alpha.
+    pub batch_colors: bool,
+}
+
+/// The color the sprites of the batch with the given index are drawn with while
+/// [`SpriteDebug::batch_colors`] is enabled.
+///
+/// Successive indices are spread around the hue circle by the golden angle, so that
+/// neighbouring batches are easy to tell apart.
+pub fn sprite_batch_debug_color(batch_index: usize) -> LinearRgba {
+    const GOLDEN_ANGLE: f32 = 137.507_77;
+    let hue = (batch_index as f32 * GOLDEN_ANGLE) % 360.0;
+    Hsla::hsl(hue, 0.9, 0.6).into()
+}
+
+/// Draws a sprite with a custom render pipeline instead of the one specialized from the
+/// [`SpritePipeline`], e.g. to use a different fragment shader for an outline effect.
+///
+/// The pipeline is used with [`DrawSprite`], so it has to use the sprite instance vertex
+/// buffer layout and the view and material bind group layouts of the [`SpritePipeline`], and
+/// must match the target format and sample count of the views the sprite is drawn in. Starting
+/// from the descriptor returned by `SpritePipeline::specialize` and swapping the shader is the
+/// easiest way to get there. The id is usually obtained by queuing that descriptor on the render
+/// world [`PipelineCache`] when the plugin is finished.
+///
+/// Sprites are only batched together with sprites drawn with the same pipeline.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpritePipelineOverride(pub CachedRenderPipelineId);
+
 #[derive(Resource, Default)]
 pub struct ExtractedSprites {
     pub sprites: EntityHashMap<ExtractedSprite>,
@@ -334,80 +408,149 @@ pub struct SpriteAssetEvents {
     pub images: Vec<AssetEvent<Image>>,
 }
 
+/// Extracts the [`AssetEvent<Image>`]s of this frame into [`SpriteAssetEvents`].
+///
+/// Repeated events of the same kind for the same image are only forwarded once, since images
+/// that are modified many times in a frame only need their bind groups invalidated once.
 pub fn extract_sprite_events(
     mut events: ResMut<SpriteAssetEvents>,
     mut image_events: Extract<EventReader<AssetEvent<Image>>>,
+    mut seen: Local<HashSet<(Discriminant<AssetEvent<Image>>, AssetId<Image>)>>,
 ) {
     let SpriteAssetEvents { ref mut images } = *events;
     images.clear();
+    seen.clear();
 
     for event in image_events.read() {
-        images.push(*event);
+        let (AssetEvent::Added { id }
+        | AssetEvent::Modified { id }
+        | AssetEvent::Removed { id }
+        | AssetEvent::Unused { id }
+        | AssetEvent::LoadedWithDependencies { id }) = event;
+        if seen.insert((discriminant(event), *id)) {
+            images.push(*event);
+        }
     }
 }
 
+type ExtractSpriteQueryData = (
+    Entity,
+    &'static ViewVisibility,
+    &'static Sprite,
+    &'static GlobalTransform,
+    &'static Handle<Image>,
+    Option<&'static TextureAtlas>,
+    Option<&'static ComputedTextureSlices>,
+    Option<&'static SpritePipelineOverride>,
+);
+
+/// The sprites extracted by one thread during [`extract_sprites`].
+#[derive(Default)]
+pub struct ExtractedSpriteQueue {
+    sprites: Vec<(Entity, ExtractedSprite)>,
+    /// Sprites that need a new render world entity, which can't be spawned from worker threads.
+    slices: Vec<ExtractedSprite>,
+}
+
 pub fn extract_sprites(
     mut commands: Commands,
     mut extracted_sprites: ResMut<ExtractedSprites>,
+    extract_parallelism: Option<Res<ExtractParallelism>>,
+    mut thread_queues: Local<Parallel<ExtractedSpriteQueue>>,
     texture_atlases: Extract<Res<Assets<TextureAtlasLayout>>>,
-    sprite_query: Extract<
-        Query<(
-            Entity,
-            &ViewVisibility,
-            &Sprite,
-            &GlobalTransform,
-            &Handle<Image>,
-            Option<&TextureAtlas>,
-            Option<&ComputedTextureSlices>,
-        )>,
-    >,
+    sprite_query: Extract<Query<ExtractSpriteQueryData>>,
 ) {
     extracted_sprites.sprites.clear();
-    for (entity, view_visibility, sprite, transform, handle, sheet, slices) in sprite_query.iter() {
-        if !view_visibility.get() {
-            continue;
-        }
 
-        if let Some(slices) = slices {
-            extracted_sprites.sprites.extend(
-                slices
-                    .extract_sprites(transform, entity, sprite, handle)
-                    .map(|e| (commands.spawn_empty().id(), e)),
-            );
-        } else {
-            let atlas_rect = sheet.and_then(|s| s.texture_rect(&texture_atlases));
-            let rect = match (atlas_rect, sprite.rect) {
-                (None, None) => None,
-                (None, Some(sprite_rect)) => Some(sprite_rect),
-                (Some(atlas_rect), None) => Some(atlas_rect.as_rect()),
-                (Some(atlas_rect), Some(mut sprite_rect)) => {
-                    sprite_rect.min += atlas_rect.min.as_vec2();
-                    sprite_rect.max += atlas_rect.min.as_vec2();
-
-                    Some(sprite_rect)
-                }
-            };
+    match extract_parallelism.and_then(|parallelism| parallelism.batching_strategy()) {
+        Some(batching_strategy) => {
+            sprite_query
+                .par_iter()
+                .batching_strategy(batching_strategy)
+                .for_each_init(
+                    || thread_queues.borrow_local_mut(),
+                    |queue, query_item| queue_extracted_sprite(queue, &texture_atlases, query_item),
+                );
+        }
+        None => {
+            let mut queue = thread_queues.borrow_local_mut();
+            for query_item in sprite_query.iter() {
+                queue_extracted_sprite(&mut queue, &texture_atlases, query_item);
+            }
+        }
+    }
 
-            // PERF: we don't check in this function that the `Image` asset is ready, since it should be in most cases and hashing the handle is expensive
-            extracted_sprites.sprites.insert(
-                entity,
-                ExtractedSprite {
-                    color: sprite.color.into(),
-                    transform: *transform,
-                    rect,
-                    // Pass the custom size
-                    custom_size: sprite.custom_size,
-                    flip_x: sprite.flip_x,
-                    flip_y: sprite.flip_y,
-                    image_handle_id: handle.id(),
-                    anchor: sprite.anchor.as_vec(),
-                    original_entity: None,
-                },
-            );
+    // Each thread only ever pushed into its own queue, and the queues are only merged here, on
+    // the render thread, once the iteration above has finished. Sprites are keyed by their
+    // entity so the merge order doesn't change the result; only the ids of the entities spawned
+    // for texture slices depend on it.
+    for queue in thread_queues.iter_mut() {
+        extracted_sprites.sprites.extend(queue.sprites.drain(..));
+        for slice in queue.slices.drain(..) {
+            extracted_sprites
+                .sprites
+                .insert(commands.spawn_empty().id(), slice);
         }
     }
 }
 
+fn queue_extracted_sprite(
+    queue: &mut ExtractedSpriteQueue,
+    texture_atlases: &Assets<TextureAtlasLayout>,
+    query_item: ROQueryItem<'_, ExtractSpriteQueryData>,
+) {
+    let (entity, view_visibility, sprite, transform, handle, sheet, slices, pipeline_override) =
+        query_item;
+    if !view_visibility.get() {
+        return;
+    }
+
+    let pipeline_override = pipeline_override.map(|pipeline_override| pipeline_override.0);
+
+    if let Some(slices) = slices {
+        queue.slices.extend(
+            slices
+                .extract_sprites(transform, entity, sprite, handle)
+                .map(|e| ExtractedSprite {
+                    pipeline_override,
+                    ..e
+                }),
+        );
+    } else {
+        let atlas_rect = sheet.and_then(|s| s.texture_rect(texture_atlases));
+        let rect = match (atlas_rect, sprite.rect) {
+            (None, None) => None,
+            (None, Some(sprite_rect)) => Some(sprite_rect),
+            (Some(atlas_rect), None) => Some(atlas_rect.as_rect()),
+            (Some(atlas_rect), Some(mut sprite_rect)) => {
+                sprite_rect.min += atlas_rect.min.as_vec2();
+                sprite_rect.max += atlas_rect.min.as_vec2();
+
+                Some(sprite_rect)
+            }
+        };
+
+        // PERF: we don't check in this function that the `Image` asset is ready, since it should be in most cases and hashing the handle is expensive
+        queue.sprites.push((
+            entity,
+            ExtractedSprite {
+                color: sprite.color.into(),
+                transform: *transform,
+                rect,
+                // Pass the custom size
+                custom_size: sprite.custom_size,
+                flip_x: sprite.flip_x,
+                flip_y: sprite.flip_y,
+                image_handle_id: handle.id(),
+                anchor: sprite.anchor.as_vec(),
+                original_entity: None,
+                sort_bias: sprite.sort_bias,
+                pipeline_override,
+            },
+        ));
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct SpriteInstance {
@@ -481,8 +624,12 @@ pub fn queue_sprites(
         Option<&Tonemapping>,
         Option<&DebandDither>,
     )>,
+    sprite_debug: Option<Res<SpriteDebug>>,
 ) {
-    let msaa_key = SpritePipelineKey::from_msaa_samples(msaa.samples());
+    let mut msaa_key = SpritePipelineKey::from_msaa_samples(msaa.samples());
+    if sprite_debug.is_some_and(|sprite_debug| sprite_debug.batch_colors) {
+        msaa_key |= SpritePipelineKey::DEBUG_BATCH_COLORS;
+    }
 
     let draw_sprite_function = draw_functions.read().id::<DrawSprite>();
 
@@ -537,14 +684,17 @@ pub fn queue_sprites(
             }
 
             // These items will be sorted by depth with other phase items
-            let sort_key = FloatOrd(extracted_sprite.transform.translation().z);
+            let sort_key = extracted_sprite.sort_key();
 
             // Add the item to the render phase
             transparent_phase.add(Transparent2d {
                 draw_function: draw_sprite_function,
-                pipeline,
+                pipeline: extracted_sprite.pipeline_override.unwrap_or(pipeline),
                 entity: *entity,
                 sort_key,
+                // The main world entity, as the render world entities of some sprites are
+                // respawned with different ids every frame
+                extracted_index: index,
                 // batch_range and dynamic_offset will be calculated in prepare_sprites
                 batch_range: 0..0,
                 extra_index: PhaseItemExtraIndex::NONE,
@@ -600,7 +750,10 @@ pub fn prepare_sprite_image_bind_groups(
     extracted_sprites: Res<ExtractedSprites>,
     mut phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
     events: Res<SpriteAssetEvents>,
+    sprite_debug: Option<Res<SpriteDebug>>,
 ) {
+    let debug_batch_colors = sprite_debug.is_some_and(|sprite_debug| sprite_debug.batch_colors);
+
     // If an image has changed, the GpuImage has (probably) changed
     for event in &events.images {
         match event {
@@ -627,6 +780,7 @@ pub fn prepare_sprite_image_bind_groups(
         let mut batch_item_index = 0;
         let mut batch_image_size = Vec2::ZERO;
         let mut batch_image_handle = AssetId::invalid();
+        let mut batch_pipeline = CachedRenderPipelineId::INVALID;
 
         // Iterate through the phase items and detect when successive sprites that can be batched.
         // Spawn an entity with a `SpriteBatch` component for each possible batch.
@@ -642,6 +796,9 @@ pub fn prepare_sprite_image_bind_groups(
             };
 
             let batch_image_changed = batch_image_handle != extracted_sprite.image_handle_id;
+            // Only the first item of a batch is drawn, so sprites drawn with another pipeline
+            // (see `SpritePipelineOverride`) need their own batch.
+            let batch_pipeline_changed = batch_pipeline != item.pipeline;
             if batch_image_changed {
                 let Some(gpu_image) = gpu_images.get(extracted_sprite.image_handle_id) else {
                     continue;
@@ -704,17 +861,23 @@ pub fn prepare_sprite_image_bind_groups(
                     (quad_size * (-extracted_sprite.anchor - Vec2::splat(0.5))).extend(0.0),
                 );
 
+            let new_batch = batch_image_changed || batch_pipeline_changed;
+            let color = if debug_batch_colors {
+                // The batch is pushed below, so a new batch gets the index `batches.len()`.
+                let batch_index = batches.len() - usize::from(!new_batch);
+                sprite_batch_debug_color(batch_index)
+            } else {
+                extracted_sprite.color
+            };
+
             // Store the vertex data and add the item to the render phase
             sprite_meta
                 .sprite_instance_buffer
-                .push(SpriteInstance::from(
-                    &transform,
-                    &extracted_sprite.color,
-                    &uv_offset_scale,
-                ));
+                .push(SpriteInstance::from(&transform, &color, &uv_offset_scale));
 
-            if batch_image_changed {
+            if new_batch {
                 batch_item_index = item_index;
+                batch_pipeline = item.pipeline;
 
                 batches.push((
                     item.entity,
@@ -854,3 +1017,28 @@ impl<P: PhaseItem> RenderCommand<P> for DrawSpriteBatch {
         RenderCommandResult::Success
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_bias_orders_coplanar_sprites() {
+        for z in [0.0, 1.0, -3.0, 1000.0] {
+            assert!(biased_z(z, -1.0) < biased_z(z, 0.0));
+            assert!(biased_z(z, 0.0) < biased_z(z, 1.0));
+            assert!(biased_z(z, 1.0) < biased_z(z, 2.0));
+            assert_eq!(biased_z(z, 0.0), z);
+        }
+
+        // Small biases stay between neighbouring `z` values that are far enough apart.
+        let next_z = 1000.0 + 0.01;
+        assert!(biased_z(1000.0, 10.0) < biased_z(next_z, -10.0));
+
+        // Large biases cross them.
+        assert!(biased_z(1000.0, 100.0) > next_z);
+
+        // Fractional biases smaller than the precision of `z` round away.
+        assert_eq!(biased_z(1000.0, 0.25), 1000.0);
+    }
+}
@@ -53,7 +53,13 @@ fn vertex(in: VertexInput) -> VertexOutput {
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef DEBUG_BATCH_COLORS
+    // `in.color` holds the debug color of the sprite's batch, keep only the texture's alpha
+    // so sprite shapes stay visible.
+    var color = vec4<f32>(in.color.rgb, textureSample(sprite_texture, sprite_sampler, in.uv).a);
+#else
     var color = in.color * textureSample(sprite_texture, sprite_sampler, in.uv);
+#endif
 
 #ifdef TONEMAP_IN_SHADER
     color = tonemapping::tone_mapping(color, view.color_grading);
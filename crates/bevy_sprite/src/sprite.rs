@@ -29,6 +29,16 @@ pub struct Sprite {
     pub rect: Option<Rect>,
     /// [`Anchor`] point of the sprite in the world
     pub anchor: Anchor,
+    /// Tiebreak for the draw order of sprites sharing the same `z` translation.
+    ///
+    /// Among coplanar sprites, those with a higher bias are drawn on top of those with a lower one.
+    /// The bias is added to the sort key in units of the precision of `z`, so a bias of `1.0`
+    /// moves the key by about one representable `f32` value. Use whole numbers: fractional
+    /// biases may round away entirely. The bias can move a sprite past sprites on a different
+    /// `z` that is closer than `sort_bias * f32::EPSILON * z.abs().max(1.0)`, so keep it small
+    /// compared to the spacing of your `z` values.
+    /// Defaults to `0.0`.
+    pub sort_bias: f32,
 }
 
 /// Controls how the image is altered when scaled.
@@ -54,6 +54,8 @@ impl ComputedTextureSlices {
                 flip_y,
                 image_handle_id: handle.id(),
                 anchor: Self::redepend_anchor_from_sprite_to_slice(sprite, slice),
+                sort_bias: sprite.sort_bias,
+                pipeline_override: None,
             }
         })
     }
@@ -38,7 +38,8 @@ use bevy_asset::AssetApp;
 use bevy_asset::{load_internal_binary_asset, Handle};
 use bevy_ecs::prelude::*;
 use bevy_render::{
-    camera::CameraUpdateSystem, view::VisibilitySystems, ExtractSchedule, RenderApp,
+    camera::CameraUpdateSystem, redraw::RedrawPolicyApp, view::VisibilitySystems, ExtractSchedule,
+    RenderApp,
 };
 use bevy_sprite::SpriteSystem;
 use std::num::NonZeroUsize;
@@ -91,6 +92,9 @@ impl Plugin for TextPlugin {
             .init_resource::<TextSettings>()
             .init_resource::<FontAtlasSets>()
             .insert_resource(TextPipeline::default())
+            .redraw_on_change::<Text>()
+            .redraw_on_change::<Text2dBounds>()
+            .redraw_on_change::<TextLayoutInfo>()
             .add_systems(
                 PostUpdate,
                 (
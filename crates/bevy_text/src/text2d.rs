@@ -156,6 +156,8 @@ pub fn extract_text2d_sprite(
                     flip_y: false,
                     anchor: Anchor::Center.as_vec(),
                     original_entity: Some(original_entity),
+                    sort_bias: 0.0,
+                    pipeline_override: None,
                 },
             );
         }
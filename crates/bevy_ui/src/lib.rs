@@ -54,6 +54,7 @@ use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_input::InputSystem;
 use bevy_render::{
+    redraw::RedrawPolicyApp,
     view::{check_visibility, VisibilitySystems},
     RenderApp,
 };
@@ -129,6 +130,17 @@ impl Plugin for UiPlugin {
             .register_type::<widget::Label>()
             .register_type::<ZIndex>()
             .register_type::<Outline>()
+            .redraw_on_change::<Node>()
+            .redraw_on_change::<Style>()
+            .redraw_on_change::<BackgroundColor>()
+            .redraw_on_change::<BorderColor>()
+            .redraw_on_change::<BorderRadius>()
+            .redraw_on_change::<Outline>()
+            .redraw_on_change::<UiImage>()
+            .redraw_on_change::<CalculatedClip>()
+            .redraw_on_change::<ZIndex>()
+            .redraw_on_change::<TargetCamera>()
+            .redraw_on_resource_change::<UiScale>()
             .add_systems(
                 PreUpdate,
                 ui_focus_system.in_set(UiSystem::Focus).after(InputSystem),
@@ -205,6 +205,9 @@ impl SpecializedRenderPipeline for ColoredMesh2dPipeline {
                 alpha_to_coverage_enabled: false,
             },
             label: Some("colored_mesh2d_pipeline".into()),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
         }
     }
 }
@@ -394,6 +397,7 @@ pub fn queue_colored_mesh2d(
                     // The 2d render items are sorted according to their z value before rendering,
                     // in order to get correct transparency
                     sort_key: FloatOrd(mesh_z),
+                    extracted_index: visible_entity.index(),
                     // This material is not batched
                     batch_range: 0..1,
                     extra_index: PhaseItemExtraIndex::NONE,
@@ -341,7 +341,9 @@ mod pipelines_ready {
     pub struct PipelinesReady(pub bool);
 
     fn update_pipelines_ready(mut main_world: ResMut<MainWorld>, pipelines: Res<PipelineCache>) {
-        if let Some(mut pipelines_ready) = main_world.get_resource_mut::<PipelinesReady>() {
+        if let Some(mut pipelines_ready) =
+            main_world.world_mut().get_resource_mut::<PipelinesReady>()
+        {
             pipelines_ready.0 = pipelines.waiting_pipelines().count() == 0;
         }
     }
@@ -22,7 +22,9 @@ const SHADER_ASSET_PATH: &str = "shaders/game_of_life.wgsl";
 
 const DISPLAY_FACTOR: u32 = 4;
 const SIZE: (u32, u32) = (1280 / DISPLAY_FACTOR, 720 / DISPLAY_FACTOR);
+// Must match the `@workgroup_size` of the shader entry points.
 const WORKGROUP_SIZE: u32 = 8;
+const INITIAL_ALIVE_PROBABILITY: f64 = 0.1;
 
 fn main() {
     App::new()
@@ -181,6 +183,14 @@ impl FromWorld for GameOfLifePipeline {
             shader: shader.clone(),
             shader_defs: vec![],
             entry_point: Cow::from("init"),
+            // Overrides the `initial_alive_probability` constant of the shader without changing
+            // its source. The workgroup size can't be overridden the same way: naga and wgpu don't
+            // support pipeline-overridable constants in `@workgroup_size` yet, so it stays a
+            // literal in the shader, mirrored by `WORKGROUP_SIZE`.
+            constants: vec![(
+                "initial_alive_probability".to_string(),
+                INITIAL_ALIVE_PROBABILITY,
+            )],
         });
         let update_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
             label: None,
@@ -189,6 +199,7 @@ impl FromWorld for GameOfLifePipeline {
             shader,
             shader_defs: vec![],
             entry_point: Cow::from("update"),
+            constants: vec![],
         });
 
         GameOfLifePipeline {
@@ -189,6 +189,7 @@ impl FromWorld for ComputePipeline {
             shader: shader.clone(),
             shader_defs: Vec::new(),
             entry_point: "main".into(),
+            constants: Vec::new(),
         });
         ComputePipeline { layout, pipeline }
     }
@@ -287,6 +287,9 @@ impl FromWorld for PostProcessPipeline {
                 depth_stencil: None,
                 multisample: MultisampleState::default(),
                 push_constant_ranges: vec![],
+                constants: Vec::new(),
+                vertex_compilation_options: None,
+                fragment_compilation_options: None,
             });
 
         Self {
@@ -210,22 +210,16 @@ impl SpecializedMeshPipeline for CustomPipeline {
         let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
 
         descriptor.vertex.shader = self.shader.clone();
-        descriptor.vertex.buffers.push(VertexBufferLayout {
-            array_stride: std::mem::size_of::<InstanceData>() as u64,
-            step_mode: VertexStepMode::Instance,
-            attributes: vec![
-                VertexAttribute {
-                    format: VertexFormat::Float32x4,
-                    offset: 0,
-                    shader_location: 3, // shader locations 0-2 are taken up by Position, Normal and UV attributes
-                },
-                VertexAttribute {
-                    format: VertexFormat::Float32x4,
-                    offset: VertexFormat::Float32x4.size(),
-                    shader_location: 4,
-                },
-            ],
-        });
+        // The instance data is stepped once per instance, while the mesh vertex buffer is stepped
+        // once per vertex.
+        descriptor.vertex.buffers.push(
+            VertexBufferLayout::from_vertex_formats(
+                VertexStepMode::Instance,
+                [VertexFormat::Float32x4, VertexFormat::Float32x4],
+            )
+            // shader locations 0-2 are taken up by Position, Normal and UV attributes
+            .offset_locations_by(3),
+        );
         descriptor.fragment.as_mut().unwrap().shader = self.shader.clone();
         Ok(descriptor)
     }
@@ -0,0 +1,295 @@
+//! A shader that renders many 2d quads in one draw call.
+//!
+//! The quad's corners are read from a vertex buffer stepped once per vertex, while the position,
+//! size and color of each quad are read from a second buffer stepped once per instance.
+
+use bevy::{
+    core_pipeline::core_2d::Transparent2d,
+    ecs::{
+        query::QueryItem,
+        system::{lifetimeless::*, SystemParamItem},
+    },
+    math::FloatOrd,
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+            RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+        },
+        render_resource::*,
+        renderer::RenderDevice,
+        texture::BevyDefault,
+        view::{ExtractedView, ViewTarget},
+        Render, RenderApp, RenderSet,
+    },
+    sprite::{Mesh2dPipeline, Mesh2dPipelineKey, SetMesh2dViewBindGroup},
+};
+use bytemuck::{Pod, Zeroable};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/instancing_2d.wgsl";
+
+/// The corners of a unit quad, drawn as a triangle strip.
+const QUAD_CORNERS: [Vec2; 4] = [
+    Vec2::new(-0.5, -0.5),
+    Vec2::new(0.5, -0.5),
+    Vec2::new(-0.5, 0.5),
+    Vec2::new(0.5, 0.5),
+];
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, InstancedQuadsPlugin))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(InstancedQuads(
+        (0..20)
+            .flat_map(|x| (0..12).map(move |y| (x as f32 / 20.0, y as f32 / 12.0)))
+            .map(|(x, y)| QuadInstance {
+                position: Vec2::new(x * 1000.0 - 475.0, y * 600.0 - 275.0),
+                size: Vec2::splat(40.0),
+                color: LinearRgba::from(Color::hsla(x * 360., y, 0.5, 1.0)).to_f32_array(),
+            })
+            .collect(),
+    ));
+
+    commands.spawn(Camera2dBundle::default());
+}
+
+/// The quads to draw, in world space.
+#[derive(Component, Deref)]
+struct InstancedQuads(Vec<QuadInstance>);
+
+impl ExtractComponent for InstancedQuads {
+    type QueryData = &'static InstancedQuads;
+    type QueryFilter = ();
+    type Out = Self;
+
+    fn extract_component(item: QueryItem<'_, Self::QueryData>) -> Option<Self> {
+        Some(InstancedQuads(item.0.clone()))
+    }
+}
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+struct QuadInstance {
+    position: Vec2,
+    size: Vec2,
+    color: [f32; 4],
+}
+
+struct InstancedQuadsPlugin;
+
+impl Plugin for InstancedQuadsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ExtractComponentPlugin::<InstancedQuads>::default());
+        app.sub_app_mut(RenderApp)
+            .add_render_command::<Transparent2d, DrawInstancedQuads>()
+            .init_resource::<SpecializedRenderPipelines<InstancedQuadsPipeline>>()
+            .add_systems(
+                Render,
+                (
+                    queue_instanced_quads.in_set(RenderSet::QueueMeshes),
+                    prepare_instance_buffers.in_set(RenderSet::PrepareResources),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        app.sub_app_mut(RenderApp)
+            .init_resource::<InstancedQuadsPipeline>();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_instanced_quads(
+    transparent_2d_draw_functions: Res<DrawFunctions<Transparent2d>>,
+    instanced_quads_pipeline: Res<InstancedQuadsPipeline>,
+    msaa: Res<Msaa>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<InstancedQuadsPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    instanced_quads: Query<Entity, With<InstancedQuads>>,
+    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
+    views: Query<(Entity, &ExtractedView)>,
+) {
+    let draw_instanced_quads = transparent_2d_draw_functions
+        .read()
+        .id::<DrawInstancedQuads>();
+
+    for (view_entity, view) in &views {
+        let Some(transparent_phase) = transparent_render_phases.get_mut(&view_entity) else {
+            continue;
+        };
+
+        let key = Mesh2dPipelineKey::from_msaa_samples(msaa.samples())
+            | Mesh2dPipelineKey::from_hdr(view.hdr);
+        let pipeline = pipelines.specialize(&pipeline_cache, &instanced_quads_pipeline, key);
+        for entity in &instanced_quads {
+            transparent_phase.add(Transparent2d {
+                entity,
+                pipeline,
+                draw_function: draw_instanced_quads,
+                sort_key: FloatOrd(0.0),
+                extracted_index: entity.index(),
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::NONE,
+            });
+        }
+    }
+}
+
+#[derive(Component)]
+struct InstanceBuffer {
+    buffer: Buffer,
+    length: usize,
+}
+
+fn prepare_instance_buffers(
+    mut commands: Commands,
+    query: Query<(Entity, &InstancedQuads)>,
+    render_device: Res<RenderDevice>,
+) {
+    for (entity, instanced_quads) in &query {
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("quad instance buffer"),
+            contents: bytemuck::cast_slice(instanced_quads.as_slice()),
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        });
+        commands.entity(entity).insert(InstanceBuffer {
+            buffer,
+            length: instanced_quads.len(),
+        });
+    }
+}
+
+#[derive(Resource)]
+struct InstancedQuadsPipeline {
+    shader: Handle<Shader>,
+    mesh2d_pipeline: Mesh2dPipeline,
+    /// The corners of the quad shared by all instances.
+    vertex_buffer: Buffer,
+}
+
+impl FromWorld for InstancedQuadsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let vertex_buffer =
+            world
+                .resource::<RenderDevice>()
+                .create_buffer_with_data(&BufferInitDescriptor {
+                    label: Some("quad vertex buffer"),
+                    contents: bytemuck::cast_slice(&QUAD_CORNERS),
+                    usage: BufferUsages::VERTEX,
+                });
+
+        InstancedQuadsPipeline {
+            shader: world.load_asset(SHADER_ASSET_PATH),
+            mesh2d_pipeline: world.resource::<Mesh2dPipeline>().clone(),
+            vertex_buffer,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for InstancedQuadsPipeline {
+    type Key = Mesh2dPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let format = match key.contains(Mesh2dPipelineKey::HDR) {
+            true => ViewTarget::TEXTURE_FORMAT_HDR,
+            false => TextureFormat::bevy_default(),
+        };
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: self.shader.clone(),
+                entry_point: "vertex".into(),
+                shader_defs: vec![],
+                buffers: vec![
+                    // The corner of the quad, stepped once per vertex.
+                    VertexBufferLayout::from_vertex_formats(
+                        VertexStepMode::Vertex,
+                        [VertexFormat::Float32x2],
+                    ),
+                    // The position, size and color of each quad, stepped once per instance.
+                    VertexBufferLayout::from_vertex_formats(
+                        VertexStepMode::Instance,
+                        [VertexFormat::Float32x4, VertexFormat::Float32x4],
+                    )
+                    // shader location 0 is taken up by the corner attribute
+                    .offset_locations_by(1),
+                ],
+            },
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            // Bind group 0 is the view uniform
+            layout: vec![self.mesh2d_pipeline.view_layout.clone()],
+            push_constant_ranges: vec![],
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleStrip,
+                ..default()
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples(),
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("instanced_quads_pipeline".into()),
+            constants: Vec::new(),
+            vertex_compilation_options: None,
+            fragment_compilation_options: None,
+        }
+    }
+}
+
+type DrawInstancedQuads = (
+    SetItemPipeline,
+    SetMesh2dViewBindGroup<0>,
+    DrawQuadsInstanced,
+);
+
+struct DrawQuadsInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawQuadsInstanced {
+    type Param = SRes<InstancedQuadsPipeline>;
+    type ViewQuery = ();
+    type ItemQuery = Read<InstanceBuffer>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        instance_buffer: Option<&'w InstanceBuffer>,
+        instanced_quads_pipeline: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(instance_buffer) = instance_buffer else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_vertex_buffer(
+            0,
+            instanced_quads_pipeline
+                .into_inner()
+                .vertex_buffer
+                .slice(..),
+        );
+        pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+        pass.draw(
+            0..QUAD_CORNERS.len() as u32,
+            0..instance_buffer.length as u32,
+        );
+        RenderCommandResult::Success
+    }
+}